@@ -0,0 +1,67 @@
+//! Decodes arbitrary bytes, read in small chunks, checking the decoder agrees with the parser.
+//!
+//! The first byte picks the protocol, from its high bit, and the size of the chunks.
+
+#![no_main]
+
+use std::io::BufReader;
+
+use lib_resp::{Decoder, Parser, Protocol};
+use lib_resp_fuzz::{assert_same, limit_memory, parse_all};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let (&seed, data) = match data.split_first() {
+        Some(split) => split,
+
+        None => return,
+    };
+
+    let protocol = if seed & 0x80 == 0 {
+        Protocol::Resp2
+    } else {
+        Protocol::Resp3
+    };
+
+    let chunk = (seed & 0x7f) as usize + 1;
+
+    let (expected, rest) = parse_all(data, protocol);
+
+    let mut decoder = Decoder::new(BufReader::with_capacity(chunk, data));
+
+    decoder.set_protocol(protocol);
+
+    limit_memory(data.len(), || {
+        for value in &expected {
+            match decoder.decode() {
+                Ok(Some(decoded)) => assert_same(&decoded, value),
+
+                ret => panic!("Expected {:?}, decoded {:?}", value, ret),
+            }
+        }
+
+        // The parser stopped at an incomplete value, or invalid RESP
+        let invalid = match Parser::parse_protocol(rest, protocol) {
+            Err(e) => !e.is_incomplete(),
+
+            Ok(_) => false,
+        };
+
+        match decoder.decode() {
+            Ok(None) => assert!(!invalid, "Missed invalid RESP in {:?}", rest),
+
+            Err(_) => assert!(invalid, "Rejected valid RESP in {:?}", rest),
+
+            Ok(Some(value)) => panic!("Decoded {:?} from {:?}", value, rest),
+        }
+
+        // Decoding resumes after errors, but must always reach the end of the stream
+        for _ in 0..=data.len() {
+            if let Ok(None) = decoder.decode() {
+                return;
+            }
+        }
+
+        panic!("Decoder never reached the end of the stream");
+    });
+});
@@ -0,0 +1,103 @@
+//! Encodes arbitrary values, checking that they survive being parsed again.
+
+#![no_main]
+
+use lib_resp::{Protocol, Value};
+use lib_resp_fuzz::{assert_round_trip, limit_memory};
+use libfuzzer_sys::arbitrary::{Result, Unstructured};
+use libfuzzer_sys::fuzz_target;
+
+/// How deeply aggregates may be nested.
+const MAX_DEPTH: u32 = 4;
+
+fuzz_target!(|data: &[u8]| {
+    let mut u = Unstructured::new(data);
+
+    let protocol = match u.arbitrary() {
+        Ok(true) => Protocol::Resp3,
+
+        _ => Protocol::Resp2,
+    };
+
+    let value = match value(&mut u, protocol, MAX_DEPTH) {
+        Ok(value) => value,
+
+        Err(_) => return,
+    };
+
+    limit_memory(value.encode().len(), || assert_round_trip(&value, protocol));
+});
+
+fn value(u: &mut Unstructured, protocol: Protocol, depth: u32) -> Result<Value> {
+    // Scalars come before aggregates
+    let (scalars, aggregates) = match protocol {
+        Protocol::Resp2 => (4, 1),
+        Protocol::Resp3 => (10, 4),
+    };
+
+    let kind = if depth == 0 {
+        u.choose_index(scalars)?
+    } else {
+        u.choose_index(scalars + aggregates)?
+    };
+
+    Ok(match (protocol, kind) {
+        (_, 0) => Value::Int(u.arbitrary()?),
+        (_, 1) => Value::Str(line(u)?),
+        (_, 2) => Value::Err(line(u)?),
+        (_, 3) if u.arbitrary()? => Value::BStr(None),
+        (_, 3) => Value::BStr(Some(u.arbitrary()?)),
+
+        // RESP2 has a single aggregate, and nulls of its own
+        (Protocol::Resp2, 4) if u.arbitrary()? => Value::Array(None),
+        (Protocol::Resp2, 4) => Value::Array(Some(values(u, protocol, depth)?)),
+
+        (_, 4) => Value::Null,
+        (_, 5) => Value::Bool(u.arbitrary()?),
+        (_, 6) => Value::Double(u.arbitrary()?),
+        (_, 7) => Value::BigNum(format!(
+            "{}{}",
+            u.arbitrary::<i128>()?,
+            u.arbitrary::<u64>()?
+        )),
+        (_, 8) => Value::BErr(u.arbitrary()?),
+        (_, 9) => {
+            let format = (0..3)
+                .map(|_| u.choose(b"abcdefghijklmnopqrstuvwxyz").map(|&b| b as char))
+                .collect::<Result<String>>()?;
+
+            Value::Verbatim(format, u.arbitrary()?)
+        }
+        (_, 10) if u.arbitrary()? => Value::Array(None),
+        (_, 10) => Value::Array(Some(values(u, protocol, depth)?)),
+        (_, 11) => {
+            let len = u.arbitrary_len::<u8>()?.min(8);
+
+            Value::Map(
+                (0..len)
+                    .map(|_| {
+                        Ok((
+                            value(u, protocol, depth - 1)?,
+                            value(u, protocol, depth - 1)?,
+                        ))
+                    })
+                    .collect::<Result<_>>()?,
+            )
+        }
+        (_, 12) => Value::Set(values(u, protocol, depth)?),
+        _ => Value::Push(values(u, protocol, depth)?),
+    })
+}
+
+fn values(u: &mut Unstructured, protocol: Protocol, depth: u32) -> Result<Vec<Value>> {
+    let len = u.arbitrary_len::<u8>()?.min(8);
+
+    (0..len).map(|_| value(u, protocol, depth - 1)).collect()
+}
+
+/// A string which fits on a single line, for simple strings and errors.
+fn line(u: &mut Unstructured) -> Result<String> {
+    let line: String = u.arbitrary()?;
+
+    Ok(line.replace(['\r', '\n'], ""))
+}
@@ -0,0 +1,19 @@
+//! Parses arbitrary bytes, checking that anything parsed survives being encoded and parsed again.
+
+#![no_main]
+
+use lib_resp::{Parser, Protocol};
+use lib_resp_fuzz::{assert_round_trip, limit_memory};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok((_, value)) = limit_memory(data.len(), || Parser::parse(data)) {
+        assert_round_trip(&value, Protocol::Resp2);
+    }
+
+    let parsed = limit_memory(data.len(), || Parser::parse_protocol(data, Protocol::Resp3));
+
+    if let Ok((_, value)) = parsed {
+        assert_round_trip(&value, Protocol::Resp3);
+    }
+});
@@ -0,0 +1,141 @@
+//! Helpers shared by the fuzz targets.
+//!
+//! The targets are run with [cargo-fuzz](https://github.com/rust-fuzz/cargo-fuzz), e.g.
+//! `cargo +nightly fuzz run parse`. The `parse` and `decode` seed corpora are built from the
+//! vectors in `tests/`. `encode` builds values from its input rather than parsing it, so it has
+//! no seeds.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use lib_resp::{Parser, Protocol, Value};
+
+/// Memory which may be used regardless of the size of the input, e.g. for buffers.
+pub const MEMORY_BASE: usize = 64 * 1024;
+
+/// Memory which may be used for each byte of input, `*1\r\n` is only 4 bytes but becomes a
+/// `Vec<Value>`.
+pub const MEMORY_PER_BYTE: usize = 64;
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// Tracks the peak amount of memory allocated.
+struct Counting;
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        grow(layout.size());
+
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        grow(layout.size());
+
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        // The old and new blocks may both be live while copying
+        grow(new_size);
+
+        LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+fn grow(size: usize) {
+    let live = LIVE.fetch_add(size, Ordering::Relaxed) + size;
+
+    PEAK.fetch_max(live, Ordering::Relaxed);
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// Runs `f`, panicking if it uses more memory than allowed for an input of `len` bytes.
+pub fn limit_memory<T, F: FnOnce() -> T>(len: usize, f: F) -> T {
+    let start = LIVE.load(Ordering::Relaxed);
+
+    PEAK.store(start, Ordering::Relaxed);
+
+    let ret = f();
+
+    let used = PEAK.load(Ordering::Relaxed) - start;
+    let limit = MEMORY_BASE + MEMORY_PER_BYTE * len;
+
+    assert!(
+        used <= limit,
+        "Used {} bytes for {} bytes of input, the limit is {}",
+        used,
+        len,
+        limit
+    );
+
+    ret
+}
+
+/// Parses every value in `data`, stopping at the first error or incomplete value.
+pub fn parse_all(mut data: &[u8], protocol: Protocol) -> (Vec<Value>, &[u8]) {
+    let mut values = Vec::new();
+
+    while let Ok((rest, value)) = Parser::parse_protocol(data, protocol) {
+        values.push(value);
+
+        data = rest;
+    }
+
+    (values, data)
+}
+
+/// Checks that `value` survives being encoded then parsed.
+pub fn assert_round_trip(value: &Value, protocol: Protocol) {
+    let encoded = value.encode_bytes();
+
+    let parsed = match Parser::parse_protocol(&encoded, protocol) {
+        Ok((rest, parsed)) => {
+            assert!(rest.is_empty(), "Trailing data after {:?}", value);
+
+            parsed
+        }
+
+        Err(e) => panic!(
+            "Failed to parse {:?} encoded as {:?}: {:?}",
+            value, encoded, e
+        ),
+    };
+
+    assert_same(&parsed, value);
+}
+
+/// Checks that two values are equal, treating all NaNs as equal.
+pub fn assert_same(left: &Value, right: &Value) {
+    // NaN payloads are lost when encoding, so compare the encodings instead
+    if has_nan(left) || has_nan(right) {
+        assert_eq!(left.encode_bytes(), right.encode_bytes());
+    } else {
+        assert_eq!(left, right);
+    }
+}
+
+fn has_nan(value: &Value) -> bool {
+    match *value {
+        Value::Double(datum) => datum.is_nan(),
+
+        Value::Array(Some(ref data)) | Value::Set(ref data) | Value::Push(ref data) => {
+            data.iter().any(has_nan)
+        }
+
+        Value::Map(ref pairs) => pairs.iter().any(|(k, v)| has_nan(k) || has_nan(v)),
+
+        _ => false,
+    }
+}
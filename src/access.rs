@@ -0,0 +1,230 @@
+use std::ops::Index;
+use std::slice::Iter;
+
+use super::Value;
+
+impl Value {
+    /// Returns the integer, if the `Value` is an integer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lib_resp::Value;
+    /// assert_eq!(Value::int(42).as_int(), Some(42));
+    ///
+    /// assert_eq!(Value::b_str(Some("42")).as_int(), None);
+    /// ```
+    pub fn as_int(&self) -> Option<i64> {
+        match *self {
+            Value::Int(datum) => Some(datum),
+
+            _ => None,
+        }
+    }
+
+    /// Returns the string, if the `Value` is a simple, bulk or verbatim string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lib_resp::Value;
+    /// assert_eq!(Value::str("OK").as_str(), Some("OK"));
+    /// assert_eq!(Value::b_str(Some("foo")).as_str(), Some("foo"));
+    ///
+    /// assert_eq!(Value::err("ERR").as_str(), None);
+    /// ```
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            Value::Str(ref datum)
+            | Value::BStr(Some(ref datum))
+            | Value::Verbatim(_, ref datum) => Some(datum),
+
+            _ => None,
+        }
+    }
+
+    /// Returns the bytes of the string, if the `Value` is a simple, bulk or verbatim string.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        self.as_str().map(str::as_bytes)
+    }
+
+    /// Returns the items, if the `Value` is an array, set or push.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lib_resp::Value;
+    /// let users = Value::array(Some(vec![Value::b_str(Some("foo"))]));
+    ///
+    /// assert_eq!(users.as_array(), Some(&[Value::b_str(Some("foo"))][..]));
+    ///
+    /// assert_eq!(Value::Array(None).as_array(), None);
+    /// ```
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match *self {
+            Value::Array(Some(ref values)) | Value::Set(ref values) | Value::Push(ref values) => {
+                Some(values)
+            }
+
+            _ => None,
+        }
+    }
+
+    /// Returns the items, if the `Value` is an array, set or push, consuming it.
+    pub fn into_array(self) -> Option<Vec<Value>> {
+        match self {
+            Value::Array(Some(values)) | Value::Set(values) | Value::Push(values) => Some(values),
+
+            _ => None,
+        }
+    }
+
+    /// Returns the key-value pairs, if the `Value` is a map.
+    pub fn as_map(&self) -> Option<&[(Value, Value)]> {
+        match *self {
+            Value::Map(ref pairs) => Some(pairs),
+
+            _ => None,
+        }
+    }
+
+    /// Checks if a `Value` is an error, or a blob error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lib_resp::Value;
+    /// assert!(Value::err("ERR").is_error());
+    ///
+    /// assert!(!Value::str("OK").is_error());
+    /// ```
+    pub fn is_error(&self) -> bool {
+        matches!(*self, Value::Err(_) | Value::BErr(_))
+    }
+
+    /// Returns the item at `index`, if the `Value` is an array, set or push and it's in bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lib_resp::Value;
+    /// let users = Value::array(Some(vec![Value::b_str(Some("foo"))]));
+    ///
+    /// assert_eq!(users.get(0), Some(&Value::b_str(Some("foo"))));
+    /// assert_eq!(users.get(1), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&Value> {
+        self.as_array().and_then(|values| values.get(index))
+    }
+
+    /// Returns the number of items in an array, set or push, the number of pairs in a map, or the
+    /// length of a string in bytes.
+    ///
+    /// NOTE: Nulls have a length of `0`, and the other scalars (e.g. integers) a length of `1`,
+    /// so that `len() == 0` agrees with [is_empty](Value::is_empty).
+    pub fn len(&self) -> usize {
+        match *self {
+            Value::Str(ref datum)
+            | Value::Err(ref datum)
+            | Value::BStr(Some(ref datum))
+            | Value::BigNum(ref datum)
+            | Value::BErr(ref datum)
+            | Value::Verbatim(_, ref datum) => datum.len(),
+
+            Value::Array(Some(ref values)) | Value::Set(ref values) | Value::Push(ref values) => {
+                values.len()
+            }
+
+            Value::Map(ref pairs) => pairs.len(),
+
+            Value::BStr(None) | Value::Array(None) | Value::Null => 0,
+
+            Value::Int(_) | Value::Bool(_) | Value::Double(_) => 1,
+        }
+    }
+
+    /// Iterates over the items of an array, set or push. Other values have no items.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lib_resp::Value;
+    /// let values = Value::array(Some(vec![Value::int(1), Value::int(2)]));
+    ///
+    /// assert_eq!(values.iter().filter_map(Value::as_int).sum::<i64>(), 3);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, Value> {
+        self.as_array().unwrap_or(&[]).iter()
+    }
+
+    /// Looks up a value by a [JSON pointer](https://tools.ietf.org/html/rfc6901) like path, e.g.
+    /// `/2/0` for the first item of the third item.
+    ///
+    /// Arrays, sets and pushes are indexed by position, and maps by keys which are strings or
+    /// integers. An empty path refers to the value itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lib_resp::Value;
+    /// let value = Value::array(Some(vec![
+    ///     Value::int(1),
+    ///     Value::Map(vec![(Value::str("a/b"), Value::array(Some(vec![Value::int(2)])))]),
+    /// ]));
+    ///
+    /// assert_eq!(value.pointer("/1/a~1b/0"), Some(&Value::int(2)));
+    /// assert_eq!(value.pointer("/2"), None);
+    /// ```
+    pub fn pointer(&self, pointer: &str) -> Option<&Value> {
+        if pointer.is_empty() {
+            return Some(self);
+        }
+
+        if !pointer.starts_with('/') {
+            return None;
+        }
+
+        pointer[1..].split('/').try_fold(self, |value, token| {
+            let token = token.replace("~1", "/").replace("~0", "~");
+
+            match *value {
+                Value::Map(ref pairs) => pairs
+                    .iter()
+                    .find(|&(key, _)| match *key {
+                        Value::Int(datum) => datum.to_string() == token,
+
+                        ref key => key.as_str() == Some(&token),
+                    })
+                    .map(|(_, value)| value),
+
+                ref value => value.get(token.parse().ok()?),
+            }
+        })
+    }
+}
+
+/// Indexes the items of an array, set or push.
+///
+/// # Panics
+///
+/// Panics if the `Value` isn't an array, set or push, or if `index` is out of bounds.
+impl Index<usize> for Value {
+    type Output = Value;
+
+    fn index(&self, index: usize) -> &Value {
+        match self.as_array() {
+            Some(values) => &values[index],
+
+            None => panic!("Can't index into {:?}", self.kind()),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Value {
+    type Item = &'a Value;
+    type IntoIter = Iter<'a, Value>;
+
+    fn into_iter(self) -> Iter<'a, Value> {
+        self.iter()
+    }
+}
@@ -0,0 +1,405 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Error, ErrorKind, Result, Write};
+use std::path::{Path, PathBuf};
+
+use super::{Decoder, Value};
+
+/// Iterator over the commands in an append-only file.
+///
+/// A command cut off by the end of the file, e.g. because the server crashed while writing it, is
+/// not an error, instead iteration stops and its offset is made available through
+/// [truncated](AofReader::truncated).
+///
+/// An RDB preamble, as written when `aof-use-rdb-preamble` is enabled, is skipped, as are the
+/// contents of an RDB base file of a multi-part AOF. Only the commands after it are read.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use lib_resp::AofReader;
+/// let mut reader = AofReader::open("appendonly.aof").unwrap();
+///
+/// for command in &mut reader {
+///     println!("{}", command.unwrap());
+/// }
+///
+/// if let Some(offset) = reader.truncated() {
+///     println!("AOF is truncated, valid up to offset {}", offset);
+/// }
+/// ```
+pub struct AofReader<R: BufRead> {
+    decoder: Decoder<R>,
+    // Bytes of annotations and the RDB preamble, which aren't seen by the decoder
+    skipped: u64,
+    truncated: Option<u64>,
+    done: bool,
+}
+
+impl AofReader<BufReader<File>> {
+    /// Opens an append-only file for reading.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(AofReader::new(BufReader::new(File::open(path)?)))
+    }
+}
+
+impl<R: BufRead> AofReader<R> {
+    pub fn new(src: R) -> Self {
+        AofReader {
+            decoder: Decoder::new(src),
+            skipped: 0,
+            truncated: None,
+            done: false,
+        }
+    }
+
+    /// Returns the offset of the end of the last command read.
+    pub fn offset(&self) -> u64 {
+        self.decoder.offset() + self.skipped
+    }
+
+    /// Returns the offset of a truncated command at the end of the file, once it's been reached.
+    ///
+    /// Everything before the offset is valid, so the file can be repaired by truncating it there.
+    pub fn truncated(&self) -> Option<u64> {
+        self.truncated
+    }
+
+    /// Reads the next command, returning `None` at the end of the file.
+    pub fn read_command(&mut self) -> Result<Option<Value>> {
+        if self.done {
+            return Ok(None);
+        }
+
+        let ret = self.read();
+
+        match ret {
+            Ok(Some(_)) => {}
+
+            _ => self.done = true,
+        }
+
+        ret
+    }
+
+    fn read(&mut self) -> Result<Option<Value>> {
+        // Only the RDB magic string, `REDIS`, starts with `R`
+        if self.offset() == 0 && self.decoder.get_mut().fill_buf()?.starts_with(b"R") {
+            self.skipped += ::rdb::skip_rdb(self.decoder.get_mut())?;
+        }
+
+        self.skip_annotations()?;
+
+        let offset = self.offset();
+
+        match self.decoder.decode() {
+            Ok(Some(ref command)) if is_command(command) => Ok(Some(command.clone())),
+
+            Ok(None) => {
+                if !self.decoder.partial().is_empty() {
+                    self.truncated = Some(offset);
+                }
+
+                Ok(None)
+            }
+
+            _ => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Bad file format reading the append only file at offset {}",
+                    offset
+                ),
+            )),
+        }
+    }
+
+    /// Skips annotation lines, e.g. `#TS:1628217470`.
+    fn skip_annotations(&mut self) -> Result<()> {
+        // Annotations can't be mistaken for part of a command, they only appear between them
+        while self.decoder.get_mut().fill_buf()?.starts_with(b"#") {
+            let mut line = Vec::new();
+
+            self.decoder.get_mut().read_until(b'\n', &mut line)?;
+
+            self.skipped += line.len() as u64;
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Iterator for AofReader<R> {
+    type Item = Result<Value>;
+
+    fn next(&mut self) -> Option<Result<Value>> {
+        match self.read_command() {
+            Ok(Some(command)) => Some(Ok(command)),
+
+            Ok(None) => None,
+
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// Writer for append-only files.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use lib_resp::AofWriter;
+/// let mut writer = AofWriter::append("appendonly.aof").unwrap();
+///
+/// writer.command(&["SET", "foo", "bar"]).unwrap();
+/// writer.flush().unwrap();
+/// ```
+pub struct AofWriter<W: Write> {
+    dst: W,
+}
+
+impl AofWriter<BufWriter<File>> {
+    /// Creates an append-only file, truncating it if it already exists.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(AofWriter::new(BufWriter::new(File::create(path)?)))
+    }
+
+    /// Opens an append-only file for appending, creating it if it doesn't exist.
+    pub fn append<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = OpenOptions::new().append(true).create(true).open(path)?;
+
+        Ok(AofWriter::new(BufWriter::new(file)))
+    }
+}
+
+impl<W: Write> AofWriter<W> {
+    pub fn new(dst: W) -> Self {
+        AofWriter { dst }
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.dst
+    }
+
+    /// Unwraps the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.dst
+    }
+
+    /// Writes a command, which must be a non-empty array of bulk strings.
+    pub fn write(&mut self, command: &Value) -> Result<()> {
+        if !is_command(command) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Commands must be non-empty arrays of bulk strings",
+            ));
+        }
+
+        self.dst.write_all(&command.encode_bytes())
+    }
+
+    /// Writes a command built from its arguments.
+    pub fn command<T: ToString>(&mut self, args: &[T]) -> Result<()> {
+        self.write(&::client::command(args))
+    }
+
+    /// Flushes buffered commands to the underlying writer.
+    pub fn flush(&mut self) -> Result<()> {
+        self.dst.flush()
+    }
+}
+
+/// Replaces an append-only file with the given commands.
+///
+/// The commands are written to a temporary file next to `path`, which is then renamed over it, so
+/// the file is never left partially written.
+pub fn rewrite_aof<P, I>(path: P, commands: I) -> Result<()>
+where
+    P: AsRef<Path>,
+    I: IntoIterator<Item = Value>,
+{
+    let path = path.as_ref();
+
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+
+    if let Err(e) = write_file(&tmp, commands) {
+        let _ = fs::remove_file(&tmp);
+
+        return Err(e);
+    }
+
+    fs::rename(&tmp, path)
+}
+
+fn write_file<I: IntoIterator<Item = Value>>(path: &Path, commands: I) -> Result<()> {
+    let mut writer = AofWriter::create(path)?;
+
+    for command in commands {
+        writer.write(&command)?;
+    }
+
+    writer.flush()?;
+
+    writer.into_inner().get_ref().sync_all()
+}
+
+/// Repairs an append-only file with a truncated command at the end, by removing it.
+///
+/// Returns the offset the file was truncated at, or `None` if it didn't need repairing. Other
+/// errors in the file are returned rather than repaired.
+pub fn repair_aof<P: AsRef<Path>>(path: P) -> Result<Option<u64>> {
+    let mut reader = AofReader::open(&path)?;
+
+    while reader.read_command()?.is_some() {}
+
+    if let Some(offset) = reader.truncated() {
+        OpenOptions::new()
+            .write(true)
+            .open(&path)?
+            .set_len(offset)?;
+    }
+
+    Ok(reader.truncated())
+}
+
+/// The type of a file in a multi-part AOF.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum AofFileType {
+    /// A base file, written by a rewrite. Either an RDB or an AOF file.
+    Base,
+    /// An incremental file, appended to after the base.
+    Incr,
+    /// A file from before the last rewrite, waiting to be deleted.
+    History,
+}
+
+/// A file listed in a multi-part AOF manifest.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ManifestEntry {
+    /// The name of the file, relative to the directory of the manifest.
+    pub file: String,
+    /// The sequence number of the file.
+    pub seq: u64,
+    /// The type of the file.
+    pub file_type: AofFileType,
+}
+
+/// A multi-part AOF manifest, e.g. `appendonly.aof.manifest`.
+///
+/// # Examples
+///
+/// ```
+/// # use lib_resp::Manifest;
+/// let manifest = Manifest::parse(
+///     "file appendonly.aof.1.base.rdb seq 1 type b\n\
+///      file appendonly.aof.1.incr.aof seq 1 type i\n",
+/// )
+/// .unwrap();
+///
+/// let files: Vec<&str> = manifest.files().map(|entry| &entry.file[..]).collect();
+///
+/// assert_eq!(files, vec!["appendonly.aof.1.base.rdb", "appendonly.aof.1.incr.aof"]);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Manifest {
+    /// The files, in the order they're listed.
+    pub entries: Vec<ManifestEntry>,
+}
+
+impl Manifest {
+    /// Reads a manifest file.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Manifest::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Parses the text of a manifest.
+    ///
+    /// NOTE: Quoted file names aren't supported.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut entries = Vec::new();
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = || {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Invalid AOF manifest at line {}", number + 1),
+                )
+            };
+
+            let parts: Vec<&str> = line.split_whitespace().collect();
+
+            if parts.len() % 2 != 0 {
+                return Err(invalid());
+            }
+
+            let (mut file, mut seq, mut file_type) = (None, None, None);
+
+            for pair in parts.chunks(2) {
+                match pair[0] {
+                    "file" => file = Some(pair[1].to_owned()),
+
+                    "seq" => seq = Some(pair[1].parse().map_err(|_| invalid())?),
+
+                    "type" => {
+                        file_type = Some(match pair[1] {
+                            "b" => AofFileType::Base,
+                            "i" => AofFileType::Incr,
+                            "h" => AofFileType::History,
+
+                            _ => return Err(invalid()),
+                        })
+                    }
+
+                    // Newer versions may add keys
+                    _ => {}
+                }
+            }
+
+            match (file, seq, file_type) {
+                (Some(file), Some(seq), Some(file_type)) => entries.push(ManifestEntry {
+                    file,
+                    seq,
+                    file_type,
+                }),
+
+                _ => return Err(invalid()),
+            }
+        }
+
+        Ok(Manifest { entries })
+    }
+
+    /// Returns the files making up the AOF in the order they should be read, i.e. the base,
+    /// followed by the incremental files by sequence number. History files are skipped.
+    pub fn files(&self) -> impl Iterator<Item = &ManifestEntry> {
+        let mut incr: Vec<&ManifestEntry> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.file_type == AofFileType::Incr)
+            .collect();
+
+        incr.sort_by_key(|entry| entry.seq);
+
+        self.entries
+            .iter()
+            .filter(|entry| entry.file_type == AofFileType::Base)
+            .chain(incr)
+    }
+}
+
+fn is_command(value: &Value) -> bool {
+    match *value {
+        Value::Array(Some(ref args)) => {
+            !args.is_empty() && args.iter().all(|arg| matches!(*arg, Value::BStr(Some(_))))
+        }
+
+        _ => false,
+    }
+}
@@ -0,0 +1,110 @@
+/// Splits a line into arguments, using the same quoting rules as `redis-cli`.
+///
+/// Arguments are separated by whitespace, and may be quoted. Double quoted arguments support the
+/// escapes `\n`, `\r`, `\t`, `\b`, `\a`, `\\`, `\"` and `\xHH`, single quoted arguments only
+/// support `\'`. Returns `None` if a quote isn't closed, or is followed by something other than
+/// whitespace.
+///
+/// # Examples
+///
+/// ```
+/// # use lib_resp::split_args;
+/// let args = split_args(r#"SET "greeting" 'hello world' "\x00""#).unwrap();
+///
+/// assert_eq!(
+///     args,
+///     vec![b"SET".to_vec(), b"greeting".to_vec(), b"hello world".to_vec(), b"\0".to_vec()]
+/// );
+///
+/// assert_eq!(split_args(r#"SET "unclosed"#), None);
+/// ```
+pub fn split_args(line: &str) -> Option<Vec<Vec<u8>>> {
+    let mut args = Vec::new();
+    let mut bytes = line.bytes().peekable();
+
+    loop {
+        while matches!(bytes.peek(), Some(b) if b.is_ascii_whitespace()) {
+            bytes.next();
+        }
+
+        if bytes.peek().is_none() {
+            return Some(args);
+        }
+
+        let mut arg = Vec::new();
+        let mut quote = None;
+
+        loop {
+            match quote {
+                Some(b'"') => match bytes.next()? {
+                    b'\\' => {
+                        let mut ahead = bytes.clone();
+
+                        let hex = match (ahead.next(), ahead.next(), ahead.next()) {
+                            (Some(b'x'), Some(high), Some(low)) => hex_byte(high, low),
+
+                            _ => None,
+                        };
+
+                        match hex {
+                            Some(byte) => {
+                                arg.push(byte);
+
+                                bytes = ahead;
+                            }
+
+                            None => arg.push(match bytes.next()? {
+                                b'n' => b'\n',
+                                b'r' => b'\r',
+                                b't' => b'\t',
+                                b'b' => 0x08,
+                                b'a' => 0x07,
+
+                                byte => byte,
+                            }),
+                        }
+                    }
+
+                    b'"' => break,
+
+                    byte => arg.push(byte),
+                },
+
+                Some(_) => match bytes.next()? {
+                    b'\\' if bytes.peek() == Some(&b'\'') => arg.push(bytes.next()?),
+
+                    b'\'' => break,
+
+                    byte => arg.push(byte),
+                },
+
+                None => match bytes.peek() {
+                    Some(&byte) if !byte.is_ascii_whitespace() => {
+                        if byte == b'"' || byte == b'\'' {
+                            quote = Some(byte);
+                        } else {
+                            arg.push(byte);
+                        }
+
+                        bytes.next();
+                    }
+
+                    _ => break,
+                },
+            }
+        }
+
+        // Closing quotes must be followed by whitespace
+        if quote.is_some() && matches!(bytes.peek(), Some(b) if !b.is_ascii_whitespace()) {
+            return None;
+        }
+
+        args.push(arg);
+    }
+}
+
+fn hex_byte(high: u8, low: u8) -> Option<u8> {
+    let digit = |b: u8| (b as char).to_digit(16);
+
+    Some((digit(high)? * 16 + digit(low)?) as u8)
+}
@@ -0,0 +1,193 @@
+//! Pretty-prints a stream of RESP values.
+
+extern crate lib_resp;
+
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::process;
+
+use lib_resp::{Decoder, Protocol, Value};
+
+const USAGE: &str = "Usage: resp-cat [OPTIONS] [FILE]
+
+Reads RESP values from FILE, or stdin if FILE is missing or `-`, and prints them.
+
+Options:
+    -f, --format FORMAT   Print values as `display` (like redis-cli, the default), `debug` or
+                          `json`
+    -o, --offsets         Print the byte offset and size of each value
+    -k, --keep-going      Skip invalid values instead of stopping
+    -3, --resp3           Decode RESP3 instead of RESP2
+    -h, --help            Print this message";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Display,
+    Debug,
+    Json,
+}
+
+struct Options {
+    format: Format,
+    offsets: bool,
+    keep_going: bool,
+    protocol: Protocol,
+    path: Option<String>,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        format: Format::Display,
+        offsets: false,
+        keep_going: false,
+        protocol: Protocol::Resp2,
+        path: None,
+    };
+
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-f" | "--format" => {
+                options.format = match args.next().as_ref().map(|format| &format[..]) {
+                    Some("display") => Format::Display,
+                    Some("debug") => Format::Debug,
+                    Some("json") => Format::Json,
+
+                    _ => return Err("--format must be one of display, debug or json".to_owned()),
+                }
+            }
+
+            "-o" | "--offsets" => options.offsets = true,
+
+            "-k" | "--keep-going" => options.keep_going = true,
+
+            "-3" | "--resp3" => options.protocol = Protocol::Resp3,
+
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+
+                process::exit(0);
+            }
+
+            "-" => options.path = None,
+
+            _ if arg.starts_with('-') => return Err(format!("Unknown option: {}", arg)),
+
+            _ if options.path.is_some() => return Err("Only one FILE may be given".to_owned()),
+
+            _ => options.path = Some(arg),
+        }
+    }
+
+    Ok(options)
+}
+
+fn print(
+    out: &mut dyn Write,
+    options: &Options,
+    value: &Value,
+    offset: u64,
+    size: u64,
+) -> io::Result<()> {
+    match options.format {
+        Format::Json if options.offsets => writeln!(
+            out,
+            r#"{{"offset":{},"size":{},"value":{}}}"#,
+            offset,
+            size,
+            value.to_json()
+        ),
+
+        Format::Json => writeln!(out, "{}", value.to_json()),
+
+        format => {
+            if options.offsets {
+                writeln!(out, "@{} ({} bytes)", offset, size)?;
+            }
+
+            if format == Format::Debug {
+                writeln!(out, "{:?}", value)
+            } else {
+                writeln!(out, "{}", value)
+            }
+        }
+    }
+}
+
+/// Prints every value in `src`, returning whether it was entirely valid.
+fn cat<R: BufRead>(src: R, options: &Options) -> io::Result<bool> {
+    let mut decoder = Decoder::new(src);
+    decoder.set_protocol(options.protocol);
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+
+    let mut valid = true;
+
+    loop {
+        let offset = decoder.offset();
+
+        match decoder.decode() {
+            Ok(Some(value)) => print(&mut out, options, &value, offset, decoder.offset() - offset)?,
+
+            Ok(None) => {
+                if !decoder.partial().is_empty() {
+                    eprintln!("resp-cat: truncated value at offset {}", offset);
+
+                    valid = false;
+                }
+
+                return Ok(valid);
+            }
+
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidData => {
+                out.flush()?;
+
+                eprintln!("resp-cat: invalid RESP at offset {}", offset);
+
+                valid = false;
+
+                if !options.keep_going {
+                    return Ok(valid);
+                }
+            }
+
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn main() {
+    let options = match parse_args() {
+        Ok(options) => options,
+
+        Err(e) => {
+            eprintln!("resp-cat: {}\n\n{}", e, USAGE);
+
+            process::exit(2);
+        }
+    };
+
+    let ret = match options.path {
+        Some(ref path) => File::open(path).and_then(|file| cat(BufReader::new(file), &options)),
+
+        None => cat(io::stdin().lock(), &options),
+    };
+
+    match ret {
+        Ok(true) => {}
+
+        Ok(false) => process::exit(1),
+
+        // Stop quietly when piped into e.g. `head`
+        Err(ref e) if e.kind() == io::ErrorKind::BrokenPipe => {}
+
+        Err(e) => {
+            eprintln!("resp-cat: {}", e);
+
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,429 @@
+//! Interactive client, in the style of `redis-cli`.
+
+extern crate lib_resp;
+extern crate rustyline;
+
+use std::env;
+use std::io::{self, BufRead, BufReader, IsTerminal, Read, Write};
+use std::net::TcpStream;
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::process;
+use std::str;
+
+use lib_resp::{split_args, Decoder, Handshake, HandshakeOptions, PrettyOptions, Protocol, Value};
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+
+const USAGE: &str = "Usage: resp-cli [OPTIONS] [COMMAND [ARG...]]
+
+Sends COMMAND to the server and prints the reply, or starts an interactive session if no COMMAND
+is given. Commands are read from stdin, one per line, if it isn't a terminal.
+
+Options:
+    -h HOST       Server hostname (default: 127.0.0.1)
+    -p PORT       Server port (default: 6379)
+    -s SOCKET     Server unix socket, overrides HOST and PORT
+    -a PASSWORD   Password to authenticate with
+    --user USER   User to authenticate as
+    -n DB         Database number
+    -3            Use RESP3, replies are shown with their RESP3 types
+    --raw         Print replies without types or quotes
+    --help        Print this message";
+
+struct Options {
+    host: String,
+    port: u16,
+    socket: Option<String>,
+    password: Option<String>,
+    user: Option<String>,
+    db: i64,
+    resp3: bool,
+    raw: bool,
+    command: Vec<String>,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        host: "127.0.0.1".to_owned(),
+        port: 6379,
+        socket: None,
+        password: None,
+        user: None,
+        db: 0,
+        resp3: false,
+        raw: false,
+        command: Vec::new(),
+    };
+
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| format!("{} requires a value", name))
+        };
+
+        match arg.as_str() {
+            "-h" => options.host = value("-h")?,
+
+            "-p" => options.port = value("-p")?.parse().map_err(|_| "Invalid port")?,
+
+            "-s" => options.socket = Some(value("-s")?),
+
+            "-a" => options.password = Some(value("-a")?),
+
+            "--user" => options.user = Some(value("--user")?),
+
+            "-n" => options.db = value("-n")?.parse().map_err(|_| "Invalid database")?,
+
+            "-3" => options.resp3 = true,
+
+            "--raw" => options.raw = true,
+
+            "--help" => {
+                println!("{}", USAGE);
+
+                process::exit(0);
+            }
+
+            _ if arg.starts_with('-') => return Err(format!("Unknown option: {}", arg)),
+
+            _ => {
+                options.command.push(arg);
+                options.command.extend(args);
+
+                break;
+            }
+        }
+    }
+
+    Ok(options)
+}
+
+struct Connection {
+    decoder: Decoder<BufReader<Box<dyn Read>>>,
+    writer: Box<dyn Write>,
+}
+
+impl Connection {
+    fn open(options: &Options) -> io::Result<Self> {
+        let (reader, writer): (Box<dyn Read>, Box<dyn Write>) = match options.socket {
+            #[cfg(unix)]
+            Some(ref path) => {
+                let stream = UnixStream::connect(path)?;
+
+                (Box::new(stream.try_clone()?), Box::new(stream))
+            }
+
+            #[cfg(not(unix))]
+            Some(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "Unix sockets aren't supported on this platform",
+                ))
+            }
+
+            None => {
+                let stream = TcpStream::connect((&options.host[..], options.port))?;
+
+                (Box::new(stream.try_clone()?), Box::new(stream))
+            }
+        };
+
+        Ok(Connection {
+            decoder: Decoder::new(BufReader::new(reader)),
+            writer,
+        })
+    }
+
+    /// Sends a command, arguments are sent as is, even if they aren't valid UTF-8.
+    fn send(&mut self, args: &[Vec<u8>]) -> io::Result<()> {
+        let mut command = format!("*{}\r\n", args.len()).into_bytes();
+
+        for arg in args {
+            command.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+            command.extend_from_slice(arg);
+            command.extend_from_slice(b"\r\n");
+        }
+
+        self.writer.write_all(&command)
+    }
+
+    fn recv(&mut self) -> io::Result<Value> {
+        match self.decoder.decode()? {
+            Some(value) => Ok(value),
+
+            None => Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "Connection closed by server",
+            )),
+        }
+    }
+
+    fn command(&mut self, args: &[Vec<u8>]) -> io::Result<Value> {
+        self.send(args)?;
+
+        self.recv()
+    }
+}
+
+impl Handshake for Connection {
+    fn call(&mut self, value: &Value) -> io::Result<Value> {
+        self.writer.write_all(&value.encode_bytes())?;
+
+        self.recv()
+    }
+
+    fn protocol(&self) -> Protocol {
+        self.decoder.protocol()
+    }
+
+    fn set_protocol(&mut self, protocol: Protocol) {
+        self.decoder.set_protocol(protocol);
+    }
+}
+
+fn print(value: &Value, options: &Options) {
+    let pretty = PrettyOptions {
+        raw: options.raw,
+        ..PrettyOptions::default()
+    };
+
+    println!("{}", value.pretty(&pretty));
+}
+
+fn connect(options: &Options) -> Result<Connection, String> {
+    let mut connection = Connection::open(options).map_err(|e| match options.socket {
+        Some(ref path) => format!("Could not connect to {}: {}", path, e),
+
+        None => format!(
+            "Could not connect to {}:{}: {}",
+            options.host, options.port, e
+        ),
+    })?;
+
+    // Only RESP3, or the details it needs, are worth the extra round trip
+    if options.resp3 || options.password.is_some() || options.db != 0 {
+        let handshake = HandshakeOptions {
+            protocol: if options.resp3 {
+                Protocol::Resp3
+            } else {
+                Protocol::Resp2
+            },
+            username: options.user.clone(),
+            password: options.password.clone(),
+            client_name: None,
+            db: if options.db != 0 {
+                Some(options.db)
+            } else {
+                None
+            },
+        };
+
+        connection
+            .handshake(&handshake)
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(connection)
+}
+
+/// Commands after which the server keeps sending values.
+fn is_streaming(command: &[u8]) -> bool {
+    ["subscribe", "psubscribe", "ssubscribe", "monitor"]
+        .iter()
+        .any(|streaming| command.eq_ignore_ascii_case(streaming.as_bytes()))
+}
+
+/// Runs a command, returning `false` if the connection failed.
+fn run(connection: &mut Connection, args: &[Vec<u8>], options: &Options, db: &mut i64) -> bool {
+    let reply = match connection.command(args) {
+        Ok(reply) => reply,
+
+        Err(e) => {
+            eprintln!("Error: {}", e);
+
+            return false;
+        }
+    };
+
+    print(&reply, options);
+
+    if args[0].eq_ignore_ascii_case(b"select") && reply.server_error().is_none() {
+        let selected = args
+            .get(1)
+            .and_then(|db| str::from_utf8(db).ok())
+            .and_then(|db| db.parse().ok());
+
+        if let Some(selected) = selected {
+            *db = selected;
+        }
+    }
+
+    if is_streaming(&args[0]) && reply.server_error().is_none() {
+        // Only ends when the connection does
+        loop {
+            match connection.recv() {
+                Ok(value) => print(&value, options),
+
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+
+                    return false;
+                }
+            }
+        }
+    }
+
+    true
+}
+
+fn history_path() -> Option<PathBuf> {
+    env::var_os("HOME").map(|home| PathBuf::from(home).join(".resp_cli_history"))
+}
+
+fn prompt(options: &Options, db: i64) -> String {
+    let addr = match options.socket {
+        Some(ref path) => path.clone(),
+
+        None => format!("{}:{}", options.host, options.port),
+    };
+
+    if db == 0 {
+        format!("{}> ", addr)
+    } else {
+        format!("{}[{}]> ", addr, db)
+    }
+}
+
+/// Reads commands from stdin, returning `false` if any failed.
+fn repl(mut connection: Connection, options: &Options) -> bool {
+    let interactive = io::stdin().is_terminal();
+
+    let mut editor = if interactive {
+        match DefaultEditor::new() {
+            Ok(editor) => Some(editor),
+
+            Err(e) => {
+                eprintln!("Error: {}", e);
+
+                return false;
+            }
+        }
+    } else {
+        None
+    };
+
+    if let (Some(editor), Some(path)) = (editor.as_mut(), history_path()) {
+        // There's no history the first time
+        let _ = editor.load_history(&path);
+    }
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    let mut db = options.db;
+    let mut ok = true;
+
+    loop {
+        let line = match editor {
+            Some(ref mut editor) => match editor.readline(&prompt(options, db)) {
+                Ok(line) => line,
+
+                Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => break,
+
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+
+                    ok = false;
+
+                    break;
+                }
+            },
+
+            None => match lines.next() {
+                Some(Ok(line)) => line,
+
+                _ => break,
+            },
+        };
+
+        if let Some(ref mut editor) = editor {
+            if !line.trim().is_empty() {
+                let _ = editor.add_history_entry(line.as_str());
+            }
+        }
+
+        let args = match split_args(&line) {
+            Some(ref args) if args.is_empty() => continue,
+
+            Some(args) => args,
+
+            None => {
+                eprintln!("Invalid argument(s)");
+
+                ok = false;
+
+                continue;
+            }
+        };
+
+        if args[0].eq_ignore_ascii_case(b"quit") || args[0].eq_ignore_ascii_case(b"exit") {
+            break;
+        }
+
+        if !run(&mut connection, &args, options, &mut db) {
+            ok = false;
+
+            break;
+        }
+    }
+
+    if let (Some(editor), Some(path)) = (editor.as_mut(), history_path()) {
+        let _ = editor.save_history(&path);
+    }
+
+    ok
+}
+
+fn main() {
+    let options = match parse_args() {
+        Ok(options) => options,
+
+        Err(e) => {
+            eprintln!("resp-cli: {}\n\n{}", e, USAGE);
+
+            process::exit(2);
+        }
+    };
+
+    let mut connection = match connect(&options) {
+        Ok(connection) => connection,
+
+        Err(e) => {
+            eprintln!("{}", e);
+
+            process::exit(1);
+        }
+    };
+
+    let ok = if options.command.is_empty() {
+        repl(connection, &options)
+    } else {
+        let mut db = options.db;
+
+        let args: Vec<Vec<u8>> = options
+            .command
+            .iter()
+            .map(|arg| arg.clone().into_bytes())
+            .collect();
+
+        run(&mut connection, &args, &options, &mut db)
+    };
+
+    if !ok {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,162 @@
+//! Encodes command lines or JSON documents as RESP.
+
+extern crate lib_resp;
+
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::process;
+
+use lib_resp::{split_args, Value};
+
+const USAGE: &str = "Usage: resp-encode [OPTIONS] [FILE]
+
+Reads commands from FILE, or stdin if FILE is missing or `-`, and writes them to stdout as RESP.
+
+Each line is a command, with arguments quoted like redis-cli, e.g.
+    SET greeting \"hello world\"
+
+With --json, each line is a JSON document instead. Arrays are commands, with strings, numbers
+and booleans as arguments, e.g.
+    [\"SET\", \"counter\", 1]
+Anything else is written as the RESP value it converts to.
+
+Options:
+    -j, --json    Read JSON documents, one per line, instead of commands
+    -h, --help    Print this message";
+
+struct Options {
+    json: bool,
+    path: Option<String>,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        json: false,
+        path: None,
+    };
+
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "-j" | "--json" => options.json = true,
+
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+
+                process::exit(0);
+            }
+
+            "-" => options.path = None,
+
+            _ if arg.starts_with('-') => return Err(format!("Unknown option: {}", arg)),
+
+            _ if options.path.is_some() => return Err("Only one FILE may be given".to_owned()),
+
+            _ => options.path = Some(arg),
+        }
+    }
+
+    Ok(options)
+}
+
+/// Converts the items of a JSON array to the arguments of a command, as Redis only accepts bulk
+/// strings in requests.
+fn json_args(items: Vec<Value>) -> Result<Vec<Vec<u8>>, String> {
+    if items.is_empty() {
+        return Err("Commands can't be empty".to_owned());
+    }
+
+    items
+        .into_iter()
+        .map(|item| match item {
+            Value::BStr(Some(datum)) | Value::BigNum(datum) => Ok(datum.into_bytes()),
+
+            Value::Int(datum) => Ok(datum.to_string().into_bytes()),
+
+            Value::Double(datum) => Ok(datum.to_string().into_bytes()),
+
+            Value::Bool(datum) => Ok(datum.to_string().into_bytes()),
+
+            item if item.is_null() => Err("Arguments can't be null".to_owned()),
+
+            _ => Err("Arguments must be strings, numbers or booleans".to_owned()),
+        })
+        .collect()
+}
+
+/// Encodes a command, unlike `Value` its arguments needn't be valid UTF-8.
+fn encode_command(out: &mut dyn Write, args: &[Vec<u8>]) -> io::Result<()> {
+    write!(out, "*{}\r\n", args.len())?;
+
+    for arg in args {
+        write!(out, "${}\r\n", arg.len())?;
+        out.write_all(arg)?;
+        out.write_all(b"\r\n")?;
+    }
+
+    Ok(())
+}
+
+fn encode<R: BufRead>(src: R, options: &Options) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut out = io::BufWriter::new(stdout.lock());
+
+    for (i, line) in src.lines().enumerate() {
+        let line = line?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let invalid = |e: String| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", i + 1, e))
+        };
+
+        if options.json {
+            match Value::from_json(&line).map_err(|e| invalid(e.to_string()))? {
+                Value::Array(Some(items)) => {
+                    encode_command(&mut out, &json_args(items).map_err(invalid)?)?
+                }
+
+                value => out.write_all(&value.encode_bytes())?,
+            }
+        } else {
+            let args = split_args(&line).ok_or_else(|| invalid("Unbalanced quotes".to_owned()))?;
+
+            encode_command(&mut out, &args)?;
+        }
+    }
+
+    out.flush()
+}
+
+fn main() {
+    let options = match parse_args() {
+        Ok(options) => options,
+
+        Err(e) => {
+            eprintln!("resp-encode: {}\n\n{}", e, USAGE);
+
+            process::exit(2);
+        }
+    };
+
+    let ret = match options.path {
+        Some(ref path) => File::open(path).and_then(|file| encode(BufReader::new(file), &options)),
+
+        None => encode(io::stdin().lock(), &options),
+    };
+
+    match ret {
+        Ok(()) => {}
+
+        // Stop quietly when piped into e.g. `head`
+        Err(ref e) if e.kind() == io::ErrorKind::BrokenPipe => {}
+
+        Err(e) => {
+            eprintln!("resp-encode: {}", e);
+
+            process::exit(1);
+        }
+    }
+}
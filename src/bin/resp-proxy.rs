@@ -0,0 +1,209 @@
+//! Logs the RESP traffic between clients and a server.
+
+extern crate lib_resp;
+
+use std::env;
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::process;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use lib_resp::{Action, Exchange, Proxy, SessionEntry, SessionWriter, Value};
+
+const USAGE: &str = "Usage: resp-proxy [OPTIONS] LISTEN UPSTREAM
+
+Listens on LISTEN, e.g. `127.0.0.1:6380`, forwarding connections to the server at UPSTREAM, e.g.
+`127.0.0.1:6379`. Each request and its reply are printed on a line of their own, e.g.
+
+    127.0.0.1:52814 0.215ms [\"GET\",\"key\"] -> \"value\"
+
+giving the client's address, the time taken, and the request and reply as JSON, followed by
+`(blocked)` or `(rewritten)` if the request wasn't forwarded as is.
+
+Options:
+    -b, --block COMMAND   Reply to COMMAND with an error instead of forwarding it, may be given
+                          more than once
+    -r, --record FILE     Record the session to FILE, for replaying with resp-replay, blocked
+                          requests aren't recorded
+    -h, --help            Print this message";
+
+struct Options {
+    listen: String,
+    upstream: String,
+    blocked: Vec<String>,
+    record: Option<String>,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut addrs = Vec::new();
+    let mut blocked = Vec::new();
+    let mut record = None;
+
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-b" | "--block" => match args.next() {
+                Some(command) => blocked.push(command),
+
+                None => return Err("--block requires a command".to_owned()),
+            },
+
+            "-r" | "--record" => match args.next() {
+                Some(path) => record = Some(path),
+
+                None => return Err("--record requires a file".to_owned()),
+            },
+
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+
+                process::exit(0);
+            }
+
+            _ if arg.starts_with('-') => return Err(format!("Unknown option: {}", arg)),
+
+            _ => addrs.push(arg),
+        }
+    }
+
+    if addrs.len() != 2 {
+        return Err("LISTEN and UPSTREAM are required".to_owned());
+    }
+
+    Ok(Options {
+        upstream: addrs.pop().unwrap(),
+        listen: addrs.pop().unwrap(),
+        blocked,
+        record,
+    })
+}
+
+struct Recording {
+    writer: SessionWriter<BufWriter<File>>,
+    start: Instant,
+}
+
+impl Recording {
+    fn record(&mut self, exchange: &Exchange) -> io::Result<()> {
+        // Record what the server received, so a replay sends the same requests
+        let request = match exchange.action {
+            Action::Forward => &exchange.request,
+
+            Action::Rewrite(ref rewritten) => rewritten,
+
+            // The server never saw the request, so there's nothing to replay
+            Action::Block(_) => return Ok(()),
+        };
+
+        let entry = SessionEntry {
+            connection: exchange.connection,
+            time: self.start.elapsed().saturating_sub(exchange.latency),
+            request: request.clone(),
+            reply: exchange.reply.clone(),
+        };
+
+        self.writer.write(&entry)?;
+
+        // Keep the file valid if the proxy is killed
+        self.writer.flush()
+    }
+}
+
+fn command_name(request: &Value) -> Option<&str> {
+    match *request {
+        Value::Array(Some(ref args)) => match args.first() {
+            Some(&Value::BStr(Some(ref name))) => Some(name),
+
+            _ => None,
+        },
+
+        _ => None,
+    }
+}
+
+fn main() {
+    let options = match parse_args() {
+        Ok(options) => options,
+
+        Err(e) => {
+            eprintln!("resp-proxy: {}\n\n{}", e, USAGE);
+
+            process::exit(2);
+        }
+    };
+
+    let blocked = options.blocked;
+
+    let session = match options.record {
+        Some(ref path) => match SessionWriter::create(path) {
+            Ok(writer) => Some(Mutex::new(Recording {
+                writer,
+                start: Instant::now(),
+            })),
+
+            Err(e) => {
+                eprintln!("resp-proxy: {}: {}", path, e);
+
+                process::exit(1);
+            }
+        },
+
+        None => None,
+    };
+
+    let proxy = match Proxy::bind(&options.listen[..], &options.upstream[..]) {
+        Ok(proxy) => proxy,
+
+        Err(e) => {
+            eprintln!("resp-proxy: {}", e);
+
+            process::exit(1);
+        }
+    };
+
+    let proxy = proxy
+        .filter(move |request| match command_name(request) {
+            Some(name) if blocked.iter().any(|b| b.eq_ignore_ascii_case(name)) => {
+                Action::Block(Value::err(format!(
+                    "ERR {} is blocked by resp-proxy",
+                    name.to_uppercase()
+                )))
+            }
+
+            _ => Action::Forward,
+        })
+        .log(move |exchange| {
+            let blocked = match exchange.action {
+                Action::Block(_) => " (blocked)",
+                Action::Rewrite(_) => " (rewritten)",
+                Action::Forward => "",
+            };
+
+            println!(
+                "{} {:.3}ms {} -> {}{}",
+                exchange.client,
+                exchange.latency.as_secs_f64() * 1000.0,
+                exchange.request.to_json(),
+                exchange.reply.to_json(),
+                blocked
+            );
+
+            if let Some(ref session) = session {
+                let mut session = session.lock().unwrap();
+
+                if let Err(e) = session.record(exchange) {
+                    eprintln!("resp-proxy: Failed to record session: {}", e);
+
+                    process::exit(1);
+                }
+            }
+        });
+
+    if let Err(e) = proxy.run() {
+        eprintln!("resp-proxy: {}", e);
+
+        process::exit(1);
+    }
+}
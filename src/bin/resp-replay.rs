@@ -0,0 +1,118 @@
+//! Replays a recorded session against a server, reporting replies which differ.
+
+extern crate lib_resp;
+
+use std::env;
+use std::process;
+
+use lib_resp::{replay, ReplayOptions, SessionReader};
+
+const USAGE: &str = "Usage: resp-replay [OPTIONS] FILE SERVER
+
+Replays the session recorded in FILE, e.g. by `resp-proxy --record`, against the server at
+SERVER, e.g. `127.0.0.1:6379`. Replies which differ from the ones recorded are printed as JSON.
+Exits with status 1 if any replies differ.
+
+Options:
+    -s, --speed SPEED      Send requests SPEED times faster than recorded, default 1
+    -f, --fast             Send requests as fast as possible
+    -i, --ignore COMMAND   Don't compare replies to COMMAND, may be given more than once
+    -h, --help             Print this message";
+
+struct Options {
+    path: String,
+    server: String,
+    replay: ReplayOptions,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut positional = Vec::new();
+    let mut replay = ReplayOptions::default();
+
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-s" | "--speed" => {
+                replay.speed = match args.next().and_then(|speed| speed.parse().ok()) {
+                    Some(speed) if speed > 0.0 => speed,
+
+                    _ => return Err("--speed requires a positive number".to_owned()),
+                }
+            }
+
+            "-f" | "--fast" => replay.speed = f64::INFINITY,
+
+            "-i" | "--ignore" => match args.next() {
+                Some(command) => replay.ignore.push(command),
+
+                None => return Err("--ignore requires a command".to_owned()),
+            },
+
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+
+                process::exit(0);
+            }
+
+            _ if arg.starts_with('-') => return Err(format!("Unknown option: {}", arg)),
+
+            _ => positional.push(arg),
+        }
+    }
+
+    if positional.len() != 2 {
+        return Err("FILE and SERVER are required".to_owned());
+    }
+
+    Ok(Options {
+        server: positional.pop().unwrap(),
+        path: positional.pop().unwrap(),
+        replay,
+    })
+}
+
+fn main() {
+    let options = match parse_args() {
+        Ok(options) => options,
+
+        Err(e) => {
+            eprintln!("resp-replay: {}\n\n{}", e, USAGE);
+
+            process::exit(2);
+        }
+    };
+
+    let report = SessionReader::open(&options.path)
+        .and_then(|session| replay(session, &options.server[..], &options.replay));
+
+    let report = match report {
+        Ok(report) => report,
+
+        Err(e) => {
+            eprintln!("resp-replay: {}", e);
+
+            process::exit(1);
+        }
+    };
+
+    for mismatch in &report.mismatches {
+        println!(
+            "#{} {}: expected {}, got {}",
+            mismatch.index,
+            mismatch.entry.request.to_json(),
+            mismatch.entry.reply.to_json(),
+            mismatch.reply.to_json()
+        );
+    }
+
+    eprintln!(
+        "resp-replay: {} replayed, {} differed",
+        report.replayed,
+        report.mismatches.len()
+    );
+
+    if !report.mismatches.is_empty() {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,220 @@
+use std::io::{BufReader, Error, ErrorKind, Result, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+use super::{Decoder, Protocol, Value};
+
+/// Blocking client for a single RESP connection.
+pub struct Client {
+    decoder: Decoder<BufReader<TcpStream>>,
+    stream: TcpStream,
+    pending: usize,
+    poisoned: bool,
+}
+
+impl Client {
+    /// Opens a connection to a server.
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> Result<Self> {
+        Client::from_stream(TcpStream::connect(addr)?)
+    }
+
+    /// Wraps an already connected stream.
+    pub fn from_stream(stream: TcpStream) -> Result<Self> {
+        stream.set_nodelay(true)?;
+
+        Ok(Client {
+            decoder: Decoder::new(BufReader::new(stream.try_clone()?)),
+            stream,
+            pending: 0,
+            poisoned: false,
+        })
+    }
+
+    /// Returns the address of the server.
+    pub fn peer_addr(&self) -> Result<SocketAddr> {
+        self.stream.peer_addr()
+    }
+
+    /// Returns a reference to the underlying stream.
+    ///
+    /// NOTE: Reading from or writing to the stream directly will desynchronize the client.
+    pub fn get_ref(&self) -> &TcpStream {
+        &self.stream
+    }
+
+    /// Returns a mutable reference to the decoder, for reading values which aren't replies.
+    pub(crate) fn decoder_mut(&mut self) -> &mut Decoder<BufReader<TcpStream>> {
+        &mut self.decoder
+    }
+
+    /// Returns the version of the protocol replies are decoded as.
+    pub fn protocol(&self) -> Protocol {
+        self.decoder.protocol()
+    }
+
+    /// Sets the version of the protocol replies are decoded as.
+    ///
+    /// NOTE: This doesn't negotiate anything with the server, see
+    /// [handshake](Client::handshake).
+    pub fn set_protocol(&mut self, protocol: Protocol) {
+        self.decoder.set_protocol(protocol);
+    }
+
+    /// Returns the number of values sent which haven't been replied to yet.
+    pub fn pending(&self) -> usize {
+        self.pending
+    }
+
+    /// Checks if the connection is quiet, i.e. there are no outstanding or unread replies.
+    ///
+    /// NOTE: Connections which have failed, or have been closed by the server, are never idle.
+    pub fn is_idle(&self) -> bool {
+        if self.poisoned
+            || self.pending != 0
+            || !self.decoder.partial().is_empty()
+            || !self.decoder.get_ref().buffer().is_empty()
+        {
+            return false;
+        }
+
+        // Anything waiting on the socket is a reply nobody asked for
+        if self.stream.set_nonblocking(true).is_err() {
+            return false;
+        }
+
+        let quiet = match self.stream.peek(&mut [0]) {
+            Err(ref e) => e.kind() == ErrorKind::WouldBlock,
+
+            // Either unread data, or the connection was closed
+            Ok(_) => false,
+        };
+
+        self.stream.set_nonblocking(false).is_ok() && quiet
+    }
+
+    /// Reads and discards the replies which haven't been read yet, e.g. to reuse a connection
+    /// which was abandoned mid-pipeline.
+    ///
+    /// Fails if they haven't all arrived within `timeout`, after which the connection can't be
+    /// used anymore.
+    ///
+    /// NOTE: The read timeout of the connection is restored afterwards.
+    pub fn drain(&mut self, timeout: Duration) -> Result<()> {
+        if self.poisoned {
+            return Err(Error::new(ErrorKind::Other, "Connection has failed"));
+        }
+
+        let previous = self.stream.read_timeout()?;
+        let deadline = Instant::now() + timeout;
+
+        let mut ret = Ok(());
+
+        while self.pending > 0 {
+            let now = Instant::now();
+
+            if now >= deadline {
+                self.poisoned = true;
+
+                ret = Err(Error::new(
+                    ErrorKind::TimedOut,
+                    "Timed out reading pending replies",
+                ));
+
+                break;
+            }
+
+            if let Err(e) = self.stream.set_read_timeout(Some(deadline - now)) {
+                ret = Err(e);
+
+                break;
+            }
+
+            if let Err(e) = self.recv() {
+                ret = Err(e);
+
+                break;
+            }
+        }
+
+        let restored = self.stream.set_read_timeout(previous);
+
+        ret.and(restored)
+    }
+
+    /// Sends a value to the server without waiting for a reply.
+    pub fn send(&mut self, value: &Value) -> Result<()> {
+        match self.stream.write_all(&value.encode_bytes()) {
+            Ok(()) => {
+                self.pending += 1;
+
+                Ok(())
+            }
+
+            Err(e) => {
+                self.poisoned = true;
+
+                Err(e)
+            }
+        }
+    }
+
+    /// Waits for a value from the server.
+    ///
+    /// NOTE: Error replies are returned as `Ok(Value::Err(_))`, `Err` is reserved for I/O and
+    /// protocol errors.
+    pub fn recv(&mut self) -> Result<Value> {
+        let ret = match self.decoder.decode() {
+            Ok(Some(value)) => Ok(value),
+
+            Ok(None) => Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                "Connection closed by server",
+            )),
+
+            Err(e) => Err(e),
+        };
+
+        match ret {
+            // Servers may push values which weren't asked for, e.g. after `MONITOR`
+            Ok(_) => self.pending = self.pending.saturating_sub(1),
+
+            Err(_) => self.poisoned = true,
+        }
+
+        ret
+    }
+
+    /// Sends a value to the server, then waits for its reply.
+    pub fn call(&mut self, value: &Value) -> Result<Value> {
+        self.send(value)?;
+
+        self.recv()
+    }
+
+    /// Sends a command to the server, then waits for its reply.
+    ///
+    /// Each argument is sent as a bulk string.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use lib_resp::Client;
+    /// let mut client = Client::connect("127.0.0.1:6379").unwrap();
+    ///
+    /// let reply = client.command(&["SET", "foo", "bar"]).unwrap();
+    ///
+    /// println!("{}", reply);
+    /// ```
+    pub fn command<T: ToString>(&mut self, args: &[T]) -> Result<Value> {
+        self.call(&command(args))
+    }
+}
+
+/// Builds a command from its arguments, each argument becomes a bulk string.
+pub(crate) fn command<T: ToString>(args: &[T]) -> Value {
+    Value::Array(Some(
+        args.iter()
+            .map(|arg| Value::BStr(Some(arg.to_string())))
+            .collect(),
+    ))
+}
@@ -0,0 +1,643 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Error, Result};
+use std::thread;
+use std::time::Duration;
+
+use super::client::{command, Client};
+use super::{ErrorKind, Redirect, Value};
+
+/// The number of hash slots in a cluster.
+pub const SLOT_COUNT: u16 = 16384;
+
+/// The maximum number of redirections followed for a single command.
+const MAX_REDIRECTS: usize = 16;
+
+/// How long to wait before retrying a command which failed with `TRYAGAIN`.
+const TRY_AGAIN_DELAY: Duration = Duration::from_millis(10);
+
+/// The reply to a command whose keys are in different slots, as a server would send it.
+const CROSS_SLOT: &str = "CROSSSLOT Keys in request don't hash to the same slot";
+
+/// Computes the hash slot of a key.
+///
+/// If the key contains a non-empty hash tag (e.g. `{user1000}.following`) only the tag is hashed.
+///
+/// # Examples
+///
+/// ```
+/// # use lib_resp::key_slot;
+/// assert_eq!(key_slot(b"foo"), 12182);
+///
+/// assert_eq!(key_slot(b"{user1000}.following"), key_slot(b"{user1000}.followers"));
+/// ```
+pub fn key_slot(key: &[u8]) -> u16 {
+    let key = match key.iter().position(|&b| b == b'{') {
+        Some(open) => match key[open + 1..].iter().position(|&b| b == b'}') {
+            Some(len) if len > 0 => &key[open + 1..open + 1 + len],
+
+            _ => key,
+        },
+
+        None => key,
+    };
+
+    crc16(key) % SLOT_COUNT
+}
+
+/// CRC16 (XMODEM), as used by Redis Cluster.
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+
+    for &byte in data {
+        crc ^= u16::from(byte) << 8;
+
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+
+    crc
+}
+
+/// Mapping of hash slots to the address of the master serving them.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SlotMap {
+    // Keyed by the first slot of each range
+    ranges: BTreeMap<u16, (u16, String)>,
+}
+
+impl SlotMap {
+    /// Constructs an empty slot map.
+    pub fn new() -> Self {
+        SlotMap::default()
+    }
+
+    /// Loads a slot map from a `CLUSTER SLOTS` reply.
+    ///
+    /// `default_host` is used for nodes which don't report their host, usually it should be the
+    /// host of the node which sent the reply.
+    ///
+    /// Returns `None` if the reply is malformed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lib_resp::{SlotMap, Value};
+    /// let reply = Value::Array(Some(vec![Value::Array(Some(vec![
+    ///     Value::int(0),
+    ///     Value::int(16383),
+    ///     Value::Array(Some(vec![
+    ///         Value::b_str(Some("127.0.0.1")),
+    ///         Value::int(30001),
+    ///         Value::b_str(Some("09dbe9720cda62f7865eabc5fd8857c5d2678366")),
+    ///     ])),
+    /// ]))]));
+    ///
+    /// let slots = SlotMap::from_slots(&reply, "127.0.0.1").unwrap();
+    ///
+    /// assert_eq!(slots.get(12182), Some("127.0.0.1:30001"));
+    /// ```
+    pub fn from_slots(reply: &Value, default_host: &str) -> Option<Self> {
+        let mut slots = SlotMap::new();
+
+        for range in array(reply)? {
+            let range = array(range)?;
+
+            if range.len() < 3 {
+                return None;
+            }
+
+            let master = array(&range[2])?;
+
+            if master.len() < 2 {
+                return None;
+            }
+
+            let addr = node_addr(string(&master[0])?, int(&master[1])?, default_host);
+
+            slots.insert(slot(&range[0])?, slot(&range[1])?, addr);
+        }
+
+        Some(slots)
+    }
+
+    /// Loads a slot map from a `CLUSTER SHARDS` reply.
+    ///
+    /// Only masters with a `health` of `online` are used.
+    ///
+    /// Returns `None` if the reply is malformed.
+    pub fn from_shards(reply: &Value, default_host: &str) -> Option<Self> {
+        let mut slots = SlotMap::new();
+
+        for shard in array(reply)? {
+            let shard = array(shard)?;
+
+            let ranges = array(field(shard, "slots")?)?;
+
+            if ranges.len() % 2 != 0 {
+                return None;
+            }
+
+            let master = array(field(shard, "nodes")?)?
+                .iter()
+                .filter_map(array)
+                .find(|node| {
+                    field(node, "role").and_then(string) == Some("master")
+                        && field(node, "health").and_then(string) == Some("online")
+                });
+
+            let master = match master {
+                Some(master) => master,
+
+                // The shard has no usable master, so its slots are unassigned
+                None => continue,
+            };
+
+            let host = match field(master, "endpoint").and_then(string) {
+                Some(endpoint) if endpoint != "?" => endpoint,
+
+                _ => field(master, "ip").and_then(string)?,
+            };
+
+            let port = match field(master, "port") {
+                Some(port) => int(port)?,
+
+                None => int(field(master, "tls-port")?)?,
+            };
+
+            let addr = node_addr(host, port, default_host);
+
+            for range in ranges.chunks(2) {
+                slots.insert(slot(&range[0])?, slot(&range[1])?, addr.clone());
+            }
+        }
+
+        Some(slots)
+    }
+
+    /// Assigns an inclusive range of slots to a node, replacing any previous assignments.
+    pub fn insert(&mut self, start: u16, end: u16, addr: String) {
+        let overlapping: Vec<u16> = self
+            .ranges
+            .range(..=end)
+            .filter(|&(_, &(range_end, _))| range_end >= start)
+            .map(|(&range_start, _)| range_start)
+            .collect();
+
+        for range_start in overlapping {
+            let (range_end, range_addr) = self.ranges.remove(&range_start).unwrap();
+
+            // Keep the parts of the old range either side of the new one
+            if range_start < start {
+                self.ranges
+                    .insert(range_start, (start - 1, range_addr.clone()));
+            }
+
+            if range_end > end {
+                self.ranges.insert(end + 1, (range_end, range_addr));
+            }
+        }
+
+        self.ranges.insert(start, (end, addr));
+    }
+
+    /// Returns the address of the node serving a slot.
+    pub fn get(&self, slot: u16) -> Option<&str> {
+        match self.ranges.range(..=slot).next_back() {
+            Some((_, &(end, ref addr))) if end >= slot => Some(addr),
+
+            _ => None,
+        }
+    }
+
+    /// Returns the address of every node in the map, without duplicates.
+    pub fn nodes(&self) -> Vec<&str> {
+        let mut nodes: Vec<&str> = self.ranges.values().map(|(_, addr)| &addr[..]).collect();
+
+        nodes.sort();
+        nodes.dedup();
+
+        nodes
+    }
+
+    /// Checks if no slots are assigned.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+}
+
+/// Blocking client for a Redis Cluster.
+///
+/// Commands are routed to the master serving their key's slot, `MOVED` and `ASK` redirections
+/// are followed transparently.
+pub struct ClusterClient {
+    seeds: Vec<String>,
+    slots: SlotMap,
+    nodes: HashMap<String, Client>,
+}
+
+impl ClusterClient {
+    /// Connects to a cluster, loading its slot map from the first reachable seed node.
+    ///
+    /// Seeds are `host:port` addresses of any nodes in the cluster.
+    pub fn connect<T: ToString>(seeds: &[T]) -> Result<Self> {
+        let mut client = ClusterClient {
+            seeds: seeds.iter().map(ToString::to_string).collect(),
+            slots: SlotMap::new(),
+            nodes: HashMap::new(),
+        };
+
+        client.refresh()?;
+
+        Ok(client)
+    }
+
+    /// Returns the current slot map.
+    pub fn slots(&self) -> &SlotMap {
+        &self.slots
+    }
+
+    /// Reloads the slot map from the cluster.
+    ///
+    /// Known nodes are queried first, then the seeds. `CLUSTER SHARDS` is used if a node
+    /// doesn't support `CLUSTER SLOTS`.
+    pub fn refresh(&mut self) -> Result<()> {
+        let mut candidates: Vec<String> =
+            self.slots.nodes().iter().map(|&n| n.to_owned()).collect();
+
+        candidates.extend(self.seeds.iter().cloned());
+
+        let mut last_err = None;
+
+        for addr in candidates {
+            match self.load_slots(&addr) {
+                Ok(Some(slots)) => {
+                    self.slots = slots;
+
+                    // Drop connections to nodes which are no longer masters
+                    let nodes = self.slots.nodes();
+
+                    self.nodes.retain(|addr, _| nodes.contains(&&addr[..]));
+
+                    return Ok(());
+                }
+
+                Ok(None) => {}
+
+                Err(e) => last_err = Some(e),
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| {
+            Error::new(io::ErrorKind::Other, "Unable to load the cluster slot map")
+        }))
+    }
+
+    /// Sends a command to the cluster, then waits for its reply.
+    ///
+    /// The command is routed by its keys, which are known for common commands, e.g. `GET`,
+    /// `MSET`, `EVAL` and `XREAD`. Other commands, including those without keys such as `INFO`,
+    /// are sent to any node. Use [call_slot](ClusterClient::call_slot) to route other commands
+    /// with keys.
+    ///
+    /// NOTE: If the keys are in different slots, the command isn't sent, and the reply is a
+    /// `CROSSSLOT` error as a server would send it.
+    pub fn call(&mut self, value: &Value) -> Result<Value> {
+        let mut slot = None;
+
+        if let Value::Array(Some(ref args)) = *value {
+            for key in keys(args) {
+                let key_slot = match *key {
+                    Value::BStr(Some(ref key)) | Value::Str(ref key) => key_slot(key.as_bytes()),
+
+                    Value::Int(key) => key_slot(key.to_string().as_bytes()),
+
+                    _ => continue,
+                };
+
+                match slot {
+                    Some(slot) if slot != key_slot => return Ok(Value::err(CROSS_SLOT)),
+
+                    _ => slot = Some(key_slot),
+                }
+            }
+        }
+
+        self.route(slot, value)
+    }
+
+    /// Sends a command to the node serving a slot, then waits for its reply.
+    ///
+    /// Returns an `InvalidInput` error if the slot is out of range.
+    pub fn call_slot(&mut self, slot: u16, value: &Value) -> Result<Value> {
+        if slot >= SLOT_COUNT {
+            return Err(Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Slot {} is out of range", slot),
+            ));
+        }
+
+        self.route(Some(slot), value)
+    }
+
+    /// Sends a command to the cluster, then waits for its reply.
+    ///
+    /// Each argument is sent as a bulk string, see [call](ClusterClient::call) for routing.
+    pub fn command<T: ToString>(&mut self, args: &[T]) -> Result<Value> {
+        self.call(&command(args))
+    }
+
+    fn route(&mut self, slot: Option<u16>, value: &Value) -> Result<Value> {
+        let mut target = None;
+        let mut asking = false;
+
+        for _ in 0..MAX_REDIRECTS {
+            let addr = match target.take() {
+                Some(addr) => addr,
+
+                None => self.addr_for(slot)?,
+            };
+
+            let reply = match self.exchange(&addr, value, asking) {
+                Ok(reply) => reply,
+
+                Err(e) => {
+                    self.nodes.remove(&addr);
+
+                    return Err(e);
+                }
+            };
+
+            asking = false;
+
+            let error = match reply.server_error() {
+                Some(error) => error,
+
+                None => return Ok(reply),
+            };
+
+            match *error.kind() {
+                ErrorKind::Moved(ref redirect) => {
+                    let moved_to = redirect_addr(redirect, &addr);
+
+                    self.slots
+                        .insert(redirect.slot, redirect.slot, moved_to.clone());
+
+                    // The topology changed, but the redirect alone is enough to retry
+                    let _ = self.refresh();
+
+                    target = Some(moved_to);
+                }
+
+                ErrorKind::Ask(ref redirect) => {
+                    target = Some(redirect_addr(redirect, &addr));
+
+                    asking = true;
+                }
+
+                ErrorKind::TryAgain => {
+                    thread::sleep(TRY_AGAIN_DELAY);
+
+                    target = Some(addr);
+                }
+
+                _ => return Ok(reply),
+            }
+        }
+
+        Err(Error::new(
+            io::ErrorKind::Other,
+            "Too many cluster redirections",
+        ))
+    }
+
+    fn addr_for(&self, slot: Option<u16>) -> Result<String> {
+        let addr = match slot {
+            Some(slot) => self.slots.get(slot),
+
+            None => self.slots.nodes().first().cloned(),
+        };
+
+        match addr {
+            Some(addr) => Ok(addr.to_owned()),
+
+            None => Err(Error::new(
+                io::ErrorKind::Other,
+                "Slot not served by any node",
+            )),
+        }
+    }
+
+    fn exchange(&mut self, addr: &str, value: &Value, asking: bool) -> Result<Value> {
+        let node = self.node(addr)?;
+
+        if asking {
+            node.send(&command(&["ASKING"]))?;
+        }
+
+        node.send(value)?;
+
+        if asking {
+            let reply = node.recv()?;
+
+            if let Value::Err(_) = reply {
+                node.recv()?;
+
+                return Ok(reply);
+            }
+        }
+
+        node.recv()
+    }
+
+    fn load_slots(&mut self, addr: &str) -> Result<Option<SlotMap>> {
+        let host = host(addr).to_owned();
+
+        let node = self.node(addr)?;
+
+        let reply = node.command(&["CLUSTER", "SLOTS"])?;
+
+        if let Value::Err(_) = reply {
+            let reply = node.command(&["CLUSTER", "SHARDS"])?;
+
+            return Ok(SlotMap::from_shards(&reply, &host));
+        }
+
+        Ok(SlotMap::from_slots(&reply, &host))
+    }
+
+    fn node(&mut self, addr: &str) -> Result<&mut Client> {
+        if !self.nodes.contains_key(addr) {
+            let client = Client::connect(addr)?;
+
+            self.nodes.insert(addr.to_owned(), client);
+        }
+
+        Ok(self.nodes.get_mut(addr).unwrap())
+    }
+}
+
+/// Returns the host part of a `host:port` address.
+fn host(addr: &str) -> &str {
+    match addr.rfind(':') {
+        Some(colon) => &addr[..colon],
+
+        None => addr,
+    }
+}
+
+fn redirect_addr(redirect: &Redirect, from: &str) -> String {
+    if redirect.host.is_empty() {
+        format!("{}:{}", host(from), redirect.port)
+    } else {
+        redirect.addr()
+    }
+}
+
+fn node_addr(host: &str, port: i64, default_host: &str) -> String {
+    if host.is_empty() {
+        format!("{}:{}", default_host, port)
+    } else {
+        format!("{}:{}", host, port)
+    }
+}
+
+fn array(value: &Value) -> Option<&[Value]> {
+    match *value {
+        Value::Array(Some(ref items)) => Some(items),
+
+        _ => None,
+    }
+}
+
+fn string(value: &Value) -> Option<&str> {
+    match *value {
+        Value::BStr(Some(ref datum)) | Value::Str(ref datum) => Some(datum),
+
+        _ => None,
+    }
+}
+
+fn int(value: &Value) -> Option<i64> {
+    match *value {
+        Value::Int(datum) => Some(datum),
+
+        Value::BStr(Some(ref datum)) => datum.parse().ok(),
+
+        _ => None,
+    }
+}
+
+fn slot(value: &Value) -> Option<u16> {
+    match int(value) {
+        Some(slot) if slot >= 0 && slot < i64::from(SLOT_COUNT) => Some(slot as u16),
+
+        _ => None,
+    }
+}
+
+/// Returns the keys of a command.
+///
+/// NOTE: Only common commands are known, others are taken to have no keys.
+fn keys(args: &[Value]) -> Vec<&Value> {
+    let name = match args.first().and_then(string) {
+        Some(name) => name.to_ascii_uppercase(),
+
+        None => return Vec::new(),
+    };
+
+    let args = &args[1..];
+
+    match &name[..] {
+        "DEL" | "EXISTS" | "MGET" | "PFCOUNT" | "PFMERGE" | "SDIFF" | "SDIFFSTORE" | "SINTER"
+        | "SINTERSTORE" | "SUNION" | "SUNIONSTORE" | "TOUCH" | "UNLINK" | "WATCH" => {
+            args.iter().collect()
+        }
+
+        "MSET" | "MSETNX" => args.iter().step_by(2).collect(),
+
+        // The last argument is the timeout
+        "BLPOP" | "BRPOP" | "BZPOPMAX" | "BZPOPMIN" => {
+            args[..args.len().saturating_sub(1)].iter().collect()
+        }
+
+        "BLMOVE" | "BRPOPLPUSH" | "COPY" | "LMOVE" | "RENAME" | "RENAMENX" | "RPOPLPUSH"
+        | "SMOVE" => args.iter().take(2).collect(),
+
+        // The script or function, then the number of keys
+        "EVAL" | "EVALSHA" | "EVAL_RO" | "EVALSHA_RO" | "FCALL" | "FCALL_RO" => {
+            numbered_keys(args.get(1..).unwrap_or(&[]))
+        }
+
+        // The destination, then the number of keys
+        "ZDIFFSTORE" | "ZINTERSTORE" | "ZUNIONSTORE" => {
+            let mut keys: Vec<&Value> = args.iter().take(1).collect();
+
+            keys.extend(numbered_keys(args.get(1..).unwrap_or(&[])));
+
+            keys
+        }
+
+        // The keys are the first half of the arguments after `STREAMS`, the rest are IDs
+        "XREAD" | "XREADGROUP" => {
+            let streams = args.iter().position(|arg| match string(arg) {
+                Some(arg) => arg.eq_ignore_ascii_case("STREAMS"),
+
+                None => false,
+            });
+
+            match streams {
+                Some(i) => {
+                    let streams = &args[i + 1..];
+
+                    streams[..streams.len() / 2].iter().collect()
+                }
+
+                None => Vec::new(),
+            }
+        }
+
+        // Strings, generic, lists, hashes, sets, sorted sets, streams, geo and HyperLogLog
+        "APPEND" | "BITCOUNT" | "BITFIELD" | "BITFIELD_RO" | "BITPOS" | "DECR" | "DECRBY"
+        | "GET" | "GETBIT" | "GETDEL" | "GETEX" | "GETRANGE" | "GETSET" | "INCR" | "INCRBY"
+        | "INCRBYFLOAT" | "PSETEX" | "SET" | "SETBIT" | "SETEX" | "SETNX" | "SETRANGE"
+        | "STRLEN" | "DUMP" | "EXPIRE" | "EXPIREAT" | "EXPIRETIME" | "PERSIST" | "PEXPIRE"
+        | "PEXPIREAT" | "PEXPIRETIME" | "PTTL" | "RESTORE" | "TTL" | "TYPE" | "LINDEX"
+        | "LINSERT" | "LLEN" | "LPOP" | "LPOS" | "LPUSH" | "LPUSHX" | "LRANGE" | "LREM"
+        | "LSET" | "LTRIM" | "RPOP" | "RPUSH" | "RPUSHX" | "HDEL" | "HEXISTS" | "HGET"
+        | "HGETALL" | "HINCRBY" | "HINCRBYFLOAT" | "HKEYS" | "HLEN" | "HMGET" | "HMSET"
+        | "HRANDFIELD" | "HSCAN" | "HSET" | "HSETNX" | "HSTRLEN" | "HVALS" | "SADD" | "SCARD"
+        | "SISMEMBER" | "SMEMBERS" | "SMISMEMBER" | "SPOP" | "SRANDMEMBER" | "SREM" | "SSCAN"
+        | "ZADD" | "ZCARD" | "ZCOUNT" | "ZINCRBY" | "ZLEXCOUNT" | "ZMSCORE" | "ZPOPMAX"
+        | "ZPOPMIN" | "ZRANDMEMBER" | "ZRANGE" | "ZRANGEBYLEX" | "ZRANGEBYSCORE" | "ZRANK"
+        | "ZREM" | "ZREMRANGEBYLEX" | "ZREMRANGEBYRANK" | "ZREMRANGEBYSCORE" | "ZREVRANGE"
+        | "ZREVRANGEBYLEX" | "ZREVRANGEBYSCORE" | "ZREVRANK" | "ZSCAN" | "ZSCORE" | "XACK"
+        | "XADD" | "XAUTOCLAIM" | "XCLAIM" | "XDEL" | "XLEN" | "XPENDING" | "XRANGE"
+        | "XREVRANGE" | "XSETID" | "XTRIM" | "GEOADD" | "GEODIST" | "GEOHASH" | "GEOPOS"
+        | "GEORADIUS" | "GEORADIUSBYMEMBER" | "GEOSEARCH" | "PFADD" => {
+            args.iter().take(1).collect()
+        }
+
+        _ => Vec::new(),
+    }
+}
+
+/// Returns the keys following their number, e.g. `2 key1 key2`.
+fn numbered_keys(args: &[Value]) -> Vec<&Value> {
+    match args.first().and_then(int) {
+        Some(n) if n > 0 => args[1..].iter().take(n as usize).collect(),
+
+        _ => Vec::new(),
+    }
+}
+
+/// Looks up a field in a flattened map, i.e. `[key, value, key, value, ...]`.
+fn field<'a>(map: &'a [Value], name: &str) -> Option<&'a Value> {
+    map.chunks(2)
+        .find(|pair| pair.len() == 2 && string(&pair[0]) == Some(name))
+        .map(|pair| &pair[1])
+}
@@ -1,11 +1,18 @@
-use nom::Err;
+use nom::{Err, Needed};
 use std::io::{BufRead, Error, ErrorKind, Result};
 
-use super::{Parser, Value};
+use super::parser::MAX_DEPTH;
+use super::{Parser, Protocol, Value};
 
 /// Streaming decoder implementation for [BufRead](std::io::BufRead) implementors
 pub struct Decoder<T: BufRead> {
     src: T,
+    protocol: Protocol,
+    partial: Vec<u8>,
+    scan: Scan,
+    consumed: u64,
+    // Whether the rest of a line containing an error needs to be skipped
+    skipping: bool,
     multi_buf: Vec<Value>,
 }
 
@@ -16,32 +23,158 @@ where
     pub fn new(src: T) -> Self {
         Decoder {
             src,
+            protocol: Protocol::Resp2,
+            partial: Vec::new(),
+            scan: Scan::new(),
+            consumed: 0,
+            skipping: false,
             multi_buf: Vec::new(),
         }
     }
 
+    /// Returns the version of the protocol being decoded.
+    pub fn protocol(&self) -> Protocol {
+        self.protocol
+    }
+
+    /// Sets the version of the protocol to decode, RESP2 is decoded by default.
+    pub fn set_protocol(&mut self, protocol: Protocol) {
+        self.protocol = protocol;
+    }
+
+    /// Returns a reference to the underlying stream.
+    pub fn get_ref(&self) -> &T {
+        &self.src
+    }
+
+    /// Returns a mutable reference to the underlying stream.
+    ///
+    /// NOTE: Reading from the stream directly while a value is [partially](Decoder::partial)
+    /// decoded will corrupt it.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.src
+    }
+
+    /// Returns the bytes of a value which has only been partially read from the stream.
+    pub fn partial(&self) -> &[u8] {
+        &self.partial
+    }
+
+    /// Returns the number of bytes of the stream taken up by the values decoded so far.
+    pub fn offset(&self) -> u64 {
+        self.consumed - self.partial.len() as u64
+    }
+
     /// Attempts to read a single value from the stream, then parse it.
+    ///
+    /// Values which span multiple reads of the underlying stream are buffered until they're
+    /// complete, `None` is only returned if the stream ends before a complete value is read.
+    ///
+    /// If the stream contains invalid RESP, the first line of the invalid value is skipped, so
+    /// decoding can resume from the line after it.
     pub fn decode(&mut self) -> Result<Option<Value>> {
-        let (ret, consumed) = {
-            let buf = self.src.fill_buf()?;
+        loop {
+            let (ret, consumed) = {
+                let buf = self.src.fill_buf()?;
+
+                if self.skipping {
+                    if buf.is_empty() {
+                        return Ok(None);
+                    }
+
+                    let (len, skipping) = line_len(buf);
+
+                    self.skipping = skipping;
+
+                    (None, len)
+                } else if self.partial.is_empty() {
+                    match Parser::parse_protocol(buf, self.protocol) {
+                        Ok((i, o)) => (Some(Ok(Some(o))), buf.len() - i.len()),
 
-            match Parser::parse(buf) {
-                Ok((i, o)) => (Ok(Some(o)), buf.len() - i.len()),
+                        Err(Err::Incomplete(_)) => {
+                            if buf.is_empty() {
+                                (Some(Ok(None)), 0)
+                            } else {
+                                self.partial.extend_from_slice(buf);
 
-                Err(Err::Incomplete(_)) => (Ok(None), 0),
+                                (None, buf.len())
+                            }
+                        }
 
-                Err(_) => (
-                    Err(Error::new(ErrorKind::InvalidData, "Invalid RESP")),
-                    buf.len(),
-                ),
+                        Err(_) => {
+                            let (len, skipping) = line_len(buf);
+
+                            self.skipping = skipping;
+
+                            (Some(Err(invalid_resp())), len)
+                        }
+                    }
+                } else {
+                    let held = self.partial.len();
+
+                    self.partial.extend_from_slice(buf);
+
+                    // Parsing the whole value again after every read would be quadratic
+                    let parsed = if self.scan.is_complete(&self.partial, self.protocol) {
+                        Parser::parse_protocol(&self.partial, self.protocol)
+                    } else {
+                        Err(Err::Incomplete(Needed::Unknown))
+                    };
+
+                    let (ret, skip) = match parsed {
+                        Ok((i, o)) => (Ok(Some(o)), self.partial.len() - i.len()),
+
+                        Err(Err::Incomplete(_)) => {
+                            if buf.is_empty() {
+                                // The stream ended part way through a value
+                                return Ok(None);
+                            }
+
+                            // Keep reading
+                            (Ok(None), 0)
+                        }
+
+                        Err(_) => {
+                            let (len, skipping) = line_len(&self.partial);
+
+                            self.skipping = skipping;
+
+                            (Err(invalid_resp()), len)
+                        }
+                    };
+
+                    if skip != 0 {
+                        self.scan = Scan::new();
+                    }
+
+                    if skip == 0 {
+                        (None, buf.len())
+                    } else if skip >= held {
+                        self.partial.clear();
+
+                        // Anything left over wasn't part of the value, leave it in the stream
+                        (Some(ret), skip - held)
+                    } else {
+                        // Only possible after an error, the rest of the buffered bytes may
+                        // still hold values
+                        self.partial.truncate(held);
+                        self.partial.drain(..skip);
+
+                        (Some(ret), 0)
+                    }
+                }
+            };
+
+            if consumed != 0 {
+                self.src.consume(consumed);
+
+                self.consumed += consumed as u64;
             }
-        };
 
-        if consumed != 0 {
-            self.src.consume(consumed);
+            if let Some(ret) = ret {
+                return ret;
+            }
         }
-
-        ret
     }
 
     /// Attempts to read *all* values from the stream, then parse them.
@@ -55,10 +188,120 @@ where
             }
         }
 
-        if self.src.fill_buf()?.len() > 0 {
+        if !self.partial.is_empty() || !self.src.fill_buf()?.is_empty() {
             return Ok(None);
         }
 
         Ok(Some(::std::mem::replace(&mut self.multi_buf, Vec::new())))
     }
 }
+
+/// Tracks how much of a partially read value has arrived, so that each read only scans the new
+/// bytes, rather than parsing the whole value again.
+struct Scan {
+    // Where the next unscanned value starts
+    pos: usize,
+    // The number of values left in each enclosing aggregate, innermost last
+    remaining: Vec<u64>,
+}
+
+impl Scan {
+    fn new() -> Self {
+        Scan {
+            pos: 0,
+            remaining: vec![1],
+        }
+    }
+
+    /// Scans the bytes of `buf` which haven't been scanned yet, returning whether they're worth
+    /// parsing, i.e. they hold a complete value, or something the parser will reject.
+    fn is_complete(&mut self, buf: &[u8], protocol: Protocol) -> bool {
+        while !self.remaining.is_empty() {
+            if self.remaining.len() > MAX_DEPTH + 1 {
+                return true;
+            }
+
+            let rest = &buf[self.pos..];
+
+            let line_end = match rest.iter().position(|&b| b == b'\n') {
+                Some(i) => i + 1,
+
+                None => return false,
+            };
+
+            let header = match rest[..line_end].strip_suffix(b"\r\n") {
+                Some(header) if !header.is_empty() => header,
+
+                _ => return true,
+            };
+
+            let resp3 = protocol == Protocol::Resp3;
+
+            let len = ::std::str::from_utf8(&header[1..])
+                .ok()
+                .and_then(|len| len.parse::<i64>().ok());
+
+            let mut next = self.pos + line_end;
+            let mut children = 0;
+
+            match (header[0], len) {
+                (b'+', _) | (b'-', _) | (b':', _) => {}
+
+                (b'_', _) | (b'#', _) | (b',', _) | (b'(', _) if resp3 => {}
+
+                // Nulls, the parser takes any negative length as null
+                (b'$', Some(len)) | (b'*', Some(len)) if len < 0 => {}
+
+                (b'$', Some(len)) | (b'!', Some(len)) | (b'=', Some(len))
+                    if len >= 0 && (resp3 || header[0] == b'$') =>
+                {
+                    next = next.saturating_add(len as usize).saturating_add(2);
+
+                    if next > buf.len() {
+                        return false;
+                    }
+                }
+
+                (b'*', Some(len)) | (b'~', Some(len)) | (b'>', Some(len))
+                    if len >= 0 && (resp3 || header[0] == b'*') =>
+                {
+                    children = len as u64;
+                }
+
+                (b'%', Some(len)) if len >= 0 && resp3 => children = (len as u64).saturating_mul(2),
+
+                _ => return true,
+            }
+
+            self.pos = next;
+
+            if let Some(remaining) = self.remaining.last_mut() {
+                *remaining -= 1;
+            }
+
+            if children > 0 {
+                self.remaining.push(children);
+            }
+
+            while self.remaining.last() == Some(&0) {
+                self.remaining.pop();
+            }
+        }
+
+        true
+    }
+}
+
+/// Returns the length of the first line in `buf` including its line ending, and whether the line
+/// continues past the end of `buf`.
+fn line_len(buf: &[u8]) -> (usize, bool) {
+    match buf.iter().position(|&b| b == b'\n') {
+        Some(i) => (i + 1, false),
+
+        None => (buf.len(), true),
+    }
+}
+
+fn invalid_resp() -> Error {
+    Error::new(ErrorKind::InvalidData, "Invalid RESP")
+}
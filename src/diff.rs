@@ -0,0 +1,288 @@
+use std::cmp::{self, Ordering};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use super::Value;
+
+/// A difference between two [Value](Value)s, see [Value::diff](Value::diff).
+///
+/// Paths are [pointers](Value::pointer), e.g. `/2/0`, empty for the values themselves.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Difference {
+    /// Values of the same kind differ.
+    Changed {
+        path: String,
+        left: Value,
+        right: Value,
+    },
+    /// Values are of different kinds.
+    KindMismatch {
+        path: String,
+        left: Value,
+        right: Value,
+    },
+    /// An item is only in the left value.
+    ///
+    /// NOTE: The path of an item of a set is the path of the set.
+    Removed { path: String, value: Value },
+    /// An item is only in the right value.
+    ///
+    /// NOTE: The path of an item of a set is the path of the set.
+    Added { path: String, value: Value },
+}
+
+impl Difference {
+    /// Returns the path to the difference.
+    pub fn path(&self) -> &str {
+        match *self {
+            Difference::Changed { ref path, .. }
+            | Difference::KindMismatch { ref path, .. }
+            | Difference::Removed { ref path, .. }
+            | Difference::Added { ref path, .. } => path,
+        }
+    }
+}
+
+impl Display for Difference {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let path = if self.path().is_empty() {
+            "(root)"
+        } else {
+            self.path()
+        };
+
+        match *self {
+            Difference::Changed {
+                ref left,
+                ref right,
+                ..
+            } => write!(f, "~ {}: {:?} -> {:?}", path, left, right),
+
+            Difference::KindMismatch {
+                ref left,
+                ref right,
+                ..
+            } => write!(
+                f,
+                "~ {}: {:?} -> {:?} ({:?} -> {:?})",
+                path,
+                left,
+                right,
+                left.kind(),
+                right.kind()
+            ),
+
+            Difference::Removed { ref value, .. } => write!(f, "- {}: {:?}", path, value),
+
+            Difference::Added { ref value, .. } => write!(f, "+ {}: {:?}", path, value),
+        }
+    }
+}
+
+impl Value {
+    /// Lists the differences between two `Value`s, empty if they're equivalent.
+    ///
+    /// Arrays and pushes are compared item by item, with items inserted or removed in one place
+    /// reported as such. Sets and maps are compared regardless of order, like they've been
+    /// [canonicalized](Value::canonicalize).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate lib_resp;
+    /// # use lib_resp::{Difference, Value};
+    /// # fn main() {
+    /// let left = resp![1, [2], "a"];
+    /// let right = resp![1, [3]];
+    ///
+    /// assert_eq!(
+    ///     left.diff(&right),
+    ///     vec![
+    ///         Difference::Changed {
+    ///             path: "/1/0".to_owned(),
+    ///             left: Value::int(2),
+    ///             right: Value::int(3),
+    ///         },
+    ///         Difference::Removed {
+    ///             path: "/2".to_owned(),
+    ///             value: Value::b_str(Some("a")),
+    ///         },
+    ///     ]
+    /// );
+    /// # }
+    /// ```
+    pub fn diff(&self, other: &Value) -> Vec<Difference> {
+        let mut left = self.clone();
+        let mut right = other.clone();
+
+        left.canonicalize();
+        right.canonicalize();
+
+        let mut differences = Vec::new();
+
+        diff(&left, &right, "", &mut differences);
+
+        differences
+    }
+}
+
+fn diff(left: &Value, right: &Value, path: &str, differences: &mut Vec<Difference>) {
+    if left == right {
+        return;
+    }
+
+    match (left, right) {
+        (Value::Array(Some(left)), Value::Array(Some(right)))
+        | (Value::Push(left), Value::Push(right)) => diff_items(left, right, path, differences),
+
+        // Canonical sets and maps are sorted, so they can be merged
+        (Value::Set(left), Value::Set(right)) => merge(left, right, Ord::cmp, |side| match side {
+            Side::Left(value) => differences.push(Difference::Removed {
+                path: path.to_owned(),
+                value: value.clone(),
+            }),
+
+            Side::Right(value) => differences.push(Difference::Added {
+                path: path.to_owned(),
+                value: value.clone(),
+            }),
+
+            Side::Both(..) => {}
+        }),
+
+        (Value::Map(left), Value::Map(right)) => merge(
+            left,
+            right,
+            |a, b| a.0.cmp(&b.0),
+            |side| match side {
+                Side::Left((key, value)) => differences.push(Difference::Removed {
+                    path: child_path(path, &key_segment(key)),
+                    value: value.clone(),
+                }),
+
+                Side::Right((key, value)) => differences.push(Difference::Added {
+                    path: child_path(path, &key_segment(key)),
+                    value: value.clone(),
+                }),
+
+                Side::Both((key, left), (_, right)) => diff(
+                    left,
+                    right,
+                    &child_path(path, &key_segment(key)),
+                    differences,
+                ),
+            },
+        ),
+
+        _ if left.kind() == right.kind() => differences.push(Difference::Changed {
+            path: path.to_owned(),
+            left: left.clone(),
+            right: right.clone(),
+        }),
+
+        _ => differences.push(Difference::KindMismatch {
+            path: path.to_owned(),
+            left: left.clone(),
+            right: right.clone(),
+        }),
+    }
+}
+
+/// Compares the items which differ between the common prefix and suffix pairwise, and reports
+/// the rest as removed or added.
+fn diff_items(left: &[Value], right: &[Value], path: &str, differences: &mut Vec<Difference>) {
+    let prefix = left.iter().zip(right).take_while(|&(a, b)| a == b).count();
+
+    let suffix = left[prefix..]
+        .iter()
+        .rev()
+        .zip(right[prefix..].iter().rev())
+        .take_while(|&(a, b)| a == b)
+        .count();
+
+    let left = &left[prefix..left.len() - suffix];
+    let right = &right[prefix..right.len() - suffix];
+
+    let common = cmp::min(left.len(), right.len());
+
+    for (i, (a, b)) in left.iter().zip(right).enumerate() {
+        diff(
+            a,
+            b,
+            &child_path(path, &(prefix + i).to_string()),
+            differences,
+        );
+    }
+
+    for (i, value) in left[common..].iter().enumerate() {
+        differences.push(Difference::Removed {
+            path: child_path(path, &(prefix + common + i).to_string()),
+            value: value.clone(),
+        });
+    }
+
+    for (i, value) in right[common..].iter().enumerate() {
+        differences.push(Difference::Added {
+            path: child_path(path, &(prefix + common + i).to_string()),
+            value: value.clone(),
+        });
+    }
+}
+
+enum Side<'a, T: 'a> {
+    Left(&'a T),
+    Right(&'a T),
+    Both(&'a T, &'a T),
+}
+
+/// Merges two sorted slices, calling `f` with each item and which side(s) it's on.
+fn merge<'a, T, C, F>(left: &'a [T], right: &'a [T], compare: C, mut f: F)
+where
+    C: Fn(&T, &T) -> Ordering,
+    F: FnMut(Side<'a, T>),
+{
+    let (mut i, mut j) = (0, 0);
+
+    while i < left.len() && j < right.len() {
+        match compare(&left[i], &right[j]) {
+            Ordering::Less => {
+                f(Side::Left(&left[i]));
+
+                i += 1;
+            }
+
+            Ordering::Greater => {
+                f(Side::Right(&right[j]));
+
+                j += 1;
+            }
+
+            Ordering::Equal => {
+                f(Side::Both(&left[i], &right[j]));
+
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+
+    left[i..].iter().for_each(|item| f(Side::Left(item)));
+    right[j..].iter().for_each(|item| f(Side::Right(item)));
+}
+
+/// Formats a map key as a path segment, like [Value::pointer](Value::pointer) looks them up.
+fn key_segment(key: &Value) -> String {
+    match *key {
+        Value::Int(datum) => datum.to_string(),
+
+        ref key => match key.as_str() {
+            Some(datum) => datum.to_owned(),
+
+            None => format!("{:?}", key),
+        },
+    }
+}
+
+fn child_path(path: &str, segment: &str) -> String {
+    format!("{}/{}", path, segment.replace('~', "~0").replace('/', "~1"))
+}
@@ -0,0 +1,335 @@
+use std::io::{Error, ErrorKind as IoErrorKind, Result};
+
+use super::client::command;
+use super::{Client, ErrorKind, Protocol, Value};
+
+/// Options for a [Client::handshake](Client::handshake).
+#[derive(Clone, Debug)]
+pub struct HandshakeOptions {
+    /// The preferred protocol version, RESP2 is used if the server doesn't support it.
+    pub protocol: Protocol,
+    /// The user to authenticate as, `default` is used if only a password is given.
+    pub username: Option<String>,
+    /// The password to authenticate with.
+    pub password: Option<String>,
+    /// The name to give the connection, see `CLIENT SETNAME`.
+    pub client_name: Option<String>,
+    /// The database to select.
+    pub db: Option<i64>,
+}
+
+impl Default for HandshakeOptions {
+    fn default() -> Self {
+        HandshakeOptions {
+            protocol: Protocol::Resp3,
+            username: None,
+            password: None,
+            client_name: None,
+            db: None,
+        }
+    }
+}
+
+/// Details of a server, as returned by `HELLO`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ServerInfo {
+    /// The name of the server software, e.g. `redis`.
+    pub server: String,
+    /// The version of the server software.
+    pub version: String,
+    /// The negotiated protocol version.
+    pub proto: i64,
+    /// The ID of the connection.
+    pub id: i64,
+    /// The mode the server is running in, e.g. `standalone` or `cluster`.
+    pub mode: String,
+    /// The replication role of the server, e.g. `master` or `replica`.
+    pub role: String,
+    /// The modules loaded by the server.
+    pub modules: Vec<Value>,
+}
+
+impl ServerInfo {
+    /// Reads server details from a `HELLO` reply.
+    ///
+    /// Both the RESP3 map, and the flattened RESP2 array forms are accepted. Returns `None`
+    /// if `server`, `version` or `proto` are missing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lib_resp::{ServerInfo, Value};
+    /// let reply = Value::Map(vec![
+    ///     (Value::b_str(Some("server")), Value::b_str(Some("redis"))),
+    ///     (Value::b_str(Some("version")), Value::b_str(Some("7.2.4"))),
+    ///     (Value::b_str(Some("proto")), Value::int(3)),
+    /// ]);
+    ///
+    /// let info = ServerInfo::from_value(&reply).unwrap();
+    ///
+    /// assert_eq!(info.version, "7.2.4");
+    /// assert_eq!(info.proto, 3);
+    /// ```
+    pub fn from_value(reply: &Value) -> Option<Self> {
+        let pairs: Vec<(&Value, &Value)> = match *reply {
+            Value::Map(ref pairs) => pairs.iter().map(|(k, v)| (k, v)).collect(),
+
+            Value::Array(Some(ref items)) if items.len() % 2 == 0 => {
+                items.chunks(2).map(|pair| (&pair[0], &pair[1])).collect()
+            }
+
+            _ => return None,
+        };
+
+        let field = |name: &str| {
+            pairs
+                .iter()
+                .find(|&&(key, _)| string(key) == Some(name))
+                .map(|&(_, value)| value)
+        };
+
+        let string_field = |name: &str| field(name).and_then(string).map(ToOwned::to_owned);
+
+        let int_field = |name: &str| match field(name) {
+            Some(&Value::Int(value)) => Some(value),
+
+            _ => None,
+        };
+
+        Some(ServerInfo {
+            server: string_field("server")?,
+            version: string_field("version")?,
+            proto: int_field("proto")?,
+            id: int_field("id").unwrap_or(0),
+            mode: string_field("mode").unwrap_or_default(),
+            role: string_field("role").unwrap_or_default(),
+            modules: match field("modules") {
+                Some(&Value::Array(Some(ref modules))) | Some(&Value::Set(ref modules)) => {
+                    modules.clone()
+                }
+
+                _ => Vec::new(),
+            },
+        })
+    }
+}
+
+impl Client {
+    /// Sets up the connection, authenticating, naming it and selecting a database as needed.
+    ///
+    /// `HELLO` is tried first, falling back to RESP2 if the server replies with `NOPROTO`. If the
+    /// server doesn't know `HELLO` at all, `AUTH` and `CLIENT SETNAME` are used instead and
+    /// `None` is returned. Replies are decoded using the negotiated protocol afterwards.
+    ///
+    /// Error replies, e.g. for invalid credentials, are returned as errors wrapping a
+    /// `ServerError`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use lib_resp::{Client, HandshakeOptions, Protocol};
+    /// let mut client = Client::connect("127.0.0.1:6379").unwrap();
+    ///
+    /// let info = client
+    ///     .handshake(&HandshakeOptions {
+    ///         password: Some("hunter2".to_owned()),
+    ///         db: Some(2),
+    ///         ..HandshakeOptions::default()
+    ///     })
+    ///     .unwrap();
+    ///
+    /// println!("{:?} speaking {:?}", info, client.protocol());
+    /// ```
+    pub fn handshake(&mut self, options: &HandshakeOptions) -> Result<Option<ServerInfo>> {
+        Handshake::handshake(self, options)
+    }
+}
+
+/// A connection which can be set up with a [handshake](Handshake::handshake).
+///
+/// NOTE: This is implemented by [Client](Client), and lets other connections, e.g. over a unix
+/// socket, share the same setup.
+pub trait Handshake {
+    /// Sends a value to the server, then waits for its reply.
+    fn call(&mut self, value: &Value) -> Result<Value>;
+
+    /// Returns the version of the protocol replies are decoded as.
+    fn protocol(&self) -> Protocol;
+
+    /// Sets the version of the protocol replies are decoded as.
+    fn set_protocol(&mut self, protocol: Protocol);
+
+    /// Sets up the connection, see [Client::handshake](Client::handshake).
+    fn handshake(&mut self, options: &HandshakeOptions) -> Result<Option<ServerInfo>> {
+        let mut reply = hello(self, options.protocol, options)?;
+
+        if options.protocol == Protocol::Resp3 && error_kind(&reply) == Some(ErrorKind::NoProto) {
+            reply = hello(self, Protocol::Resp2, options)?;
+        }
+
+        let info = if is_unknown_command(&reply) {
+            legacy_handshake(self, options)?;
+
+            None
+        } else {
+            check(&reply)?;
+
+            Some(
+                ServerInfo::from_value(&reply)
+                    .ok_or_else(|| Error::new(IoErrorKind::InvalidData, "Invalid HELLO reply"))?,
+            )
+        };
+
+        if let Some(db) = options.db {
+            check(&self.call(&command(&["SELECT".to_owned(), db.to_string()]))?)?;
+        }
+
+        Ok(info)
+    }
+}
+
+impl Handshake for Client {
+    fn call(&mut self, value: &Value) -> Result<Value> {
+        Client::call(self, value)
+    }
+
+    fn protocol(&self) -> Protocol {
+        Client::protocol(self)
+    }
+
+    fn set_protocol(&mut self, protocol: Protocol) {
+        Client::set_protocol(self, protocol)
+    }
+}
+
+fn hello<C: Handshake + ?Sized>(
+    connection: &mut C,
+    protocol: Protocol,
+    options: &HandshakeOptions,
+) -> Result<Value> {
+    let mut args = vec![
+        "HELLO".to_owned(),
+        match protocol {
+            Protocol::Resp2 => "2",
+            Protocol::Resp3 => "3",
+        }
+        .to_owned(),
+    ];
+
+    if let Some(ref password) = options.password {
+        args.push("AUTH".to_owned());
+        args.push(
+            options
+                .username
+                .clone()
+                .unwrap_or_else(|| "default".to_owned()),
+        );
+        args.push(password.clone());
+    }
+
+    if let Some(ref name) = options.client_name {
+        args.push("SETNAME".to_owned());
+        args.push(name.clone());
+    }
+
+    let previous = connection.protocol();
+
+    // The reply to a successful `HELLO` is already in the requested protocol
+    connection.set_protocol(protocol);
+
+    let reply = connection.call(&command(&args))?;
+
+    if let Value::Err(_) = reply {
+        connection.set_protocol(previous);
+    }
+
+    Ok(reply)
+}
+
+fn legacy_handshake<C: Handshake + ?Sized>(
+    connection: &mut C,
+    options: &HandshakeOptions,
+) -> Result<()> {
+    if let Some(ref password) = options.password {
+        let reply = match options.username {
+            Some(ref username) => connection.call(&command(&["AUTH", username, password]))?,
+
+            None => connection.call(&command(&["AUTH", password]))?,
+        };
+
+        check(&reply)?;
+    }
+
+    if let Some(ref name) = options.client_name {
+        check(&connection.call(&command(&["CLIENT", "SETNAME", name]))?)?;
+    }
+
+    Ok(())
+}
+
+fn string(value: &Value) -> Option<&str> {
+    match *value {
+        Value::BStr(Some(ref datum)) | Value::Str(ref datum) | Value::Verbatim(_, ref datum) => {
+            Some(datum)
+        }
+
+        _ => None,
+    }
+}
+
+fn error_kind(reply: &Value) -> Option<ErrorKind> {
+    reply.server_error().map(|error| error.kind().clone())
+}
+
+fn is_unknown_command(reply: &Value) -> bool {
+    match reply.server_error() {
+        Some(error) => {
+            *error.kind() == ErrorKind::Generic && error.message().starts_with("unknown command")
+        }
+
+        None => false,
+    }
+}
+
+/// Converts error replies into errors.
+pub(crate) fn check(reply: &Value) -> Result<()> {
+    match reply.server_error() {
+        Some(error) => {
+            let kind = match *error.kind() {
+                ErrorKind::NoAuth | ErrorKind::WrongPass | ErrorKind::NoPerm => {
+                    IoErrorKind::PermissionDenied
+                }
+
+                _ => IoErrorKind::Other,
+            };
+
+            Err(Error::new(kind, error))
+        }
+
+        None => Ok(()),
+    }
+}
+
+/// Returns the protocol requested by a `HELLO` command, if `request` is one.
+pub(crate) fn requested_protocol(request: &Value) -> Option<Protocol> {
+    let args = match *request {
+        Value::Array(Some(ref args)) => args,
+
+        _ => return None,
+    };
+
+    match (args.first(), args.get(1)) {
+        (Some(&Value::BStr(Some(ref command))), Some(&Value::BStr(Some(ref version))))
+            if command.eq_ignore_ascii_case("hello") =>
+        {
+            match &version[..] {
+                "2" => Some(Protocol::Resp2),
+                "3" => Some(Protocol::Resp3),
+
+                _ => None,
+            }
+        }
+
+        _ => None,
+    }
+}
@@ -0,0 +1,331 @@
+use super::Value;
+
+/// A value from an `INFO` reply.
+///
+/// The type of a value is guessed, but its text is kept as it was received, see
+/// [as_raw](InfoValue::as_raw).
+#[derive(Clone, Debug, PartialEq)]
+pub enum InfoValue {
+    /// An integer, e.g. `connected_clients:1`, and its text.
+    Int(i64, String),
+    /// A decimal number, e.g. `mem_fragmentation_ratio:1.23`, and its text.
+    Float(f64, String),
+    /// Anything else, e.g. `role:master`.
+    Str(String),
+    /// Nested `key=value` pairs, e.g. `db0:keys=1,expires=0,avg_ttl=0`, and their text.
+    Fields(Vec<(String, InfoValue)>, String),
+}
+
+impl InfoValue {
+    /// Parses a value, guessing its type.
+    fn parse(value: &str) -> Self {
+        if let Ok(int) = value.parse() {
+            return InfoValue::Int(int, value.to_owned());
+        }
+
+        // Avoid parsing things like `inf` and `1e5` (which might be the start of an ID) as floats
+        let is_decimal = value.contains('.')
+            && value
+                .bytes()
+                .all(|b| b.is_ascii_digit() || b == b'.' || b == b'-');
+
+        if is_decimal {
+            if let Ok(float) = value.parse() {
+                return InfoValue::Float(float, value.to_owned());
+            }
+        }
+
+        let is_fields = value.contains('=')
+            && value
+                .split(',')
+                .all(|field| matches!(field.find('='), Some(i) if i > 0));
+
+        if is_fields {
+            return InfoValue::Fields(
+                value
+                    .split(',')
+                    .map(|field| {
+                        let i = field.find('=').unwrap();
+
+                        (field[..i].to_owned(), InfoValue::parse(&field[i + 1..]))
+                    })
+                    .collect(),
+                value.to_owned(),
+            );
+        }
+
+        InfoValue::Str(value.to_owned())
+    }
+
+    /// Returns the value as an integer.
+    pub fn as_int(&self) -> Option<i64> {
+        match *self {
+            InfoValue::Int(value, _) => Some(value),
+
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a decimal number, integers are converted.
+    pub fn as_float(&self) -> Option<f64> {
+        match *self {
+            InfoValue::Int(value, _) => Some(value as f64),
+
+            InfoValue::Float(value, _) => Some(value),
+
+            _ => None,
+        }
+    }
+
+    /// Returns the text of the value, whichever type it was guessed to be, unless it's nested
+    /// fields.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lib_resp::Info;
+    /// let info = Info::parse("redis_version:7.2\r\nredis_git_sha1:00000000\r\n");
+    ///
+    /// assert_eq!(info.version(), Some("7.2"));
+    /// assert_eq!(info.get("redis_git_sha1").unwrap().as_str(), Some("00000000"));
+    /// ```
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            InfoValue::Fields(..) => None,
+
+            ref value => Some(value.as_raw()),
+        }
+    }
+
+    /// Returns the text of the value, exactly as it was received.
+    pub fn as_raw(&self) -> &str {
+        match *self {
+            InfoValue::Int(_, ref raw)
+            | InfoValue::Float(_, ref raw)
+            | InfoValue::Str(ref raw)
+            | InfoValue::Fields(_, ref raw) => raw,
+        }
+    }
+
+    /// Looks up a nested field.
+    pub fn get(&self, key: &str) -> Option<&InfoValue> {
+        match *self {
+            InfoValue::Fields(ref fields, _) => get(fields, key),
+
+            _ => None,
+        }
+    }
+}
+
+/// A section of an `INFO` reply, e.g. `# Memory`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InfoSection {
+    /// The name of the section, as written in its header.
+    pub name: String,
+    /// The fields of the section, in the order they were received.
+    pub fields: Vec<(String, InfoValue)>,
+}
+
+impl InfoSection {
+    /// Looks up a field.
+    pub fn get(&self, key: &str) -> Option<&InfoValue> {
+        get(&self.fields, key)
+    }
+}
+
+/// Statistics for a database, from the `# Keyspace` section.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Keyspace {
+    /// The index of the database.
+    pub db: i64,
+    /// The number of keys.
+    pub keys: i64,
+    /// The number of keys with an expiry.
+    pub expires: i64,
+    /// The average time to live of keys with an expiry, in milliseconds.
+    pub avg_ttl: i64,
+}
+
+/// A parsed `INFO` reply.
+///
+/// # Examples
+///
+/// ```
+/// # use lib_resp::{Info, Value};
+/// let reply = Value::b_str(Some(
+///     "# Replication\r\nrole:master\r\nconnected_slaves:0\r\n\r\n# Keyspace\r\ndb0:keys=1,expires=0,avg_ttl=0\r\n",
+/// ));
+///
+/// let info = Info::from_value(&reply).unwrap();
+///
+/// assert_eq!(info.role(), Some("master"));
+/// assert_eq!(info.keyspace()[0].keys, 1);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Info {
+    /// The sections of the reply, in the order they were received.
+    pub sections: Vec<InfoSection>,
+}
+
+impl Info {
+    /// Parses the text of an `INFO` reply.
+    ///
+    /// Fields before the first section header are put in a section with an empty name.
+    pub fn parse(text: &str) -> Self {
+        let mut sections: Vec<InfoSection> = Vec::new();
+
+        for line in text.lines() {
+            let line = line.trim_end_matches('\r');
+
+            if let Some(name) = line.strip_prefix('#') {
+                sections.push(InfoSection {
+                    name: name.trim().to_owned(),
+                    fields: Vec::new(),
+                });
+
+                continue;
+            }
+
+            let colon = match line.find(':') {
+                Some(colon) => colon,
+
+                // Blank lines separate sections
+                None => continue,
+            };
+
+            if sections.is_empty() {
+                sections.push(InfoSection {
+                    name: String::new(),
+                    fields: Vec::new(),
+                });
+            }
+
+            let section = sections.last_mut().unwrap();
+
+            section.fields.push((
+                line[..colon].to_owned(),
+                InfoValue::parse(&line[colon + 1..]),
+            ));
+        }
+
+        Info { sections }
+    }
+
+    /// Parses an `INFO` reply, which may be a bulk or verbatim string.
+    ///
+    /// Returns `None` for any other type of value.
+    pub fn from_value(value: &Value) -> Option<Self> {
+        match *value {
+            Value::BStr(Some(ref text)) | Value::Verbatim(_, ref text) => Some(Info::parse(text)),
+
+            _ => None,
+        }
+    }
+
+    /// Looks up a section by name, ignoring case.
+    pub fn section(&self, name: &str) -> Option<&InfoSection> {
+        self.sections
+            .iter()
+            .find(|section| section.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Looks up a field in any section.
+    pub fn get(&self, key: &str) -> Option<&InfoValue> {
+        self.sections
+            .iter()
+            .filter_map(|section| section.get(key))
+            .next()
+    }
+
+    fn get_int(&self, key: &str) -> Option<i64> {
+        self.get(key).and_then(InfoValue::as_int)
+    }
+
+    fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(InfoValue::as_str)
+    }
+
+    /// Returns the version of the server, from `redis_version`.
+    pub fn version(&self) -> Option<&str> {
+        self.get_str("redis_version")
+    }
+
+    /// Returns the number of seconds since the server started, from `uptime_in_seconds`.
+    pub fn uptime(&self) -> Option<i64> {
+        self.get_int("uptime_in_seconds")
+    }
+
+    /// Returns the number of connected clients, from `connected_clients`.
+    pub fn connected_clients(&self) -> Option<i64> {
+        self.get_int("connected_clients")
+    }
+
+    /// Returns the number of bytes allocated, from `used_memory`.
+    pub fn used_memory(&self) -> Option<i64> {
+        self.get_int("used_memory")
+    }
+
+    /// Returns the peak number of bytes allocated, from `used_memory_peak`.
+    pub fn used_memory_peak(&self) -> Option<i64> {
+        self.get_int("used_memory_peak")
+    }
+
+    /// Returns the memory limit in bytes, from `maxmemory`. `0` means no limit.
+    pub fn maxmemory(&self) -> Option<i64> {
+        self.get_int("maxmemory")
+    }
+
+    /// Returns the memory fragmentation ratio, from `mem_fragmentation_ratio`.
+    pub fn mem_fragmentation_ratio(&self) -> Option<f64> {
+        self.get("mem_fragmentation_ratio")
+            .and_then(InfoValue::as_float)
+    }
+
+    /// Returns the replication role, from `role`, i.e. `master` or `slave`.
+    pub fn role(&self) -> Option<&str> {
+        self.get_str("role")
+    }
+
+    /// Returns the number of connected replicas, from `connected_slaves`.
+    pub fn connected_replicas(&self) -> Option<i64> {
+        self.get_int("connected_slaves")
+    }
+
+    /// Returns the replication offset, from `master_repl_offset`.
+    pub fn repl_offset(&self) -> Option<i64> {
+        self.get_int("master_repl_offset")
+    }
+
+    /// Returns the statistics for each database in the `# Keyspace` section.
+    pub fn keyspace(&self) -> Vec<Keyspace> {
+        let section = match self.section("keyspace") {
+            Some(section) => section,
+
+            None => return Vec::new(),
+        };
+
+        section
+            .fields
+            .iter()
+            .filter_map(|(key, value)| {
+                let db = key.strip_prefix("db")?.parse().ok()?;
+
+                let field = |name| value.get(name).and_then(InfoValue::as_int).unwrap_or(0);
+
+                Some(Keyspace {
+                    db,
+                    keys: field("keys"),
+                    expires: field("expires"),
+                    avg_ttl: field("avg_ttl"),
+                })
+            })
+            .collect()
+    }
+}
+
+fn get<'a>(fields: &'a [(String, InfoValue)], key: &str) -> Option<&'a InfoValue> {
+    fields
+        .iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, value)| value)
+}
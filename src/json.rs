@@ -0,0 +1,462 @@
+use std::fmt::Write;
+use std::io::{Error, ErrorKind, Result};
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use super::Value;
+
+impl Value {
+    /// Converts a `Value` to JSON.
+    ///
+    /// Strings become JSON strings, nulls become `null`, and aggregates become arrays. Errors
+    /// become objects of the form `{"error": "..."}`, and big numbers become strings to avoid
+    /// losing precision. Maps become objects if all of their keys are strings, otherwise they
+    /// become arrays of `[key, value]` pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lib_resp::Value;
+    /// let value = Value::array(Some(vec![
+    ///     Value::int(1),
+    ///     Value::b_str(Some("two")),
+    ///     Value::b_str(None::<String>),
+    /// ]));
+    ///
+    /// assert_eq!(value.to_json(), r#"[1,"two",null]"#);
+    /// ```
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+
+        write_json(&mut json, self);
+
+        json
+    }
+
+    /// Converts a JSON document to a `Value`, roughly the reverse of [to_json](Value::to_json).
+    ///
+    /// Strings become bulk strings, `null` becomes a null bulk string, and arrays become arrays.
+    /// Integers become integers, unless they're too big, in which case they become big numbers.
+    /// Other numbers become doubles. Objects of the form `{"error": "..."}` become errors, and
+    /// any other objects become maps.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lib_resp::Value;
+    /// let value = Value::from_json(r#"["SET", "foo", 1]"#).unwrap();
+    ///
+    /// assert_eq!(
+    ///     value,
+    ///     Value::array(Some(vec![
+    ///         Value::b_str(Some("SET")),
+    ///         Value::b_str(Some("foo")),
+    ///         Value::int(1),
+    ///     ]))
+    /// );
+    /// ```
+    pub fn from_json(json: &str) -> Result<Value> {
+        let mut reader = JsonReader {
+            json,
+            chars: json.char_indices().peekable(),
+        };
+
+        let value = reader.read_value()?;
+
+        reader.skip_whitespace();
+
+        match reader.chars.peek() {
+            None => Ok(value),
+
+            Some(_) => Err(reader.error()),
+        }
+    }
+}
+
+struct JsonReader<'a> {
+    json: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl<'a> JsonReader<'a> {
+    fn error(&mut self) -> Error {
+        let offset = match self.chars.peek() {
+            Some(&(i, _)) => i,
+
+            None => self.json.len(),
+        };
+
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Invalid JSON at offset {}", offset),
+        )
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(&(_, ' ')) | Some(&(_, '\t')) | Some(&(_, '\n')) | Some(&(_, '\r')) =
+            self.chars.peek()
+        {
+            self.chars.next();
+        }
+    }
+
+    /// Consumes `c` if it's next.
+    fn eat(&mut self, c: char) -> bool {
+        match self.chars.peek() {
+            Some(&(_, next)) if next == c => {
+                self.chars.next();
+
+                true
+            }
+
+            _ => false,
+        }
+    }
+
+    fn expect(&mut self, literal: &str) -> Result<()> {
+        for c in literal.chars() {
+            if !self.eat(c) {
+                return Err(self.error());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn read_value(&mut self) -> Result<Value> {
+        self.skip_whitespace();
+
+        let c = match self.chars.peek() {
+            Some(&(_, c)) => c,
+
+            None => return Err(self.error()),
+        };
+
+        match c {
+            'n' => self.expect("null").map(|_| Value::BStr(None)),
+
+            't' => self.expect("true").map(|_| Value::Bool(true)),
+
+            'f' => self.expect("false").map(|_| Value::Bool(false)),
+
+            '"' => self.read_str().map(|datum| Value::BStr(Some(datum))),
+
+            '[' => {
+                self.chars.next();
+
+                self.read_items(']', |reader| reader.read_value())
+                    .map(|data| Value::Array(Some(data)))
+            }
+
+            '{' => {
+                self.chars.next();
+
+                let pairs = self.read_items('}', |reader| {
+                    reader.skip_whitespace();
+
+                    let key = reader.read_str()?;
+
+                    reader.skip_whitespace();
+
+                    reader.expect(":")?;
+
+                    Ok((key, reader.read_value()?))
+                })?;
+
+                Ok(match pairs.len() {
+                    1 if pairs[0].0 == "error" => match pairs[0].1 {
+                        Value::BStr(Some(ref datum)) => Value::Err(datum.clone()),
+
+                        _ => object(pairs),
+                    },
+
+                    _ => object(pairs),
+                })
+            }
+
+            '-' | '0'..='9' => self.read_number(),
+
+            _ => Err(self.error()),
+        }
+    }
+
+    /// Reads comma separated items up to `end`.
+    fn read_items<T, F>(&mut self, end: char, mut read_item: F) -> Result<Vec<T>>
+    where
+        F: FnMut(&mut Self) -> Result<T>,
+    {
+        let mut items = Vec::new();
+
+        self.skip_whitespace();
+
+        if self.eat(end) {
+            return Ok(items);
+        }
+
+        loop {
+            items.push(read_item(self)?);
+
+            self.skip_whitespace();
+
+            if self.eat(end) {
+                return Ok(items);
+            }
+
+            self.expect(",")?;
+        }
+    }
+
+    fn read_str(&mut self) -> Result<String> {
+        self.expect("\"")?;
+
+        let mut datum = String::new();
+
+        loop {
+            let c = match self.chars.next() {
+                Some((_, c)) => c,
+
+                None => return Err(self.error()),
+            };
+
+            match c {
+                '"' => return Ok(datum),
+
+                '\\' => {
+                    let escape = match self.chars.next() {
+                        Some((_, escape)) => escape,
+
+                        None => return Err(self.error()),
+                    };
+
+                    datum.push(match escape {
+                        '"' => '"',
+                        '\\' => '\\',
+                        '/' => '/',
+                        'b' => '\u{8}',
+                        'f' => '\u{c}',
+                        'n' => '\n',
+                        'r' => '\r',
+                        't' => '\t',
+
+                        'u' => {
+                            let high = self.read_hex()?;
+
+                            let code = if (0xd800..0xdc00).contains(&high) {
+                                // A surrogate pair
+                                self.expect("\\u")?;
+
+                                let low = self.read_hex()?;
+
+                                if !(0xdc00..0xe000).contains(&low) {
+                                    return Err(self.error());
+                                }
+
+                                0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+                            } else {
+                                high
+                            };
+
+                            match ::std::char::from_u32(code) {
+                                Some(c) => c,
+
+                                None => return Err(self.error()),
+                            }
+                        }
+
+                        _ => return Err(self.error()),
+                    });
+                }
+
+                c if (c as u32) < 0x20 => return Err(self.error()),
+
+                c => datum.push(c),
+            }
+        }
+    }
+
+    fn read_hex(&mut self) -> Result<u32> {
+        let mut code = 0;
+
+        for _ in 0..4 {
+            let digit = match self.chars.peek() {
+                Some(&(_, c)) => c.to_digit(16),
+
+                None => None,
+            };
+
+            match digit {
+                Some(digit) => code = code * 16 + digit,
+
+                None => return Err(self.error()),
+            }
+
+            self.chars.next();
+        }
+
+        Ok(code)
+    }
+
+    fn read_number(&mut self) -> Result<Value> {
+        let start = match self.chars.peek() {
+            Some(&(i, _)) => i,
+
+            None => return Err(self.error()),
+        };
+
+        let mut end = start;
+        let mut integer = true;
+
+        while let Some(&(i, c)) = self.chars.peek() {
+            match c {
+                '0'..='9' | '-' => {}
+
+                '.' | 'e' | 'E' | '+' => integer = false,
+
+                _ => break,
+            }
+
+            end = i + 1;
+
+            self.chars.next();
+        }
+
+        let number = &self.json[start..end];
+
+        if integer {
+            let digits = number.strip_prefix('-').unwrap_or(number);
+
+            if digits.is_empty() || digits[1..].contains('-') {
+                return Err(self.error());
+            }
+
+            return Ok(match number.parse() {
+                Ok(datum) => Value::Int(datum),
+
+                Err(_) => Value::BigNum(number.to_owned()),
+            });
+        }
+
+        match number.parse() {
+            Ok(datum) => Ok(Value::Double(datum)),
+
+            Err(_) => Err(self.error()),
+        }
+    }
+}
+
+fn object(pairs: Vec<(String, Value)>) -> Value {
+    Value::Map(
+        pairs
+            .into_iter()
+            .map(|(key, value)| (Value::BStr(Some(key)), value))
+            .collect(),
+    )
+}
+
+fn write_json(json: &mut String, value: &Value) {
+    match *value {
+        Value::Int(datum) => write!(json, "{}", datum).unwrap(),
+
+        Value::Str(ref datum)
+        | Value::BStr(Some(ref datum))
+        | Value::BigNum(ref datum)
+        | Value::Verbatim(_, ref datum) => write_str(json, datum),
+
+        Value::Err(ref datum) | Value::BErr(ref datum) => {
+            json.push_str(r#"{"error":"#);
+            write_str(json, datum);
+            json.push('}');
+        }
+
+        Value::BStr(None) | Value::Array(None) | Value::Null => json.push_str("null"),
+
+        Value::Bool(datum) => write!(json, "{}", datum).unwrap(),
+
+        // JSON has no representation for infinity or NaN
+        Value::Double(datum) if !datum.is_finite() => write_str(json, &datum.to_string()),
+
+        Value::Double(datum) => write!(json, "{:?}", datum).unwrap(),
+
+        Value::Array(Some(ref data)) | Value::Set(ref data) | Value::Push(ref data) => {
+            write_array(json, data.iter(), write_json)
+        }
+
+        Value::Map(ref pairs) => {
+            let keys: Option<Vec<&str>> = pairs.iter().map(|(key, _)| key_str(key)).collect();
+
+            match keys {
+                Some(keys) => {
+                    json.push('{');
+
+                    for (i, (key, (_, value))) in keys.iter().zip(pairs).enumerate() {
+                        if i != 0 {
+                            json.push(',');
+                        }
+
+                        write_str(json, key);
+                        json.push(':');
+                        write_json(json, value);
+                    }
+
+                    json.push('}');
+                }
+
+                None => write_array(json, pairs.iter(), |json, (key, value)| {
+                    json.push('[');
+                    write_json(json, key);
+                    json.push(',');
+                    write_json(json, value);
+                    json.push(']');
+                }),
+            }
+        }
+    }
+}
+
+fn write_array<I, F>(json: &mut String, items: I, mut write_item: F)
+where
+    I: Iterator,
+    F: FnMut(&mut String, I::Item),
+{
+    json.push('[');
+
+    for (i, item) in items.enumerate() {
+        if i != 0 {
+            json.push(',');
+        }
+
+        write_item(json, item);
+    }
+
+    json.push(']');
+}
+
+fn write_str(json: &mut String, datum: &str) {
+    json.push('"');
+
+    for c in datum.chars() {
+        match c {
+            '"' => json.push_str(r#"\""#),
+            '\\' => json.push_str(r"\\"),
+            '\n' => json.push_str(r"\n"),
+            '\r' => json.push_str(r"\r"),
+            '\t' => json.push_str(r"\t"),
+
+            c if (c as u32) < 0x20 => write!(json, r"\u{:04x}", c as u32).unwrap(),
+
+            c => json.push(c),
+        }
+    }
+
+    json.push('"');
+}
+
+fn key_str(key: &Value) -> Option<&str> {
+    match *key {
+        Value::Str(ref datum) | Value::BStr(Some(ref datum)) | Value::Verbatim(_, ref datum) => {
+            Some(datum)
+        }
+
+        _ => None,
+    }
+}
@@ -1,10 +1,60 @@
+// `is_multiple_of` and `io::Error::other` are too new for the Rust versions supported
+#![allow(clippy::manual_is_multiple_of, clippy::io_other_error)]
+
 #[macro_use]
 extern crate nom;
+#[cfg(feature = "proptest")]
+extern crate proptest;
 
+#[macro_use]
+mod macros;
 mod value;
 mod parser;
 mod decoder;
+mod server_error;
+mod client;
+mod cluster;
+mod pool;
+mod handshake;
+mod info;
+mod aof;
+mod rdb;
+mod replica;
+mod monitor;
+mod json;
+mod args;
+mod proxy;
+mod session;
+mod validate;
+mod access;
+mod visit;
+mod diff;
+mod pretty;
+mod vectored;
+#[cfg(feature = "proptest")]
+mod strategy;
 
-pub use value::Value;
-pub use parser::Parser;
+pub use value::{FromValueError, Value, ValueKind};
+pub use parser::{Parser, Protocol};
 pub use decoder::Decoder;
+pub use server_error::{ErrorKind, Redirect, ServerError};
+pub use client::Client;
+pub use cluster::{key_slot, ClusterClient, SlotMap, SLOT_COUNT};
+pub use pool::{Pool, PoolOptions, PooledClient};
+pub use handshake::{Handshake, HandshakeOptions, ServerInfo};
+pub use info::{Info, InfoSection, InfoValue, Keyspace};
+pub use aof::{repair_aof, rewrite_aof, AofFileType, AofReader, AofWriter, Manifest, ManifestEntry};
+pub use replica::{Psync, Replica, ReplicaOptions, ReplicatedCommand};
+pub use monitor::{Monitor, MonitorClient, MonitorEvent};
+pub use args::split_args;
+pub use proxy::{Action, Exchange, Proxy};
+pub use session::{
+    replay, Mismatch, ReplayOptions, ReplayReport, SessionEntry, SessionReader, SessionWriter,
+};
+pub use validate::{ValueError, ValueErrorKind};
+pub use visit::Visitor;
+pub use diff::Difference;
+pub use pretty::{Pretty, PrettyOptions};
+pub use vectored::VectoredEncoder;
+#[cfg(feature = "proptest")]
+pub use strategy::ValueParams;
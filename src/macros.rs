@@ -0,0 +1,256 @@
+/// Builds a [Value](crate::Value) from a RESP-like literal.
+///
+/// `resp![...]` builds an array, and `resp!{key => value, ...}` builds a map. Their elements may
+/// be:
+///
+/// - `+expr`, a simple string
+/// - `-expr`, an error
+/// - `bulk expr`, a bulk string
+/// - `nil`, a null bulk string, i.e. the RESP2 null
+/// - `null`, a RESP3 null
+/// - `[...]`, a nested array
+/// - `{key => value, ...}`, a nested map
+/// - any other expression, converted with `Value::from`
+///
+/// NOTE: Negative numbers must be parenthesized, e.g. `(-1)`, as `-1` is an error. Literals
+/// whose elements are all single tokens, e.g. numbers, strings, variables, `nil` or `[...]`, are
+/// built in one step. Otherwise each element is two levels of macro recursion, so literals with
+/// more than about 60 elements, or 30 pairs, need a higher `recursion_limit`.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate lib_resp;
+/// # use lib_resp::Value;
+/// # fn main() {
+/// let reply = resp![1, "x", [nil, +"OK", -"ERR bad", (-1)]];
+///
+/// assert_eq!(
+///     reply,
+///     Value::array(Some(vec![
+///         Value::int(1),
+///         Value::b_str(Some("x")),
+///         Value::array(Some(vec![
+///             Value::BStr(None),
+///             Value::str("OK"),
+///             Value::err("ERR bad"),
+///             Value::int(-1),
+///         ])),
+///     ]))
+/// );
+///
+/// let server = "redis";
+///
+/// let hello = resp! {
+///     +"server" => bulk server,
+///     +"proto" => 3,
+///     +"modules" => [],
+/// };
+///
+/// assert_eq!(
+///     hello,
+///     Value::Map(vec![
+///         (Value::str("server"), Value::b_str(Some("redis"))),
+///         (Value::str("proto"), Value::int(3)),
+///         (Value::str("modules"), Value::array(Some(vec![]))),
+///     ])
+/// );
+/// # }
+/// ```
+#[macro_export]
+macro_rules! resp {
+    // Builds a single token element, without recursing on the elements around it
+    (@one nil) => {
+        $crate::Value::BStr(None)
+    };
+    (@one null) => {
+        $crate::Value::Null
+    };
+    (@one [$($array:tt)*]) => {
+        $crate::resp!(@items $($array)*)
+    };
+    (@one {$($map:tt)*}) => {
+        $crate::resp!(@pairs $($map)*)
+    };
+    (@one $e:tt) => {
+        $crate::Value::from($e)
+    };
+
+    // The items of an array or map, in one step if they're all single tokens, otherwise one at a
+    // time
+    (@items $($elem:tt),* $(,)?) => {
+        $crate::Value::Array(Some(vec![$($crate::resp!(@one $elem)),*]))
+    };
+    (@items $($tokens:tt)*) => {
+        $crate::resp!(@array [] , $($tokens)*)
+    };
+    (@pairs $($key:tt => $value:tt),* $(,)?) => {
+        $crate::Value::Map(vec![$(($crate::resp!(@one $key), $crate::resp!(@one $value))),*])
+    };
+    (@pairs $($tokens:tt)*) => {
+        $crate::resp!(@map [] , $($tokens)*)
+    };
+
+    // Parses the element at the start of the tokens, then continues with `@$next`, passing on
+    // `$state`, the element, and the tokens after it.
+    (@elem $next:ident $state:tt nil $($rest:tt)*) => {
+        $crate::resp!(@$next $state ($crate::Value::BStr(None)) $($rest)*)
+    };
+    (@elem $next:ident $state:tt null $($rest:tt)*) => {
+        $crate::resp!(@$next $state ($crate::Value::Null) $($rest)*)
+    };
+    (@elem $next:ident $state:tt [$($array:tt)*] $($rest:tt)*) => {
+        $crate::resp!(@$next $state ($crate::resp!(@items $($array)*)) $($rest)*)
+    };
+    (@elem $next:ident $state:tt {$($map:tt)*} $($rest:tt)*) => {
+        $crate::resp!(@$next $state ($crate::resp!(@pairs $($map)*)) $($rest)*)
+    };
+    (@elem $next:ident $state:tt + $e:expr) => {
+        $crate::resp!(@$next $state ($crate::Value::Str(::std::string::String::from($e))))
+    };
+    (@elem $next:ident $state:tt + $e:expr , $($rest:tt)*) => {
+        $crate::resp!(@$next $state ($crate::Value::Str(::std::string::String::from($e))) , $($rest)*)
+    };
+    (@elem $next:ident $state:tt + $e:expr => $($rest:tt)*) => {
+        $crate::resp!(@$next $state ($crate::Value::Str(::std::string::String::from($e))) => $($rest)*)
+    };
+    (@elem $next:ident $state:tt - $e:expr) => {
+        $crate::resp!(@$next $state ($crate::Value::Err(::std::string::String::from($e))))
+    };
+    (@elem $next:ident $state:tt - $e:expr , $($rest:tt)*) => {
+        $crate::resp!(@$next $state ($crate::Value::Err(::std::string::String::from($e))) , $($rest)*)
+    };
+    (@elem $next:ident $state:tt - $e:expr => $($rest:tt)*) => {
+        $crate::resp!(@$next $state ($crate::Value::Err(::std::string::String::from($e))) => $($rest)*)
+    };
+    (@elem $next:ident $state:tt bulk $e:expr) => {
+        $crate::resp!(@$next $state ($crate::Value::BStr(Some(::std::string::String::from($e)))))
+    };
+    (@elem $next:ident $state:tt bulk $e:expr , $($rest:tt)*) => {
+        $crate::resp!(@$next $state ($crate::Value::BStr(Some(::std::string::String::from($e)))) , $($rest)*)
+    };
+    (@elem $next:ident $state:tt bulk $e:expr => $($rest:tt)*) => {
+        $crate::resp!(@$next $state ($crate::Value::BStr(Some(::std::string::String::from($e)))) => $($rest)*)
+    };
+    (@elem $next:ident $state:tt $e:expr) => {
+        $crate::resp!(@$next $state ($crate::Value::from($e)))
+    };
+    (@elem $next:ident $state:tt $e:expr , $($rest:tt)*) => {
+        $crate::resp!(@$next $state ($crate::Value::from($e)) , $($rest)*)
+    };
+    (@elem $next:ident $state:tt $e:expr => $($rest:tt)*) => {
+        $crate::resp!(@$next $state ($crate::Value::from($e)) => $($rest)*)
+    };
+
+    // Arrays, the elements are preceded by commas, including the first
+    (@array [$($elems:expr,)*]) => {
+        $crate::Value::Array(Some(vec![$($elems,)*]))
+    };
+    (@array [$($elems:expr,)*] ,) => {
+        $crate::resp!(@array [$($elems,)*])
+    };
+    (@array [$($elems:expr,)*] , $($rest:tt)+) => {
+        $crate::resp!(@elem array_elem [$($elems,)*] $($rest)+)
+    };
+    // Goes straight on to the next element, to keep the recursion shallow
+    (@array_elem [$($elems:expr,)*] ($elem:expr) , $($rest:tt)+) => {
+        $crate::resp!(@elem array_elem [$($elems,)* $elem,] $($rest)+)
+    };
+    (@array_elem [$($elems:expr,)*] ($elem:expr) $($rest:tt)*) => {
+        $crate::resp!(@array [$($elems,)* $elem,] $($rest)*)
+    };
+
+    // Maps, the pairs are preceded by commas, including the first
+    (@map [$(($key:expr, $value:expr),)*]) => {
+        $crate::Value::Map(vec![$(($key, $value),)*])
+    };
+    (@map [$($pairs:tt)*] ,) => {
+        $crate::resp!(@map [$($pairs)*])
+    };
+    (@map [$($pairs:tt)*] , $($rest:tt)+) => {
+        $crate::resp!(@elem map_key [$($pairs)*] $($rest)+)
+    };
+    (@map_key [$($pairs:tt)*] ($key:expr) => $($rest:tt)+) => {
+        $crate::resp!(@elem map_value ([$($pairs)*] $key) $($rest)+)
+    };
+    (@map_value ([$($pairs:tt)*] $key:expr) ($value:expr) , $($rest:tt)+) => {
+        $crate::resp!(@elem map_key [$($pairs)* ($key, $value),] $($rest)+)
+    };
+    (@map_value ([$($pairs:tt)*] $key:expr) ($value:expr) $($rest:tt)*) => {
+        $crate::resp!(@map [$($pairs)* ($key, $value),] $($rest)*)
+    };
+
+    // The first element decides whether it's an array or a map
+    (@top () ($first:expr) => $($rest:tt)+) => {
+        $crate::resp!(@elem map_value ([] $first) $($rest)+)
+    };
+    (@top () ($first:expr) $($rest:tt)*) => {
+        $crate::resp!(@array [$first,] $($rest)*)
+    };
+
+    () => {
+        $crate::Value::Array(Some(vec![]))
+    };
+    ($($elem:tt),+ $(,)?) => {
+        $crate::resp!(@items $($elem),+)
+    };
+    ($($key:tt => $value:tt),+ $(,)?) => {
+        $crate::resp!(@pairs $($key => $value),+)
+    };
+    ($($tokens:tt)+) => {
+        $crate::resp!(@elem top () $($tokens)+)
+    };
+}
+
+/// Asserts that two [Value](crate::Value)s are equivalent, like `assert_eq!`, but panics with a list of
+/// their [differences](crate::Value::diff) rather than their `Debug` representations.
+///
+/// NOTE: Sets and maps are compared regardless of order.
+///
+/// # Examples
+///
+/// ```should_panic
+/// # #[macro_use]
+/// # extern crate lib_resp;
+/// # use lib_resp::Value;
+/// # fn main() {
+/// let reply = Value::array(Some(vec![Value::int(1), Value::int(2)]));
+///
+/// // assertion failed: `(left == right)`
+/// //   ~ /1: Int(2) -> Int(3)
+/// assert_resp_eq!(reply, resp![1, 3]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assert_resp_eq {
+    ($left:expr, $right:expr) => {
+        $crate::assert_resp_eq!($left, $right, "")
+    };
+    ($left:expr, $right:expr,) => {
+        $crate::assert_resp_eq!($left, $right, "")
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                let differences = $crate::Value::diff(left, right);
+
+                if !differences.is_empty() {
+                    let message = format!($($arg)+);
+
+                    let mut report = if message.is_empty() {
+                        "assertion failed: `(left == right)`".to_owned()
+                    } else {
+                        format!("assertion failed: `(left == right)`: {}", message)
+                    };
+
+                    for difference in &differences {
+                        report.push_str(&format!("\n  {}", difference));
+                    }
+
+                    panic!("{}", report);
+                }
+            }
+        }
+    };
+}
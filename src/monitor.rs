@@ -0,0 +1,192 @@
+use std::io::{Error, ErrorKind, Result};
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use super::handshake::check;
+use super::{Client, Value};
+
+/// The client which issued a command seen by `MONITOR`.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum MonitorClient {
+    /// A client connected over TCP.
+    Tcp(SocketAddr),
+    /// A client connected over a unix socket, with the path of the socket.
+    Unix(String),
+    /// A Lua script or function.
+    Lua,
+}
+
+/// A command seen by `MONITOR`.
+///
+/// # Examples
+///
+/// ```
+/// # use lib_resp::{MonitorClient, MonitorEvent};
+/// let event =
+///     MonitorEvent::parse(r#"1697000000.123456 [0 127.0.0.1:5000] "SET" "k" "v\x00""#).unwrap();
+///
+/// assert_eq!(event.db, 0);
+/// assert_eq!(event.client, MonitorClient::Tcp("127.0.0.1:5000".parse().unwrap()));
+/// assert_eq!(event.args, vec![b"SET".to_vec(), b"k".to_vec(), b"v\0".to_vec()]);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct MonitorEvent {
+    /// When the command was run, since the unix epoch.
+    pub timestamp: Duration,
+    /// The database the command was run against.
+    pub db: i64,
+    /// The client which ran the command.
+    pub client: MonitorClient,
+    /// The command and its arguments.
+    pub args: Vec<Vec<u8>>,
+}
+
+impl MonitorEvent {
+    /// Parses a line of `MONITOR` output, returning `None` if it's malformed.
+    pub fn parse(line: &str) -> Option<Self> {
+        let (timestamp, rest) = line.split_once(' ')?;
+        let (secs, micros) = timestamp.split_once('.')?;
+
+        if micros.len() != 6 {
+            return None;
+        }
+
+        let timestamp =
+            Duration::from_secs(secs.parse().ok()?) + Duration::from_micros(micros.parse().ok()?);
+
+        let (source, rest) = rest.strip_prefix('[')?.split_once("] ")?;
+        let (db, client) = source.split_once(' ')?;
+
+        let client = if client == "lua" {
+            MonitorClient::Lua
+        } else if let Some(path) = client.strip_prefix("unix:") {
+            MonitorClient::Unix(path.to_owned())
+        } else {
+            MonitorClient::Tcp(client.parse().ok()?)
+        };
+
+        Some(MonitorEvent {
+            timestamp,
+            db: db.parse().ok()?,
+            client,
+            args: parse_args(rest)?,
+        })
+    }
+
+    /// Reads an event from a value, which must be a simple string.
+    pub fn from_value(value: &Value) -> Option<Self> {
+        match *value {
+            Value::Str(ref line) => MonitorEvent::parse(line),
+
+            _ => None,
+        }
+    }
+}
+
+/// Parses the space separated, quoted arguments of a command.
+fn parse_args(text: &str) -> Option<Vec<Vec<u8>>> {
+    let mut args = Vec::new();
+    let mut bytes = text.bytes();
+
+    loop {
+        match bytes.next() {
+            Some(b'"') => {}
+
+            None if !args.is_empty() => return Some(args),
+
+            _ => return None,
+        }
+
+        let mut arg = Vec::new();
+
+        loop {
+            match bytes.next()? {
+                b'"' => break,
+
+                b'\\' => arg.push(match bytes.next()? {
+                    b'n' => b'\n',
+                    b'r' => b'\r',
+                    b't' => b'\t',
+                    b'a' => 0x07,
+                    b'b' => 0x08,
+
+                    b'x' => {
+                        let hex = [bytes.next()?, bytes.next()?];
+
+                        u8::from_str_radix(::std::str::from_utf8(&hex).ok()?, 16).ok()?
+                    }
+
+                    // `\\` and `\"`
+                    byte => byte,
+                }),
+
+                byte => arg.push(byte),
+            }
+        }
+
+        args.push(arg);
+
+        match bytes.next() {
+            Some(b' ') => {}
+
+            None => return Some(args),
+
+            _ => return None,
+        }
+    }
+}
+
+/// Iterator over the commands seen by a connection in `MONITOR` mode.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use lib_resp::Client;
+/// let client = Client::connect("127.0.0.1:6379").unwrap();
+///
+/// for event in client.monitor().unwrap() {
+///     println!("{:?}", event.unwrap());
+/// }
+/// ```
+pub struct Monitor {
+    client: Client,
+}
+
+impl Monitor {
+    /// Waits for the next command, returning `None` if the connection is closed.
+    pub fn read_event(&mut self) -> Result<Option<MonitorEvent>> {
+        let value = match self.client.decoder_mut().decode()? {
+            Some(value) => value,
+
+            None => return Ok(None),
+        };
+
+        MonitorEvent::from_value(&value)
+            .map(Some)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Invalid MONITOR output"))
+    }
+}
+
+impl Iterator for Monitor {
+    type Item = Result<MonitorEvent>;
+
+    fn next(&mut self) -> Option<Result<MonitorEvent>> {
+        match self.read_event() {
+            Ok(Some(event)) => Some(Ok(event)),
+
+            Ok(None) => None,
+
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+impl Client {
+    /// Puts the connection in `MONITOR` mode, after which it can only be used to read the
+    /// commands run by the server.
+    pub fn monitor(mut self) -> Result<Monitor> {
+        check(&self.command(&["MONITOR"])?)?;
+
+        Ok(Monitor { client: self })
+    }
+}
@@ -1,81 +1,237 @@
 use super::Value;
 use std::str::FromStr;
-use nom::{crlf, digit, not_line_ending};
+use nom::{crlf, digit, not_line_ending, Context, Err, ErrorKind, IResult};
+
+/// How deeply aggregates may be nested, deeper values are rejected rather than risk overflowing
+/// the stack.
+pub(crate) const MAX_DEPTH: usize = 128;
+
+/// Version of the protocol spoken on a connection.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum Protocol {
+    /// RESP2, which only has integers, simple strings, errors, bulk strings and arrays.
+    #[default]
+    Resp2,
+    /// RESP3, which adds nulls, booleans, doubles, big numbers, blob errors, verbatim strings,
+    /// maps, sets and pushes.
+    ///
+    /// NOTE: Attributes aren't supported.
+    Resp3,
+}
+
+named!(
+    read_line<&str>,
+    do_parse!(line: map_res!(not_line_ending, ::std::str::from_utf8) >> crlf >> (line))
+);
+
+// The sign is parsed with the digits, so `i64::MIN` doesn't overflow
+named!(
+    int<i64>,
+    map_res!(
+        map_res!(recognize!(pair!(opt!(tag!("-")), digit)), ::std::str::from_utf8),
+        i64::from_str
+    )
+);
+
+named!(
+    parse_int<Value>,
+    preceded!(
+        tag!(":"),
+        do_parse!(datum: int >> crlf >> (Value::Int(datum)))
+    )
+);
+
+named!(
+    parse_str<Value>,
+    preceded!(
+        tag!("+"),
+        do_parse!(datum: read_line >> (Value::str(datum)))
+    )
+);
+
+named!(
+    parse_err<Value>,
+    preceded!(
+        tag!("-"),
+        do_parse!(datum: read_line >> (Value::err(datum)))
+    )
+);
+
+named!(
+    parse_bstr<Value>,
+    preceded!(
+        tag!("$"),
+        do_parse!(
+            len: int >> crlf >> datum: cond_with_error!(len > -1, take_str!(len))
+                >> cond_with_error!(len > -1, crlf)
+                >> (Value::b_str(datum))
+        )
+    )
+);
+
+named_args!(
+    parse_array(protocol: Protocol, depth: usize)<Value>,
+    preceded!(
+        tag!("*"),
+        do_parse!(
+            len: int >> crlf
+                >> data: cond_with_error!(len > -1, count!(call!(parse_value, protocol, depth + 1), len as usize))
+                >> (Value::Array(data))
+        )
+    )
+);
+
+named!(
+    parse_null<Value>,
+    do_parse!(tag!("_") >> crlf >> (Value::Null))
+);
+
+named!(
+    parse_bool<Value>,
+    preceded!(
+        tag!("#"),
+        do_parse!(
+            datum: alt!(value!(true, tag!("t")) | value!(false, tag!("f"))) >> crlf
+                >> (Value::Bool(datum))
+        )
+    )
+);
+
+named!(
+    parse_double<Value>,
+    preceded!(
+        tag!(","),
+        do_parse!(datum: map_res!(read_line, f64::from_str) >> (Value::Double(datum)))
+    )
+);
+
+named!(
+    parse_big_num<Value>,
+    preceded!(
+        tag!("("),
+        do_parse!(
+            datum: verify!(read_line, |datum: &str| {
+                let digits = datum.strip_prefix('-').unwrap_or(datum);
+
+                !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+            }) >> (Value::BigNum(datum.to_owned()))
+        )
+    )
+);
+
+named!(
+    parse_berr<Value>,
+    preceded!(
+        tag!("!"),
+        do_parse!(
+            len: verify!(int, |len: i64| len > -1) >> crlf >> datum: take_str!(len) >> crlf
+                >> (Value::BErr(datum.to_owned()))
+        )
+    )
+);
+
+named!(
+    parse_verbatim<Value>,
+    preceded!(
+        tag!("="),
+        do_parse!(
+            len: verify!(int, |len: i64| len > 3) >> crlf >> format: take_str!(3) >> tag!(":")
+                >> datum: take_str!(len - 4) >> crlf
+                >> (Value::Verbatim(format.to_owned(), datum.to_owned()))
+        )
+    )
+);
+
+named_args!(
+    parse_map(protocol: Protocol, depth: usize)<Value>,
+    preceded!(
+        tag!("%"),
+        do_parse!(
+            len: verify!(int, |len: i64| len > -1) >> crlf
+                >> data: count!(
+                    pair!(
+                        call!(parse_value, protocol, depth + 1),
+                        call!(parse_value, protocol, depth + 1)
+                    ),
+                    len as usize
+                ) >> (Value::Map(data))
+        )
+    )
+);
+
+named_args!(
+    parse_set(protocol: Protocol, depth: usize)<Value>,
+    preceded!(
+        tag!("~"),
+        do_parse!(
+            len: verify!(int, |len: i64| len > -1) >> crlf
+                >> data: count!(call!(parse_value, protocol, depth + 1), len as usize)
+                >> (Value::Set(data))
+        )
+    )
+);
+
+named_args!(
+    parse_push(protocol: Protocol, depth: usize)<Value>,
+    preceded!(
+        tag!(">"),
+        do_parse!(
+            len: verify!(int, |len: i64| len > -1) >> crlf
+                >> data: count!(call!(parse_value, protocol, depth + 1), len as usize)
+                >> (Value::Push(data))
+        )
+    )
+);
+
+fn parse_value(data: &[u8], protocol: Protocol, depth: usize) -> IResult<&[u8], Value> {
+    if depth > MAX_DEPTH {
+        return Err(Err::Failure(Context::Code(data, ErrorKind::TooLarge)));
+    }
+
+    match protocol {
+        Protocol::Resp2 => alt!(
+            data,
+            parse_int | parse_str | parse_err | parse_bstr | call!(parse_array, protocol, depth)
+        ),
+
+        Protocol::Resp3 => alt!(
+            data,
+            parse_int | parse_str | parse_err | parse_bstr | call!(parse_array, protocol, depth)
+                | parse_null | parse_bool | parse_double | parse_big_num | parse_berr
+                | parse_verbatim | call!(parse_map, protocol, depth) | call!(parse_set, protocol, depth)
+                | call!(parse_push, protocol, depth)
+        ),
+    }
+}
 
 /// Core parser implementation.
+///
+/// NOTE: Values with aggregates nested more than 128 deep are rejected with an error.
 pub struct Parser;
 
 impl Parser {
-    /// Parses RESP from a byte buffer.
+    /// Parses RESP2 from a byte buffer.
     pub fn parse(data: &[u8]) -> Result<(&[u8], Value), ::nom::Err<&[u8]>> {
-        named!(
-            read_line<&str>,
-            do_parse!(line: map_res!(not_line_ending, ::std::str::from_utf8) >> crlf >> (line))
-        );
-
-        named!(
-            int<i64>,
-            do_parse!(
-                neg: opt!(tag!("-"))
-                    >> value: map_res!(map_res!(digit, ::std::str::from_utf8), i64::from_str)
-                    >> (if neg == None { value } else { -value })
-            )
-        );
-
-        named!(
-            parse_int<Value>,
-            preceded!(
-                tag!(":"),
-                do_parse!(datum: int >> crlf >> (Value::Int(datum)))
-            )
-        );
-
-        named!(
-            parse_str<Value>,
-            preceded!(
-                tag!("+"),
-                do_parse!(datum: read_line >> (Value::str(datum)))
-            )
-        );
-
-        named!(
-            parse_err<Value>,
-            preceded!(
-                tag!("-"),
-                do_parse!(datum: read_line >> (Value::err(datum)))
-            )
-        );
-
-        named!(
-            parse_bstr<Value>,
-            preceded!(
-                tag!("$"),
-                do_parse!(
-                    len: int >> crlf >> datum: cond_with_error!(len > -1, take_str!(len))
-                        >> cond_with_error!(len > -1, crlf)
-                        >> (Value::b_str(datum))
-                )
-            )
-        );
-
-        named!(
-            parse_array<Value>,
-            preceded!(
-                tag!("*"),
-                do_parse!(
-                    len: int >> crlf
-                        >> data: cond_with_error!(len > -1, count!(parse_resp, len as usize))
-                        >> (Value::Array(data))
-                )
-            )
-        );
-
-        named!(
-            parse_resp<Value>,
-            alt!(parse_int | parse_str | parse_err | parse_bstr | parse_array)
-        );
-
-        parse_resp(data)
+        parse_value(data, Protocol::Resp2, 0)
+    }
+
+    /// Parses a specific version of RESP from a byte buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lib_resp::{Parser, Protocol, Value};
+    /// let (_, value) = Parser::parse_protocol(b"#t\r\n", Protocol::Resp3).unwrap();
+    ///
+    /// assert_eq!(value, Value::Bool(true));
+    ///
+    /// assert!(Parser::parse_protocol(b"#t\r\n", Protocol::Resp2).is_err());
+    /// ```
+    pub fn parse_protocol(
+        data: &[u8],
+        protocol: Protocol,
+    ) -> Result<(&[u8], Value), ::nom::Err<&[u8]>> {
+        parse_value(data, protocol, 0)
     }
 
     /// Parses a RESP encoded string.
@@ -0,0 +1,426 @@
+use std::collections::VecDeque;
+use std::io::{Error, ErrorKind, Result};
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, Weak};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::{Client, Value};
+
+/// Options for a [Pool](Pool).
+#[derive(Clone, Debug)]
+pub struct PoolOptions {
+    /// The number of connections kept open, even when idle.
+    pub min_size: usize,
+    /// The maximum number of connections, idle or checked out.
+    pub max_size: usize,
+    /// How long a checkout waits for a connection before timing out.
+    ///
+    /// NOTE: Checking a connection with `PING` isn't cut short by this, so a checkout may take up
+    /// to `check_timeout` longer.
+    pub checkout_timeout: Duration,
+    /// How long a connection may be idle before it's closed, connections below `min_size` are
+    /// never closed for being idle.
+    pub idle_timeout: Option<Duration>,
+    /// Whether connections are checked with `PING` before being checked out.
+    pub test_on_checkout: bool,
+    /// How often idle connections are checked with `PING` by a background thread.
+    pub health_check_interval: Option<Duration>,
+    /// How long to wait for the reply to a `PING` check, or for the unread replies of a returned
+    /// connection, before closing the connection, must be non-zero.
+    pub check_timeout: Duration,
+}
+
+impl Default for PoolOptions {
+    fn default() -> Self {
+        PoolOptions {
+            min_size: 0,
+            max_size: 8,
+            checkout_timeout: Duration::from_secs(30),
+            idle_timeout: Some(Duration::from_secs(600)),
+            test_on_checkout: true,
+            health_check_interval: None,
+            check_timeout: Duration::from_secs(1),
+        }
+    }
+}
+
+struct Idle {
+    client: Client,
+    since: Instant,
+}
+
+struct State {
+    idle: VecDeque<Idle>,
+    // Idle, checked out, and connecting connections
+    size: usize,
+}
+
+struct Inner {
+    addrs: Vec<SocketAddr>,
+    options: PoolOptions,
+    state: Mutex<State>,
+    returned: Condvar,
+}
+
+/// Thread-safe pool of [Client](Client) connections to a single server.
+///
+/// Connections are returned to the pool when their [PooledClient](PooledClient) is dropped.
+/// Connections returned with outstanding replies have them read and discarded, waiting up to
+/// `check_timeout`, so they're never handed to the next user. If that fails, or there are unread
+/// replies which weren't asked for, the connection is closed. Closed connections are replaced to
+/// keep `min_size` by the next checkout or [maintain](Pool::maintain), rather than when they're
+/// returned.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use lib_resp::{Pool, PoolOptions};
+/// let pool = Pool::new("127.0.0.1:6379", PoolOptions::default()).unwrap();
+///
+/// let mut client = pool.get().unwrap();
+///
+/// println!("{}", client.command(&["GET", "foo"]).unwrap());
+/// ```
+#[derive(Clone)]
+pub struct Pool {
+    inner: Arc<Inner>,
+}
+
+impl Pool {
+    /// Constructs a new pool, opening `min_size` connections up front.
+    pub fn new<A: ToSocketAddrs>(addr: A, options: PoolOptions) -> Result<Self> {
+        if options.max_size == 0 || options.min_size > options.max_size {
+            return Err(Error::new(ErrorKind::InvalidInput, "Invalid pool size"));
+        }
+
+        if options.check_timeout == Duration::from_secs(0) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The check timeout must be non-zero",
+            ));
+        }
+
+        let pool = Pool {
+            inner: Arc::new(Inner {
+                addrs: addr.to_socket_addrs()?.collect(),
+                options,
+                state: Mutex::new(State {
+                    idle: VecDeque::new(),
+                    size: 0,
+                }),
+                returned: Condvar::new(),
+            }),
+        };
+
+        pool.inner.fill()?;
+
+        if let Some(interval) = pool.inner.options.health_check_interval {
+            let inner = Arc::downgrade(&pool.inner);
+
+            thread::spawn(move || health_check_loop(&inner, interval));
+        }
+
+        Ok(pool)
+    }
+
+    /// Checks out a connection, waiting up to `checkout_timeout` for one to become available.
+    pub fn get(&self) -> Result<PooledClient> {
+        let deadline = Instant::now() + self.inner.options.checkout_timeout;
+
+        // Replace connections closed since the last checkout, failing here is fine as a
+        // connection is opened below if needed
+        let _ = self.inner.fill();
+
+        let mut state = self.inner.lock();
+
+        loop {
+            self.inner.evict_expired(&mut state);
+
+            // Most recently used first, so that surplus connections can expire
+            if let Some(Idle { mut client, .. }) = state.idle.pop_back() {
+                drop(state);
+
+                if !self.inner.options.test_on_checkout
+                    || is_healthy(&mut client, self.inner.options.check_timeout)
+                {
+                    return Ok(self.wrap(client));
+                }
+
+                state = self.inner.lock();
+
+                state.size -= 1;
+
+                continue;
+            }
+
+            if state.size < self.inner.options.max_size {
+                state.size += 1;
+
+                drop(state);
+
+                return match self.inner.connect() {
+                    Ok(client) => Ok(self.wrap(client)),
+
+                    Err(e) => {
+                        self.inner.lock().size -= 1;
+
+                        self.inner.returned.notify_one();
+
+                        Err(e)
+                    }
+                };
+            }
+
+            let now = Instant::now();
+
+            if now >= deadline {
+                return Err(Error::new(
+                    ErrorKind::TimedOut,
+                    "Timed out waiting for a connection",
+                ));
+            }
+
+            state = self
+                .inner
+                .returned
+                .wait_timeout(state, deadline - now)
+                .unwrap()
+                .0;
+        }
+    }
+
+    /// Closes expired idle connections, checks the rest with `PING`, then opens connections
+    /// until there are at least `min_size`.
+    ///
+    /// NOTE: This is done periodically if `health_check_interval` is set.
+    pub fn maintain(&self) -> Result<()> {
+        self.inner.maintain()
+    }
+
+    /// Returns the number of open connections, idle or checked out.
+    pub fn size(&self) -> usize {
+        self.inner.lock().size
+    }
+
+    /// Returns the number of idle connections.
+    pub fn idle(&self) -> usize {
+        self.inner.lock().idle.len()
+    }
+
+    fn wrap(&self, client: Client) -> PooledClient {
+        PooledClient {
+            client: Some(client),
+            pool: self.inner.clone(),
+        }
+    }
+}
+
+impl Inner {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap()
+    }
+
+    fn connect(&self) -> Result<Client> {
+        Client::connect(&self.addrs[..])
+    }
+
+    /// Opens connections until there are at least `min_size`.
+    fn fill(&self) -> Result<()> {
+        loop {
+            {
+                let mut state = self.lock();
+
+                if state.size >= self.options.min_size {
+                    return Ok(());
+                }
+
+                state.size += 1;
+            }
+
+            match self.connect() {
+                Ok(client) => {
+                    self.lock().idle.push_back(Idle {
+                        client,
+                        since: Instant::now(),
+                    });
+
+                    self.returned.notify_one();
+                }
+
+                Err(e) => {
+                    self.lock().size -= 1;
+
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    fn evict_expired(&self, state: &mut State) {
+        let idle_timeout = match self.options.idle_timeout {
+            Some(idle_timeout) => idle_timeout,
+
+            None => return,
+        };
+
+        // The least recently used connections are at the front
+        while state.size > self.options.min_size {
+            match state.idle.front() {
+                Some(idle) if idle.since.elapsed() >= idle_timeout => {}
+
+                _ => break,
+            }
+
+            state.idle.pop_front();
+
+            state.size -= 1;
+        }
+    }
+
+    fn maintain(&self) -> Result<()> {
+        let idle: Vec<Idle> = {
+            let mut state = self.lock();
+
+            self.evict_expired(&mut state);
+
+            state.idle.drain(..).collect()
+        };
+
+        let checked = idle.len();
+
+        let healthy: Vec<Idle> = idle
+            .into_iter()
+            .filter_map(|mut idle| {
+                if is_healthy(&mut idle.client, self.options.check_timeout) {
+                    Some(idle)
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        {
+            let mut state = self.lock();
+
+            state.size -= checked - healthy.len();
+
+            // Connections returned in the meantime were used more recently
+            for idle in healthy.into_iter().rev() {
+                state.idle.push_front(idle);
+            }
+        }
+
+        self.returned.notify_all();
+
+        self.fill()
+    }
+
+    /// Returns a connection to the pool, discarding any replies it's waiting for, or closes it if
+    /// it can't be reused.
+    fn put(&self, mut client: Client) {
+        let reusable = client.is_idle()
+            || (client.drain(self.options.check_timeout).is_ok() && client.is_idle());
+
+        if !reusable {
+            return self.close();
+        }
+
+        self.lock().idle.push_back(Idle {
+            client,
+            since: Instant::now(),
+        });
+
+        self.returned.notify_one();
+    }
+
+    /// Accounts for a closed connection.
+    ///
+    /// NOTE: This doesn't replace the connection, as connecting could block the caller, e.g. the
+    /// drop of a [PooledClient](PooledClient).
+    fn close(&self) {
+        self.lock().size -= 1;
+
+        self.returned.notify_one();
+    }
+}
+
+/// Checks a connection with `PING`, waiting up to `timeout` for the reply.
+fn is_healthy(client: &mut Client, timeout: Duration) -> bool {
+    if !client.is_idle() {
+        return false;
+    }
+
+    let previous = match client.get_ref().read_timeout() {
+        Ok(previous) => previous,
+
+        Err(_) => return false,
+    };
+
+    if client.get_ref().set_read_timeout(Some(timeout)).is_err() {
+        return false;
+    }
+
+    let pong = match client.command(&["PING"]) {
+        Ok(Value::Str(ref reply)) => reply == "PONG",
+
+        _ => false,
+    };
+
+    pong && client.get_ref().set_read_timeout(previous).is_ok()
+}
+
+fn health_check_loop(inner: &Weak<Inner>, interval: Duration) {
+    loop {
+        thread::sleep(interval);
+
+        match inner.upgrade() {
+            Some(inner) => {
+                let _ = inner.maintain();
+            }
+
+            // The pool has been dropped
+            None => return,
+        }
+    }
+}
+
+/// A connection checked out of a [Pool](Pool).
+///
+/// The connection is returned to the pool when dropped.
+pub struct PooledClient {
+    client: Option<Client>,
+    pool: Arc<Inner>,
+}
+
+impl PooledClient {
+    /// Closes the connection instead of returning it to the pool.
+    pub fn discard(mut self) {
+        self.client.take();
+
+        self.pool.close();
+    }
+}
+
+impl Deref for PooledClient {
+    type Target = Client;
+
+    fn deref(&self) -> &Client {
+        self.client.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for PooledClient {
+    fn deref_mut(&mut self) -> &mut Client {
+        self.client.as_mut().unwrap()
+    }
+}
+
+impl Drop for PooledClient {
+    fn drop(&mut self) {
+        if let Some(client) = self.client.take() {
+            self.pool.put(client);
+        }
+    }
+}
@@ -0,0 +1,366 @@
+use std::fmt::{Display, Formatter, Result as FmtResult, Write};
+
+use super::Value;
+
+// ANSI colors, by type
+const COLOR_NUMBER: &str = "33";
+const COLOR_STRING: &str = "32";
+const COLOR_ERROR: &str = "31";
+const COLOR_BOOL: &str = "35";
+const COLOR_DIM: &str = "90";
+
+/// Options for [pretty printing](Value::pretty) a [Value](Value).
+#[derive(Clone, Debug, Default)]
+pub struct PrettyOptions {
+    /// The maximum number of bytes shown of a string, `None` for no limit.
+    pub max_string_len: Option<usize>,
+    /// The maximum number of items shown of an aggregate, or pairs of a map, `None` for no limit.
+    pub max_items: Option<usize>,
+    /// Whether to color values by type, with ANSI escape codes.
+    pub colors: bool,
+    /// Whether to format like `redis-cli --raw`, with one unquoted string or number per line and
+    /// no types or indices.
+    ///
+    /// NOTE: Colors are never used in raw mode.
+    pub raw: bool,
+}
+
+/// A [Value](Value) formatted like `redis-cli` does, see [Value::pretty](Value::pretty).
+pub struct Pretty<'a> {
+    value: &'a Value,
+    options: &'a PrettyOptions,
+}
+
+impl Value {
+    /// Formats a `Value` over multiple lines, like `redis-cli` does, with nested aggregates
+    /// indented under their index.
+    ///
+    /// Bulk strings are quoted, with quotes, backslashes, control characters and non-ASCII bytes
+    /// escaped. Strings and aggregates longer than the limits in the `options` are cut short with
+    /// `… N more`.
+    ///
+    /// Nil replies, RESP2 or RESP3, are shown as `(nil)`, and empty aggregates by their type, e.g.
+    /// `(empty array)` or `(empty hash)`. This is also how `Value` implements `Display`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lib_resp::{PrettyOptions, Value};
+    /// let value = Value::array(Some(vec![
+    ///     Value::array(Some(vec![Value::b_str(Some("a")), Value::int(1)])),
+    ///     Value::b_str(Some("caf\u{e9}")),
+    /// ]));
+    ///
+    /// assert_eq!(
+    ///     value.pretty(&PrettyOptions::default()).to_string(),
+    ///     "1) 1) \"a\"\n   2) (integer) 1\n2) \"caf\\xc3\\xa9\""
+    /// );
+    ///
+    /// let options = PrettyOptions {
+    ///     max_items: Some(1),
+    ///     ..PrettyOptions::default()
+    /// };
+    ///
+    /// assert_eq!(
+    ///     value.pretty(&options).to_string(),
+    ///     "1) 1) \"a\"\n   … 1 more\n… 1 more"
+    /// );
+    /// ```
+    pub fn pretty<'a>(&'a self, options: &'a PrettyOptions) -> Pretty<'a> {
+        Pretty {
+            value: self,
+            options,
+        }
+    }
+}
+
+impl<'a> Display for Pretty<'a> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        if self.options.raw {
+            write_raw(f, self.value, self.options)
+        } else {
+            write_value(f, self.value, "", self.options)
+        }
+    }
+}
+
+/// Writes a value, indenting every line after the first with `prefix`.
+fn write_value<W: Write>(
+    f: &mut W,
+    value: &Value,
+    prefix: &str,
+    options: &PrettyOptions,
+) -> FmtResult {
+    match *value {
+        Value::Int(datum) => paint(f, options, COLOR_NUMBER, |f| {
+            write!(f, "(integer) {}", datum)
+        }),
+
+        Value::Str(ref datum) => paint(f, options, COLOR_STRING, |f| {
+            write_truncated(f, datum, options, |f, datum| f.write_str(datum))
+        }),
+
+        Value::Err(ref error) | Value::BErr(ref error) => paint(f, options, COLOR_ERROR, |f| {
+            f.write_str("(error) ")?;
+
+            write_truncated(f, error, options, |f, error| f.write_str(error))
+        }),
+
+        Value::BStr(Some(ref datum)) => paint(f, options, COLOR_STRING, |f| {
+            write_truncated(f, datum, options, |f, datum| {
+                write_quoted(f, datum.as_bytes())
+            })
+        }),
+
+        Value::Verbatim(_, ref datum) => paint(f, options, COLOR_STRING, |f| {
+            write_truncated(f, datum, options, |f, datum| f.write_str(datum))
+        }),
+
+        Value::BStr(None) | Value::Array(None) | Value::Null => {
+            paint(f, options, COLOR_DIM, |f| f.write_str("(nil)"))
+        }
+
+        Value::Bool(datum) => paint(f, options, COLOR_BOOL, |f| write!(f, "({})", datum)),
+
+        Value::Double(datum) => paint(f, options, COLOR_NUMBER, |f| {
+            if datum.is_nan() {
+                f.write_str("(double) nan")
+            } else {
+                write!(f, "(double) {}", datum)
+            }
+        }),
+
+        Value::BigNum(ref datum) => paint(f, options, COLOR_NUMBER, |f| {
+            write!(f, "(big number) {}", datum)
+        }),
+
+        Value::Array(Some(ref values)) => {
+            write_items(f, values, ')', "(empty array)", prefix, options)
+        }
+
+        Value::Set(ref values) => write_items(f, values, '~', "(empty set)", prefix, options),
+
+        Value::Push(ref values) => write_items(f, values, ')', "(empty push)", prefix, options),
+
+        Value::Map(ref pairs) => {
+            if pairs.is_empty() {
+                return paint(f, options, COLOR_DIM, |f| f.write_str("(empty hash)"));
+            }
+
+            write_entries(f, pairs.len(), '#', prefix, options, |f, i, prefix| {
+                let (ref key, ref value) = pairs[i];
+
+                write_value(f, key, prefix, options)?;
+
+                f.write_str(" => ")?;
+
+                write_value(f, value, prefix, options)
+            })
+        }
+    }
+}
+
+/// Writes a value like `redis-cli --raw` does, with items and map pairs on separate lines.
+fn write_raw<W: Write>(f: &mut W, value: &Value, options: &PrettyOptions) -> FmtResult {
+    match *value {
+        Value::Int(datum) => write!(f, "{}", datum),
+
+        Value::Str(ref datum)
+        | Value::Err(ref datum)
+        | Value::BStr(Some(ref datum))
+        | Value::BigNum(ref datum)
+        | Value::BErr(ref datum)
+        | Value::Verbatim(_, ref datum) => {
+            write_truncated(f, datum, options, |f, datum| f.write_str(datum))
+        }
+
+        Value::BStr(None) | Value::Array(None) | Value::Null => Ok(()),
+
+        Value::Bool(datum) => write!(f, "({})", datum),
+
+        Value::Double(datum) if datum.is_nan() => f.write_str("nan"),
+
+        Value::Double(datum) => write!(f, "{}", datum),
+
+        Value::Array(Some(ref values)) | Value::Set(ref values) | Value::Push(ref values) => {
+            write_lines(f, values.len(), options, |f, i| {
+                write_raw(f, &values[i], options)
+            })
+        }
+
+        Value::Map(ref pairs) => write_lines(f, pairs.len(), options, |f, i| {
+            let (ref key, ref value) = pairs[i];
+
+            write_raw(f, key, options)?;
+
+            f.write_char(' ')?;
+
+            write_raw(f, value, options)
+        }),
+    }
+}
+
+/// Writes `len` entries with `write_entry`, one per line.
+fn write_lines<W, F>(
+    f: &mut W,
+    len: usize,
+    options: &PrettyOptions,
+    mut write_entry: F,
+) -> FmtResult
+where
+    W: Write,
+    F: FnMut(&mut W, usize) -> FmtResult,
+{
+    let shown = options.max_items.map_or(len, |max| max.min(len));
+
+    for i in 0..shown {
+        if i > 0 {
+            f.write_char('\n')?;
+        }
+
+        write_entry(f, i)?;
+    }
+
+    if shown < len {
+        if shown > 0 {
+            f.write_char('\n')?;
+        }
+
+        write!(f, "… {} more", len - shown)?;
+    }
+
+    Ok(())
+}
+
+fn write_items<W: Write>(
+    f: &mut W,
+    values: &[Value],
+    separator: char,
+    empty: &str,
+    prefix: &str,
+    options: &PrettyOptions,
+) -> FmtResult {
+    if values.is_empty() {
+        return paint(f, options, COLOR_DIM, |f| f.write_str(empty));
+    }
+
+    write_entries(
+        f,
+        values.len(),
+        separator,
+        prefix,
+        options,
+        |f, i, prefix| write_value(f, &values[i], prefix, options),
+    )
+}
+
+/// Writes `len` numbered entries with `write_entry`, indenting nested lines under the numbers.
+fn write_entries<W, F>(
+    f: &mut W,
+    len: usize,
+    separator: char,
+    prefix: &str,
+    options: &PrettyOptions,
+    mut write_entry: F,
+) -> FmtResult
+where
+    W: Write,
+    F: FnMut(&mut W, usize, &str) -> FmtResult,
+{
+    let shown = options.max_items.map_or(len, |max| max.min(len));
+
+    // Numbers are right-aligned, and nested lines are indented past them
+    let width = shown.to_string().len();
+    let nested_prefix = format!("{}{}", prefix, " ".repeat(width + 2));
+
+    for i in 0..shown {
+        // The first number follows whatever was written before this value
+        if i > 0 {
+            write!(f, "\n{}", prefix)?;
+        }
+
+        paint(f, options, COLOR_DIM, |f| {
+            write!(f, "{:>width$}{}", i + 1, separator, width = width)
+        })?;
+
+        f.write_char(' ')?;
+
+        write_entry(f, i, &nested_prefix)?;
+    }
+
+    if shown < len {
+        if shown > 0 {
+            write!(f, "\n{}", prefix)?;
+        }
+
+        paint(f, options, COLOR_DIM, |f| {
+            write!(f, "… {} more", len - shown)
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Writes at most `max_string_len` bytes of `datum` with `write`, followed by how many were cut.
+fn write_truncated<W, F>(f: &mut W, datum: &str, options: &PrettyOptions, write: F) -> FmtResult
+where
+    W: Write,
+    F: FnOnce(&mut W, &str) -> FmtResult,
+{
+    match options.max_string_len {
+        Some(max) if datum.len() > max => {
+            // Don't split a character
+            let mut end = max;
+
+            while !datum.is_char_boundary(end) {
+                end -= 1;
+            }
+
+            write(f, &datum[..end])?;
+
+            write!(f, "… {} more", datum.len() - end)
+        }
+
+        _ => write(f, datum),
+    }
+}
+
+/// Writes `datum` quoted, escaping anything unprintable, like `redis-cli` does.
+fn write_quoted<W: Write>(f: &mut W, datum: &[u8]) -> FmtResult {
+    f.write_char('"')?;
+
+    for &byte in datum {
+        match byte {
+            b'\\' => f.write_str("\\\\")?,
+            b'"' => f.write_str("\\\"")?,
+            b'\n' => f.write_str("\\n")?,
+            b'\r' => f.write_str("\\r")?,
+            b'\t' => f.write_str("\\t")?,
+            0x07 => f.write_str("\\a")?,
+            0x08 => f.write_str("\\b")?,
+
+            b' '..=b'~' => f.write_char(byte as char)?,
+
+            _ => write!(f, "\\x{:02x}", byte)?,
+        }
+    }
+
+    f.write_char('"')
+}
+
+/// Writes with `write`, in `color` if colors are enabled.
+fn paint<W, F>(f: &mut W, options: &PrettyOptions, color: &str, write: F) -> FmtResult
+where
+    W: Write,
+    F: FnOnce(&mut W) -> FmtResult,
+{
+    if !options.colors {
+        return write(f);
+    }
+
+    write!(f, "\x1b[{}m", color)?;
+
+    write(f)?;
+
+    f.write_str("\x1b[0m")
+}
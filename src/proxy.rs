@@ -0,0 +1,314 @@
+use std::io::{BufRead, BufReader, Read, Result, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::handshake::requested_protocol;
+use super::{Decoder, Value};
+
+/// What a [Proxy](Proxy) does with a request.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Action {
+    /// Forward the request to the server unchanged.
+    Forward,
+    /// Forward a different request to the server instead.
+    Rewrite(Value),
+    /// Don't forward the request, reply to the client with the given value instead.
+    Block(Value),
+}
+
+/// A request and its reply, as seen by a [Proxy](Proxy).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Exchange {
+    /// The ID of the client's connection, counting from 0 in the order connections are accepted.
+    ///
+    /// NOTE: Unlike `client`, this is unique even if a client's address is reused by a later
+    /// connection.
+    pub connection: u64,
+    /// The address of the client.
+    pub client: SocketAddr,
+    /// The request, as sent by the client.
+    pub request: Value,
+    /// What was done with the request.
+    pub action: Action,
+    /// The reply, as sent to the client.
+    pub reply: Value,
+    /// The time between the request being received and the reply being sent.
+    pub latency: Duration,
+}
+
+type Filter = dyn Fn(&Value) -> Action + Send + Sync;
+
+type Log = dyn Fn(&Exchange) + Send + Sync;
+
+/// A proxy which sits between clients and a server, forwarding requests and replies.
+///
+/// Requests and replies are forwarded byte for byte, unless a request is rewritten or blocked by
+/// a [filter](Proxy::filter). Each request and its reply are passed to a [log](Proxy::log) once
+/// the reply has been sent.
+///
+/// NOTE: Each request is expected to have exactly one reply, so `SUBSCRIBE`, `MONITOR` and
+/// `CLIENT REPLY OFF` aren't supported. Connections sending invalid RESP, including inline
+/// commands, are closed.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use lib_resp::{Action, Proxy, Value};
+/// let proxy = Proxy::bind("127.0.0.1:6380", "127.0.0.1:6379")
+///     .unwrap()
+///     .filter(|request| match *request {
+///         Value::Array(Some(ref args)) if args.first() == Some(&Value::b_str(Some("FLUSHALL"))) => {
+///             Action::Block(Value::err("ERR FLUSHALL is blocked"))
+///         }
+///
+///         _ => Action::Forward,
+///     })
+///     .log(|exchange| println!("{:?} {} -> {}", exchange.latency, exchange.request, exchange.reply));
+///
+/// proxy.run().unwrap();
+/// ```
+pub struct Proxy {
+    listener: TcpListener,
+    upstream: Vec<SocketAddr>,
+    filter: Arc<Filter>,
+    log: Arc<Log>,
+}
+
+impl Proxy {
+    /// Listens on `addr`, proxying connections to the server at `upstream`.
+    pub fn bind<A: ToSocketAddrs, U: ToSocketAddrs>(addr: A, upstream: U) -> Result<Self> {
+        Ok(Proxy {
+            listener: TcpListener::bind(addr)?,
+            upstream: upstream.to_socket_addrs()?.collect(),
+            filter: Arc::new(|_: &Value| Action::Forward),
+            log: Arc::new(|_: &Exchange| {}),
+        })
+    }
+
+    /// Sets the function deciding what to do with each request, by default every request is
+    /// forwarded.
+    pub fn filter<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(&Value) -> Action + Send + Sync + 'static,
+    {
+        self.filter = Arc::new(filter);
+        self
+    }
+
+    /// Sets the function called with each request and its reply.
+    pub fn log<F>(mut self, log: F) -> Self
+    where
+        F: Fn(&Exchange) + Send + Sync + 'static,
+    {
+        self.log = Arc::new(log);
+        self
+    }
+
+    /// Returns the address the proxy is listening on.
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Accepts connections forever, proxying each on its own threads.
+    pub fn run(self) -> Result<()> {
+        for (connection, client) in (0..).zip(self.listener.incoming()) {
+            let client = client?;
+
+            let upstream = self.upstream.clone();
+            let filter = self.filter.clone();
+            let log = self.log.clone();
+
+            thread::spawn(move || {
+                // Errors only affect the connection, which is closed either way
+                let _ = proxy(connection, client, &upstream, filter, log);
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// A request waiting for its reply.
+struct Pending {
+    request: Value,
+    action: Action,
+    received: Instant,
+}
+
+fn proxy(
+    connection: u64,
+    client: TcpStream,
+    upstream: &[SocketAddr],
+    filter: Arc<Filter>,
+    log: Arc<Log>,
+) -> Result<()> {
+    let server = TcpStream::connect(upstream)?;
+
+    client.set_nodelay(true)?;
+    server.set_nodelay(true)?;
+
+    let (tx, rx) = channel();
+
+    let replies = {
+        let client = client.try_clone()?;
+        let server = server.try_clone()?;
+
+        thread::spawn(move || {
+            let ret = forward_replies(connection, &client, server, &rx, &*log);
+
+            // Wake up the request thread, if it's still waiting
+            let _ = client.shutdown(Shutdown::Both);
+
+            ret
+        })
+    };
+
+    let mut requests = Decoder::new(Recorder::new(BufReader::new(client.try_clone()?)));
+    let mut writer = &server;
+
+    while let Some(request) = requests.decode()? {
+        let action = filter(&request);
+
+        match action {
+            Action::Forward => writer.write_all(&requests.get_ref().frame)?,
+
+            Action::Rewrite(ref rewritten) => writer.write_all(&rewritten.encode_bytes())?,
+
+            Action::Block(_) => {}
+        }
+
+        requests.get_mut().frame.clear();
+
+        let pending = Pending {
+            request,
+            action,
+            received: Instant::now(),
+        };
+
+        if tx.send(pending).is_err() {
+            break;
+        }
+    }
+
+    // Let the server finish replying, then hang up
+    drop(tx);
+
+    let _ = server.shutdown(Shutdown::Write);
+
+    replies.join().unwrap()
+}
+
+fn forward_replies(
+    connection: u64,
+    client: &TcpStream,
+    server: TcpStream,
+    rx: &Receiver<Pending>,
+    log: &Log,
+) -> Result<()> {
+    let client_addr = client.peer_addr()?;
+
+    let mut replies = Decoder::new(Recorder::new(BufReader::new(server)));
+    let mut writer = client;
+
+    for pending in rx {
+        let reply = match pending.action {
+            Action::Block(ref reply) => {
+                writer.write_all(&reply.encode_bytes())?;
+
+                reply.clone()
+            }
+
+            _ => {
+                let previous = replies.protocol();
+
+                // The reply to `HELLO` is already in the requested protocol
+                let request = match pending.action {
+                    Action::Rewrite(ref rewritten) => rewritten,
+
+                    _ => &pending.request,
+                };
+
+                if let Some(protocol) = requested_protocol(request) {
+                    replies.set_protocol(protocol);
+                }
+
+                let reply = match replies.decode()? {
+                    Some(reply) => reply,
+
+                    None => return Ok(()),
+                };
+
+                if reply.server_error().is_some() {
+                    replies.set_protocol(previous);
+                }
+
+                writer.write_all(&replies.get_ref().frame)?;
+
+                replies.get_mut().frame.clear();
+
+                reply
+            }
+        };
+
+        log(&Exchange {
+            connection,
+            client: client_addr,
+            request: pending.request,
+            action: pending.action,
+            reply,
+            latency: pending.received.elapsed(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Wraps a reader, recording the bytes consumed from it.
+struct Recorder<R: BufRead> {
+    inner: R,
+    frame: Vec<u8>,
+}
+
+impl<R: BufRead> Recorder<R> {
+    fn new(inner: R) -> Self {
+        Recorder {
+            inner,
+            frame: Vec::new(),
+        }
+    }
+}
+
+impl<R: BufRead> Read for Recorder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let len = {
+            let available = self.fill_buf()?;
+            let len = available.len().min(buf.len());
+
+            buf[..len].copy_from_slice(&available[..len]);
+
+            len
+        };
+
+        self.consume(len);
+
+        Ok(len)
+    }
+}
+
+impl<R: BufRead> BufRead for Recorder<R> {
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        // The data is already buffered, so this can't fail
+        if let Ok(buf) = self.inner.fill_buf() {
+            self.frame.extend_from_slice(&buf[..amt]);
+        }
+
+        self.inner.consume(amt);
+    }
+}
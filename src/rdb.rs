@@ -0,0 +1,379 @@
+use std::io::{BufRead, Error, ErrorKind, Result};
+
+// Opcodes
+const OPCODE_SLOT_INFO: u8 = 0xf4;
+const OPCODE_FUNCTION2: u8 = 0xf5;
+const OPCODE_MODULE_AUX: u8 = 0xf7;
+const OPCODE_IDLE: u8 = 0xf8;
+const OPCODE_FREQ: u8 = 0xf9;
+const OPCODE_AUX: u8 = 0xfa;
+const OPCODE_RESIZEDB: u8 = 0xfb;
+const OPCODE_EXPIRETIME_MS: u8 = 0xfc;
+const OPCODE_EXPIRETIME: u8 = 0xfd;
+const OPCODE_SELECTDB: u8 = 0xfe;
+const OPCODE_EOF: u8 = 0xff;
+
+// Value types
+const TYPE_STRING: u8 = 0;
+const TYPE_LIST: u8 = 1;
+const TYPE_SET: u8 = 2;
+const TYPE_ZSET: u8 = 3;
+const TYPE_HASH: u8 = 4;
+const TYPE_ZSET_2: u8 = 5;
+const TYPE_MODULE_2: u8 = 7;
+const TYPE_HASH_ZIPMAP: u8 = 9;
+const TYPE_LIST_ZIPLIST: u8 = 10;
+const TYPE_SET_INTSET: u8 = 11;
+const TYPE_ZSET_ZIPLIST: u8 = 12;
+const TYPE_HASH_ZIPLIST: u8 = 13;
+const TYPE_LIST_QUICKLIST: u8 = 14;
+const TYPE_STREAM_LISTPACKS: u8 = 15;
+const TYPE_HASH_LISTPACK: u8 = 16;
+const TYPE_ZSET_LISTPACK: u8 = 17;
+const TYPE_LIST_QUICKLIST_2: u8 = 18;
+const TYPE_STREAM_LISTPACKS_2: u8 = 19;
+const TYPE_SET_LISTPACK: u8 = 20;
+const TYPE_STREAM_LISTPACKS_3: u8 = 21;
+
+// Module value opcodes
+const MODULE_OPCODE_EOF: u64 = 0;
+const MODULE_OPCODE_SINT: u64 = 1;
+const MODULE_OPCODE_UINT: u64 = 2;
+const MODULE_OPCODE_FLOAT: u64 = 3;
+const MODULE_OPCODE_DOUBLE: u64 = 4;
+const MODULE_OPCODE_STRING: u64 = 5;
+
+/// Skips an RDB file, e.g. the preamble of an AOF, up to and including its checksum, returning
+/// the number of bytes skipped.
+///
+/// The RDB is parsed rather than scanned for the EOF opcode, as `0xff` can appear in the data.
+pub(crate) fn skip_rdb<R: BufRead>(src: &mut R) -> Result<u64> {
+    let mut rdb = Rdb { src, read: 0 };
+
+    if &rdb.bytes::<5>()? != b"REDIS" {
+        return Err(invalid("no magic string".to_owned()));
+    }
+
+    // The version, 4 digits
+    rdb.skip(4)?;
+
+    loop {
+        match rdb.byte()? {
+            OPCODE_EOF => {
+                rdb.skip(8)?;
+
+                return Ok(rdb.read);
+            }
+
+            OPCODE_AUX => {
+                rdb.string()?;
+                rdb.string()?;
+            }
+
+            OPCODE_SELECTDB => {
+                rdb.len()?;
+            }
+
+            OPCODE_RESIZEDB => {
+                rdb.len()?;
+                rdb.len()?;
+            }
+
+            OPCODE_SLOT_INFO => {
+                rdb.len()?;
+                rdb.len()?;
+                rdb.len()?;
+            }
+
+            OPCODE_FUNCTION2 => rdb.string()?,
+
+            OPCODE_MODULE_AUX => {
+                // The module ID, and when it was saved
+                rdb.len()?;
+                rdb.len()?;
+                rdb.len()?;
+
+                rdb.module_value()?;
+            }
+
+            // These precede a key
+            OPCODE_EXPIRETIME_MS => rdb.skip(8)?,
+            OPCODE_EXPIRETIME => rdb.skip(4)?,
+            OPCODE_IDLE => {
+                rdb.len()?;
+            }
+            OPCODE_FREQ => rdb.skip(1)?,
+
+            value_type => {
+                rdb.string()?;
+
+                rdb.value(value_type)?;
+            }
+        }
+    }
+}
+
+enum Length {
+    Len(u64),
+    /// A string encoded as an integer or compressed, see [Rdb::string].
+    Encoded(u8),
+}
+
+struct Rdb<'a, R: 'a> {
+    src: &'a mut R,
+    read: u64,
+}
+
+impl<'a, R: BufRead> Rdb<'a, R> {
+    fn skip(&mut self, mut n: u64) -> Result<()> {
+        while n > 0 {
+            let available = self.src.fill_buf()?.len();
+
+            if available == 0 {
+                return Err(truncated());
+            }
+
+            let consumed = (available as u64).min(n) as usize;
+
+            self.src.consume(consumed);
+
+            self.read += consumed as u64;
+            n -= consumed as u64;
+        }
+
+        Ok(())
+    }
+
+    fn bytes<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut bytes = [0; N];
+
+        for byte in bytes.iter_mut() {
+            *byte = self.byte()?;
+        }
+
+        Ok(bytes)
+    }
+
+    fn byte(&mut self) -> Result<u8> {
+        let byte = match self.src.fill_buf()?.first() {
+            Some(&byte) => byte,
+
+            None => return Err(truncated()),
+        };
+
+        self.src.consume(1);
+
+        self.read += 1;
+
+        Ok(byte)
+    }
+
+    fn length(&mut self) -> Result<Length> {
+        let first = self.byte()?;
+
+        Ok(match first >> 6 {
+            0 => Length::Len(u64::from(first & 0x3f)),
+
+            1 => Length::Len(u64::from(first & 0x3f) << 8 | u64::from(self.byte()?)),
+
+            2 => match first {
+                0x80 => Length::Len(u64::from(u32::from_be_bytes(self.bytes()?))),
+
+                0x81 => Length::Len(u64::from_be_bytes(self.bytes()?)),
+
+                _ => return Err(invalid(format!("bad length 0x{:02x}", first))),
+            },
+
+            _ => Length::Encoded(first & 0x3f),
+        })
+    }
+
+    fn len(&mut self) -> Result<u64> {
+        match self.length()? {
+            Length::Len(len) => Ok(len),
+
+            Length::Encoded(_) => Err(invalid("an encoded length".to_owned())),
+        }
+    }
+
+    fn string(&mut self) -> Result<()> {
+        match self.length()? {
+            Length::Len(len) => self.skip(len),
+
+            // 8, 16 and 32 bit integers
+            Length::Encoded(0) => self.skip(1),
+            Length::Encoded(1) => self.skip(2),
+            Length::Encoded(2) => self.skip(4),
+
+            // LZF, the compressed and uncompressed lengths followed by the compressed data
+            Length::Encoded(3) => {
+                let compressed = self.len()?;
+
+                self.len()?;
+
+                self.skip(compressed)
+            }
+
+            Length::Encoded(encoding) => {
+                Err(invalid(format!("unknown string encoding {}", encoding)))
+            }
+        }
+    }
+
+    /// Skips a double in the old string format, a length followed by its text.
+    fn string_double(&mut self) -> Result<()> {
+        match self.byte()? {
+            // NaN and infinities
+            253..=255 => Ok(()),
+
+            len => self.skip(u64::from(len)),
+        }
+    }
+
+    fn strings(&mut self, per_item: u64) -> Result<()> {
+        for _ in 0..self.len()? {
+            for _ in 0..per_item {
+                self.string()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn value(&mut self, value_type: u8) -> Result<()> {
+        match value_type {
+            TYPE_STRING | TYPE_HASH_ZIPMAP | TYPE_LIST_ZIPLIST | TYPE_SET_INTSET
+            | TYPE_ZSET_ZIPLIST | TYPE_HASH_ZIPLIST | TYPE_HASH_LISTPACK | TYPE_ZSET_LISTPACK
+            | TYPE_SET_LISTPACK => self.string(),
+
+            TYPE_LIST | TYPE_SET | TYPE_LIST_QUICKLIST => self.strings(1),
+
+            TYPE_HASH => self.strings(2),
+
+            TYPE_ZSET => {
+                for _ in 0..self.len()? {
+                    self.string()?;
+                    self.string_double()?;
+                }
+
+                Ok(())
+            }
+
+            TYPE_ZSET_2 => {
+                for _ in 0..self.len()? {
+                    self.string()?;
+                    self.skip(8)?;
+                }
+
+                Ok(())
+            }
+
+            TYPE_LIST_QUICKLIST_2 => {
+                for _ in 0..self.len()? {
+                    // The container type, and the node
+                    self.len()?;
+                    self.string()?;
+                }
+
+                Ok(())
+            }
+
+            TYPE_MODULE_2 => {
+                self.len()?;
+
+                self.module_value()
+            }
+
+            TYPE_STREAM_LISTPACKS | TYPE_STREAM_LISTPACKS_2 | TYPE_STREAM_LISTPACKS_3 => {
+                self.stream(value_type)
+            }
+
+            _ => Err(invalid(format!("unsupported type {}", value_type))),
+        }
+    }
+
+    /// Skips the values saved by a module, which are tagged with opcodes.
+    fn module_value(&mut self) -> Result<()> {
+        loop {
+            match self.len()? {
+                MODULE_OPCODE_EOF => return Ok(()),
+
+                MODULE_OPCODE_SINT | MODULE_OPCODE_UINT => {
+                    self.len()?;
+                }
+
+                MODULE_OPCODE_FLOAT => self.skip(4)?,
+
+                MODULE_OPCODE_DOUBLE => self.skip(8)?,
+
+                MODULE_OPCODE_STRING => self.string()?,
+
+                opcode => return Err(invalid(format!("unknown module opcode {}", opcode))),
+            }
+        }
+    }
+
+    fn stream(&mut self, value_type: u8) -> Result<()> {
+        // The listpacks, keyed by their first ID
+        self.strings(2)?;
+
+        // The length, and the last ID
+        self.len()?;
+        self.len()?;
+        self.len()?;
+
+        if value_type >= TYPE_STREAM_LISTPACKS_2 {
+            // The first ID, the maximum deleted ID, and the number of entries added
+            for _ in 0..5 {
+                self.len()?;
+            }
+        }
+
+        for _ in 0..self.len()? {
+            // The name and last ID of the consumer group
+            self.string()?;
+            self.len()?;
+            self.len()?;
+
+            if value_type >= TYPE_STREAM_LISTPACKS_2 {
+                // The number of entries read
+                self.len()?;
+            }
+
+            // The pending entries: an ID, the delivery time and count
+            for _ in 0..self.len()? {
+                self.skip(16 + 8)?;
+                self.len()?;
+            }
+
+            for _ in 0..self.len()? {
+                // The name of the consumer, and when it was last seen
+                self.string()?;
+                self.skip(8)?;
+
+                if value_type >= TYPE_STREAM_LISTPACKS_3 {
+                    // When it was last active
+                    self.skip(8)?;
+                }
+
+                // The IDs of its pending entries
+                let pending = self.len()?;
+
+                self.skip(pending.saturating_mul(16))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn invalid(reason: String) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("Bad RDB preamble in the append only file: {}", reason),
+    )
+}
+
+fn truncated() -> Error {
+    Error::new(ErrorKind::UnexpectedEof, "AOF's RDB preamble is truncated")
+}
@@ -0,0 +1,336 @@
+use std::io::{self, BufRead, Error, ErrorKind, Read, Result, Write};
+use std::net::ToSocketAddrs;
+
+use super::handshake::check;
+use super::{Client, Protocol, ServerError, Value};
+
+/// The length of the marker delimiting RDB payloads sent with `capa eof`.
+const EOF_MARK_LEN: usize = 40;
+
+/// Options for a [Replica](Replica).
+#[derive(Clone, Debug)]
+pub struct ReplicaOptions {
+    /// The port reported to the master with `REPLCONF listening-port`.
+    ///
+    /// NOTE: Nothing is listening on it, it's only shown in the master's `INFO replication`.
+    pub listening_port: u16,
+    /// Whether the master may stream the RDB payload without knowing its length up front, i.e.
+    /// diskless replication.
+    pub eof: bool,
+    /// The ID of the replication stream to continue, a full resync is requested if `None`.
+    pub replid: Option<String>,
+    /// The replication offset to continue from, i.e. the offset of the last command processed.
+    pub offset: i64,
+}
+
+impl Default for ReplicaOptions {
+    fn default() -> Self {
+        ReplicaOptions {
+            listening_port: 0,
+            eof: false,
+            replid: None,
+            offset: -1,
+        }
+    }
+}
+
+/// The master's reply to `PSYNC`.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Psync {
+    /// The master sent a snapshot of its data, commands follow from `offset`.
+    FullResync { replid: String, offset: i64 },
+    /// The master is continuing the requested stream. `replid` is only sent by masters which
+    /// changed ID, e.g. after a failover.
+    Continue { replid: Option<String> },
+}
+
+/// A command propagated by the master.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReplicatedCommand {
+    /// The replication offset *after* the command, as reported with `REPLCONF ACK`.
+    pub offset: i64,
+    /// The command, as an array of bulk strings.
+    pub command: Value,
+}
+
+/// A fake replica, receiving the command stream of a master.
+///
+/// NOTE: Masters stop streaming to replicas which don't [acknowledge](Replica::ack) the stream
+/// for longer than `repl-timeout`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use lib_resp::{Replica, ReplicaOptions};
+/// let mut replica = Replica::connect("127.0.0.1:6379", &ReplicaOptions::default()).unwrap();
+///
+/// println!("{:?}", replica.psync());
+///
+/// for command in &mut replica {
+///     let command = command.unwrap();
+///
+///     println!("{} {}", command.offset, command.command);
+/// }
+/// ```
+pub struct Replica {
+    client: Client,
+    psync: Psync,
+    replid: String,
+    offset: i64,
+}
+
+impl Replica {
+    /// Connects to a master and starts replicating, discarding the RDB payload of a full resync.
+    pub fn connect<A: ToSocketAddrs>(addr: A, options: &ReplicaOptions) -> Result<Self> {
+        Replica::sync(Client::connect(addr)?, options, &mut io::sink())
+    }
+
+    /// Starts replicating over an existing connection, which must already be authenticated if
+    /// the master requires it.
+    ///
+    /// The RDB payload of a full resync is copied to `rdb`.
+    pub fn sync<W: Write>(
+        mut client: Client,
+        options: &ReplicaOptions,
+        rdb: &mut W,
+    ) -> Result<Self> {
+        // The stream is always RESP2, whatever was negotiated
+        client.set_protocol(Protocol::Resp2);
+
+        check(&client.command(&["PING"])?)?;
+
+        check(&client.command(&[
+            "REPLCONF".to_owned(),
+            "listening-port".to_owned(),
+            options.listening_port.to_string(),
+        ])?)?;
+
+        check(&if options.eof {
+            client.command(&["REPLCONF", "capa", "eof", "capa", "psync2"])?
+        } else {
+            client.command(&["REPLCONF", "capa", "psync2"])?
+        })?;
+
+        match options.replid {
+            Some(ref replid) => client.send(&::client::command(&[
+                "PSYNC".to_owned(),
+                replid.clone(),
+                (options.offset + 1).to_string(),
+            ]))?,
+
+            None => client.send(&::client::command(&["PSYNC", "?", "-1"]))?,
+        }
+
+        let reply = read_line(&mut client)?;
+
+        if let Some(error) = reply.strip_prefix('-') {
+            return Err(Error::new(ErrorKind::Other, ServerError::parse(error)));
+        }
+
+        let mut parts = reply.strip_prefix('+').unwrap_or("").split(' ');
+
+        let (psync, replid, offset) = match (parts.next(), parts.next(), parts.next()) {
+            (Some("FULLRESYNC"), Some(replid), Some(offset)) => {
+                let offset = offset.parse().map_err(|_| invalid_reply())?;
+
+                read_rdb(&mut client, rdb)?;
+
+                let psync = Psync::FullResync {
+                    replid: replid.to_owned(),
+                    offset,
+                };
+
+                (psync, replid.to_owned(), offset)
+            }
+
+            (Some("CONTINUE"), replid, None) => {
+                let psync = Psync::Continue {
+                    replid: replid.map(ToOwned::to_owned),
+                };
+
+                let replid = replid
+                    .map(ToOwned::to_owned)
+                    .or_else(|| options.replid.clone())
+                    .unwrap_or_default();
+
+                (psync, replid, options.offset)
+            }
+
+            _ => return Err(invalid_reply()),
+        };
+
+        Ok(Replica {
+            client,
+            psync,
+            replid,
+            offset,
+        })
+    }
+
+    /// Returns the master's reply to `PSYNC`.
+    pub fn psync(&self) -> &Psync {
+        &self.psync
+    }
+
+    /// Returns the ID of the replication stream.
+    pub fn replid(&self) -> &str {
+        &self.replid
+    }
+
+    /// Returns the replication offset of the last command received.
+    pub fn offset(&self) -> i64 {
+        self.offset
+    }
+
+    /// Waits for the next command from the master, returning `None` if the connection is closed.
+    pub fn read_command(&mut self) -> Result<Option<ReplicatedCommand>> {
+        let decoder = self.client.decoder_mut();
+
+        let start = decoder.offset();
+
+        let command = match decoder.decode()? {
+            Some(command) => command,
+
+            None => return Ok(None),
+        };
+
+        self.offset += (decoder.offset() - start) as i64;
+
+        Ok(Some(ReplicatedCommand {
+            offset: self.offset,
+            command,
+        }))
+    }
+
+    /// Acknowledges the stream up to the [offset](Replica::offset) of the last command received.
+    ///
+    /// NOTE: Masters ask for this with `REPLCONF GETACK *`, which is passed on like any other
+    /// command.
+    pub fn ack(&mut self) -> Result<()> {
+        let ack = ::client::command(&[
+            "REPLCONF".to_owned(),
+            "ACK".to_owned(),
+            self.offset.to_string(),
+        ]);
+
+        // The master never replies
+        self.client.get_ref().write_all(&ack.encode_bytes())
+    }
+}
+
+impl Iterator for Replica {
+    type Item = Result<ReplicatedCommand>;
+
+    fn next(&mut self) -> Option<Result<ReplicatedCommand>> {
+        match self.read_command() {
+            Ok(Some(command)) => Some(Ok(command)),
+
+            Ok(None) => None,
+
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// Reads a line from the master, skipping the empty lines sent to keep the connection alive.
+fn read_line(client: &mut Client) -> Result<String> {
+    let src = client.decoder_mut().get_mut();
+
+    loop {
+        let mut line = Vec::new();
+
+        if src.read_until(b'\n', &mut line)? == 0 {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                "Connection closed by master",
+            ));
+        }
+
+        let line = String::from_utf8(line).map_err(|_| invalid_reply())?;
+        let line = line.trim_end_matches(['\r', '\n']);
+
+        if !line.is_empty() {
+            return Ok(line.to_owned());
+        }
+    }
+}
+
+/// Copies the RDB payload of a full resync to `dst`.
+///
+/// Unlike a bulk string, the payload isn't followed by a CRLF.
+fn read_rdb<W: Write>(client: &mut Client, dst: &mut W) -> Result<()> {
+    let header = read_line(client)?;
+
+    let src = client.decoder_mut().get_mut();
+
+    if let Some(mark) = header.strip_prefix("$EOF:") {
+        if mark.len() != EOF_MARK_LEN {
+            return Err(invalid_reply());
+        }
+
+        return copy_until(src, dst, mark.as_bytes());
+    }
+
+    let len: u64 = header
+        .strip_prefix('$')
+        .and_then(|len| len.parse().ok())
+        .ok_or_else(invalid_reply)?;
+
+    if io::copy(&mut src.take(len), dst)? != len {
+        return Err(Error::new(
+            ErrorKind::UnexpectedEof,
+            "Connection closed during RDB transfer",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Copies from `src` to `dst` until `mark` is read, the mark itself isn't copied.
+fn copy_until<R: BufRead, W: Write>(src: &mut R, dst: &mut W, mark: &[u8]) -> Result<()> {
+    // Bytes which may be the start of the mark
+    let mut held: Vec<u8> = Vec::new();
+
+    loop {
+        let (consumed, found) = {
+            let buf = src.fill_buf()?;
+
+            if buf.is_empty() {
+                return Err(Error::new(
+                    ErrorKind::UnexpectedEof,
+                    "Connection closed during RDB transfer",
+                ));
+            }
+
+            held.extend_from_slice(buf);
+
+            match held.windows(mark.len()).position(|window| window == mark) {
+                Some(i) => {
+                    dst.write_all(&held[..i])?;
+
+                    // Anything after the mark is the start of the command stream
+                    (buf.len() - (held.len() - i - mark.len()), true)
+                }
+
+                None => {
+                    let keep = held.len().min(mark.len() - 1);
+
+                    dst.write_all(&held[..held.len() - keep])?;
+                    held.drain(..held.len() - keep);
+
+                    (buf.len(), false)
+                }
+            }
+        };
+
+        src.consume(consumed);
+
+        if found {
+            return Ok(());
+        }
+    }
+}
+
+fn invalid_reply() -> Error {
+    Error::new(ErrorKind::InvalidData, "Invalid PSYNC reply")
+}
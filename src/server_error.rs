@@ -0,0 +1,267 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use super::{Value, SLOT_COUNT};
+
+/// The target of a `MOVED` or `ASK` redirection.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Redirect {
+    /// The hash slot being redirected, always below [SLOT_COUNT](SLOT_COUNT).
+    pub slot: u16,
+    /// The host of the node now serving the slot.
+    ///
+    /// NOTE: This may be empty, in which case the node is on the host the
+    /// error was received from.
+    pub host: String,
+    /// The port of the node now serving the slot.
+    pub port: u16,
+}
+
+impl Redirect {
+    /// Parses the `<slot> <host>:<port>` part of a redirection.
+    fn parse(message: &str) -> Option<Self> {
+        let mut parts = message.split(' ');
+
+        let slot = parts.next()?.parse().ok()?;
+
+        if slot >= SLOT_COUNT {
+            return None;
+        }
+
+        let addr = parts.next()?;
+
+        if parts.next().is_some() {
+            return None;
+        }
+
+        // IPv6 hosts aren't bracketed, so split on the *last* colon
+        let colon = addr.rfind(':')?;
+
+        let port = addr[colon + 1..].parse().ok()?;
+
+        Some(Redirect {
+            slot,
+            host: addr[..colon].to_owned(),
+            port,
+        })
+    }
+
+    /// Returns the `host:port` address of the node now serving the slot.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lib_resp::ServerError;
+    /// let error = ServerError::parse("MOVED 3999 127.0.0.1:6381");
+    ///
+    /// assert_eq!(error.redirect().unwrap().addr(), "127.0.0.1:6381");
+    /// ```
+    pub fn addr(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+}
+
+/// The kind of a [ServerError](ServerError), derived from its error code.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ErrorKind {
+    /// `ERR`: a generic error.
+    Generic,
+    /// `WRONGTYPE`: an operation against a key holding the wrong kind of value.
+    WrongType,
+    /// `NOSCRIPT`: no script matches the given SHA1 digest.
+    NoScript,
+    /// `BUSY`: a script or function is running.
+    Busy,
+    /// `READONLY`: a write was sent to a read-only replica.
+    ReadOnly,
+    /// `LOADING`: the dataset is still being loaded into memory.
+    Loading,
+    /// `NOAUTH`: authentication is required.
+    NoAuth,
+    /// `WRONGPASS`: invalid username-password pair.
+    WrongPass,
+    /// `NOPERM`: the user lacks permission for the command or key.
+    NoPerm,
+    /// `NOPROTO`: the requested protocol version is unsupported.
+    NoProto,
+    /// `OOM`: the command is not allowed when used memory exceeds `maxmemory`.
+    OutOfMemory,
+    /// `EXECABORT`: a transaction was discarded because of previous errors.
+    ExecAbort,
+    /// `MASTERDOWN`: the link with the master is down.
+    MasterDown,
+    /// `CROSSSLOT`: the keys of a command don't hash to the same slot.
+    CrossSlot,
+    /// `TRYAGAIN`: a multi-key command hit a slot that is being migrated.
+    TryAgain,
+    /// `CLUSTERDOWN`: the cluster is down.
+    ClusterDown,
+    /// `MOVED`: the slot is permanently served by another node.
+    Moved(Redirect),
+    /// `ASK`: the slot is being migrated, retry this command once on another node.
+    Ask(Redirect),
+    /// Any other error code.
+    Other(String),
+}
+
+/// A typed error reply.
+///
+/// Error replies conventionally start with an upper-case code, followed by a
+/// human readable message, e.g. `WRONGTYPE Operation against a key holding the
+/// wrong kind of value`.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ServerError {
+    kind: ErrorKind,
+    code: String,
+    message: String,
+}
+
+impl ServerError {
+    /// Parses the contents of an error reply.
+    ///
+    /// NOTE: Parsing never fails, unrecognised codes result in an
+    /// [ErrorKind::Other](ErrorKind::Other), as do malformed redirections.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lib_resp::{ErrorKind, ServerError};
+    /// let error = ServerError::parse("WRONGTYPE Operation against a key holding the wrong kind of value");
+    ///
+    /// assert_eq!(error.kind(), &ErrorKind::WrongType);
+    /// assert_eq!(error.message(), "Operation against a key holding the wrong kind of value");
+    /// ```
+    ///
+    /// ```
+    /// # use lib_resp::{ErrorKind, Redirect, ServerError};
+    /// let error = ServerError::parse("ASK 3999 127.0.0.1:6381");
+    ///
+    /// assert_eq!(
+    ///     error.kind(),
+    ///     &ErrorKind::Ask(Redirect {
+    ///         slot: 3999,
+    ///         host: "127.0.0.1".to_owned(),
+    ///         port: 6381,
+    ///     })
+    /// );
+    /// ```
+    pub fn parse(error: &str) -> Self {
+        let (code, message) = match error.find(' ') {
+            Some(i) => (&error[..i], &error[i + 1..]),
+
+            None => (error, ""),
+        };
+
+        let kind = match code {
+            "ERR" => ErrorKind::Generic,
+            "WRONGTYPE" => ErrorKind::WrongType,
+            "NOSCRIPT" => ErrorKind::NoScript,
+            "BUSY" => ErrorKind::Busy,
+            "READONLY" => ErrorKind::ReadOnly,
+            "LOADING" => ErrorKind::Loading,
+            "NOAUTH" => ErrorKind::NoAuth,
+            "WRONGPASS" => ErrorKind::WrongPass,
+            "NOPERM" => ErrorKind::NoPerm,
+            "NOPROTO" => ErrorKind::NoProto,
+            "OOM" => ErrorKind::OutOfMemory,
+            "EXECABORT" => ErrorKind::ExecAbort,
+            "MASTERDOWN" => ErrorKind::MasterDown,
+            "CROSSSLOT" => ErrorKind::CrossSlot,
+            "TRYAGAIN" => ErrorKind::TryAgain,
+            "CLUSTERDOWN" => ErrorKind::ClusterDown,
+
+            "MOVED" | "ASK" => match Redirect::parse(message) {
+                Some(redirect) if code == "MOVED" => ErrorKind::Moved(redirect),
+
+                Some(redirect) => ErrorKind::Ask(redirect),
+
+                None => ErrorKind::Other(code.to_owned()),
+            },
+
+            _ => ErrorKind::Other(code.to_owned()),
+        };
+
+        ServerError {
+            kind,
+            code: code.to_owned(),
+            message: message.to_owned(),
+        }
+    }
+
+    /// Returns the kind of this error.
+    #[inline]
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    /// Returns the error code, e.g. `ERR` or `MOVED`.
+    #[inline]
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// Returns the message following the error code.
+    #[inline]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the target of a `MOVED` or `ASK` redirection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lib_resp::ServerError;
+    /// let error = ServerError::parse("MOVED 3999 127.0.0.1:6381");
+    ///
+    /// assert_eq!(error.redirect().unwrap().slot, 3999);
+    ///
+    /// let error = ServerError::parse("ERR unknown command 'foo'");
+    ///
+    /// assert!(error.redirect().is_none());
+    /// ```
+    #[inline]
+    pub fn redirect(&self) -> Option<&Redirect> {
+        match self.kind {
+            ErrorKind::Moved(ref redirect) | ErrorKind::Ask(ref redirect) => Some(redirect),
+
+            _ => None,
+        }
+    }
+}
+
+impl Display for ServerError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        if self.message.is_empty() {
+            write!(f, "{}", self.code)
+        } else {
+            write!(f, "{} {}", self.code, self.message)
+        }
+    }
+}
+
+impl Error for ServerError {}
+
+impl Value {
+    /// Parses an error value into a [ServerError](ServerError).
+    ///
+    /// Both simple and blob errors are parsed, returns `None` if the value isn't an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lib_resp::{ErrorKind, Value};
+    /// let error = Value::err("NOAUTH Authentication required.");
+    ///
+    /// assert_eq!(error.server_error().unwrap().kind(), &ErrorKind::NoAuth);
+    ///
+    /// assert!(Value::str("OK").server_error().is_none());
+    /// ```
+    pub fn server_error(&self) -> Option<ServerError> {
+        match *self {
+            Value::Err(ref error) | Value::BErr(ref error) => Some(ServerError::parse(error)),
+
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,337 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Error, ErrorKind, Result, Write};
+use std::net::ToSocketAddrs;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::handshake::requested_protocol;
+use super::{Client, Decoder, Protocol, Value};
+
+/// The header identifying a session file, and the version of its format.
+const MAGIC: &str = "resp-session";
+const VERSION: i64 = 1;
+
+fn header() -> Value {
+    Value::Array(Some(vec![
+        Value::Str(MAGIC.to_owned()),
+        Value::Int(VERSION),
+    ]))
+}
+
+/// A request and its reply, as recorded in a session.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SessionEntry {
+    /// Identifies the connection the request was sent on, requests on the same connection are
+    /// replayed on the same connection.
+    pub connection: u64,
+    /// When the request was sent, since the start of the session.
+    pub time: Duration,
+    /// The request, as sent to the server.
+    pub request: Value,
+    /// The reply to the request.
+    pub reply: Value,
+}
+
+impl SessionEntry {
+    fn to_value(&self) -> Value {
+        Value::Array(Some(vec![
+            Value::Int(self.connection as i64),
+            Value::Int(self.time.as_micros() as i64),
+            self.request.clone(),
+            self.reply.clone(),
+        ]))
+    }
+
+    fn from_value(value: Value) -> Option<Self> {
+        let mut fields = match value {
+            Value::Array(Some(fields)) if fields.len() == 4 => fields.into_iter(),
+
+            _ => return None,
+        };
+
+        let (connection, time) = match (fields.next()?, fields.next()?) {
+            (Value::Int(connection), Value::Int(time)) if connection >= 0 && time >= 0 => {
+                (connection as u64, Duration::from_micros(time as u64))
+            }
+
+            _ => return None,
+        };
+
+        Some(SessionEntry {
+            connection,
+            time,
+            request: fields.next()?,
+            reply: fields.next()?,
+        })
+    }
+}
+
+/// Writes a session file.
+///
+/// A session file is a RESP3 stream, starting with a header, followed by an array of the form
+/// `[connection, time in microseconds, request, reply]` for each entry.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use lib_resp::{SessionEntry, SessionWriter, Value};
+/// let mut writer = SessionWriter::create("session.resp").unwrap();
+///
+/// writer
+///     .write(&SessionEntry {
+///         connection: 0,
+///         time: Duration::from_millis(0),
+///         request: Value::array(Some(vec![Value::b_str(Some("PING"))])),
+///         reply: Value::str("PONG"),
+///     })
+///     .unwrap();
+///
+/// writer.flush().unwrap();
+/// ```
+pub struct SessionWriter<W: Write> {
+    dst: W,
+}
+
+impl SessionWriter<BufWriter<File>> {
+    /// Creates a session file, truncating it if it exists.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        SessionWriter::new(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<W: Write> SessionWriter<W> {
+    /// Starts a session, writing its header to `dst`.
+    pub fn new(mut dst: W) -> Result<Self> {
+        dst.write_all(&header().encode_bytes())?;
+
+        Ok(SessionWriter { dst })
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.dst
+    }
+
+    /// Unwraps the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.dst
+    }
+
+    /// Appends an entry to the session.
+    pub fn write(&mut self, entry: &SessionEntry) -> Result<()> {
+        self.dst.write_all(&entry.to_value().encode_bytes())
+    }
+
+    /// Flushes the underlying writer.
+    pub fn flush(&mut self) -> Result<()> {
+        self.dst.flush()
+    }
+}
+
+/// Iterator over the entries of a session file.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use lib_resp::SessionReader;
+/// let reader = SessionReader::open("session.resp").unwrap();
+///
+/// for entry in reader {
+///     let entry = entry.unwrap();
+///
+///     println!("{:?} {} -> {}", entry.time, entry.request, entry.reply);
+/// }
+/// ```
+pub struct SessionReader<R: BufRead> {
+    decoder: Decoder<R>,
+}
+
+impl SessionReader<BufReader<File>> {
+    /// Opens a session file for reading.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        SessionReader::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: BufRead> SessionReader<R> {
+    /// Starts reading a session, checking its header.
+    pub fn new(src: R) -> Result<Self> {
+        let mut decoder = Decoder::new(src);
+
+        decoder.set_protocol(Protocol::Resp3);
+
+        match decoder.decode()? {
+            Some(ref value) if *value == header() => Ok(SessionReader { decoder }),
+
+            _ => Err(Error::new(ErrorKind::InvalidData, "Not a session file")),
+        }
+    }
+
+    /// Reads the next entry, returning `None` at the end of the session.
+    pub fn read_entry(&mut self) -> Result<Option<SessionEntry>> {
+        match self.decoder.decode()? {
+            Some(value) => SessionEntry::from_value(value)
+                .map(Some)
+                .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Invalid session entry")),
+
+            None => Ok(None),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for SessionReader<R> {
+    type Item = Result<SessionEntry>;
+
+    fn next(&mut self) -> Option<Result<SessionEntry>> {
+        match self.read_entry() {
+            Ok(Some(entry)) => Some(Ok(entry)),
+
+            Ok(None) => None,
+
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// Options for [replay](replay).
+#[derive(Clone, Debug)]
+pub struct ReplayOptions {
+    /// How much faster than recorded to send requests, e.g. `2.0` for twice as fast. Requests are
+    /// sent as fast as possible if `f64::INFINITY`, must be positive.
+    pub speed: f64,
+    /// Commands whose replies aren't compared, e.g. `TIME` or `INFO`, matched case-insensitively.
+    pub ignore: Vec<String>,
+}
+
+impl Default for ReplayOptions {
+    fn default() -> Self {
+        ReplayOptions {
+            speed: 1.0,
+            ignore: Vec::new(),
+        }
+    }
+}
+
+/// A reply which differs from the one recorded.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mismatch {
+    /// The position of the entry in the session, starting from 0.
+    pub index: usize,
+    /// The recorded entry.
+    pub entry: SessionEntry,
+    /// The reply sent by the server.
+    pub reply: Value,
+}
+
+/// The outcome of [replay](replay).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ReplayReport {
+    /// The number of requests sent.
+    pub replayed: usize,
+    /// The replies which differ from the ones recorded.
+    pub mismatches: Vec<Mismatch>,
+}
+
+/// Replays a session against the server at `addr`, comparing its replies with the recorded ones.
+///
+/// Each recorded connection is replayed on a connection of its own, opened when its first request
+/// is sent. Requests are sent one at a time, in the order they were recorded.
+///
+/// NOTE: Requests waiting on other connections, e.g. `BLPOP`, will wait forever if the request
+/// they're waiting on comes later in the session.
+///
+/// Returns an `InvalidInput` error if [speed](ReplayOptions::speed) isn't positive.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use lib_resp::{replay, ReplayOptions, SessionReader};
+/// let session = SessionReader::open("session.resp").unwrap();
+///
+/// let report = replay(session, "127.0.0.1:6379", &ReplayOptions::default()).unwrap();
+///
+/// for mismatch in report.mismatches {
+///     println!("{}: expected {}, got {}", mismatch.entry.request, mismatch.entry.reply, mismatch.reply);
+/// }
+/// ```
+pub fn replay<I, A>(session: I, addr: A, options: &ReplayOptions) -> Result<ReplayReport>
+where
+    I: IntoIterator<Item = Result<SessionEntry>>,
+    A: ToSocketAddrs,
+{
+    // Infinity is fine, every request is due straight away
+    if options.speed.is_nan() || options.speed <= 0.0 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "The replay speed must be positive",
+        ));
+    }
+
+    let addrs: Vec<_> = addr.to_socket_addrs()?.collect();
+
+    let mut connections: HashMap<u64, Client> = HashMap::new();
+    let mut report = ReplayReport::default();
+
+    let start = Instant::now();
+    let mut first = None;
+
+    for (index, entry) in session.into_iter().enumerate() {
+        let entry = entry?;
+
+        let since_first = entry.time.saturating_sub(*first.get_or_insert(entry.time));
+        let due = Duration::from_secs_f64(since_first.as_secs_f64() / options.speed);
+
+        if let Some(wait) = due.checked_sub(start.elapsed()) {
+            thread::sleep(wait);
+        }
+
+        let client = match connections.entry(entry.connection) {
+            Entry::Occupied(client) => client.into_mut(),
+
+            Entry::Vacant(vacant) => vacant.insert(Client::connect(&addrs[..])?),
+        };
+
+        let previous = client.protocol();
+
+        // The reply to `HELLO` is already in the requested protocol
+        if let Some(protocol) = requested_protocol(&entry.request) {
+            client.set_protocol(protocol);
+        }
+
+        let reply = client.call(&entry.request)?;
+
+        if reply.server_error().is_some() {
+            client.set_protocol(previous);
+        }
+
+        report.replayed += 1;
+
+        if reply != entry.reply && !is_ignored(&entry.request, &options.ignore) {
+            report.mismatches.push(Mismatch {
+                index,
+                entry,
+                reply,
+            });
+        }
+    }
+
+    Ok(report)
+}
+
+fn is_ignored(request: &Value, ignore: &[String]) -> bool {
+    match *request {
+        Value::Array(Some(ref args)) => match args.first() {
+            Some(&Value::BStr(Some(ref name))) => ignore
+                .iter()
+                .any(|ignored| ignored.eq_ignore_ascii_case(name)),
+
+            _ => false,
+        },
+
+        _ => false,
+    }
+}
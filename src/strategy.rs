@@ -0,0 +1,188 @@
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::strategy::Union;
+
+use super::{Value, ValueKind};
+
+/// Parameters for generating [Value](Value)s with proptest, see `any_with::<Value>`.
+///
+/// Generated values are always valid RESP, e.g. simple strings and errors never contain CR or LF,
+/// so they survive being encoded and parsed again.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate lib_resp;
+/// # extern crate proptest;
+/// # use lib_resp::{Parser, Value, ValueParams};
+/// # use proptest::prelude::*;
+/// # use proptest::test_runner::TestRunner;
+/// let mut runner = TestRunner::default();
+///
+/// runner
+///     .run(&any_with::<Value>(ValueParams::resp2()), |value| {
+///         let encoded = value.encode_bytes();
+///
+///         prop_assert_eq!(Parser::parse(&encoded).unwrap().1, value);
+///
+///         Ok(())
+///     })
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct ValueParams {
+    /// How deeply aggregates may be nested, `0` for only scalars.
+    pub depth: u32,
+    /// The maximum number of items in an aggregate, or pairs in a map.
+    pub width: usize,
+    /// The maximum length of strings, in characters.
+    pub string_len: usize,
+    /// The kinds of value to generate, must not be empty.
+    pub kinds: Vec<ValueKind>,
+}
+
+impl ValueParams {
+    /// Parameters for generating RESP2 values.
+    pub fn resp2() -> Self {
+        ValueParams {
+            kinds: ValueKind::RESP2.to_vec(),
+            ..ValueParams::default()
+        }
+    }
+}
+
+impl Default for ValueParams {
+    fn default() -> Self {
+        ValueParams {
+            depth: 3,
+            width: 8,
+            string_len: 32,
+            kinds: ValueKind::RESP3.to_vec(),
+        }
+    }
+}
+
+impl Arbitrary for Value {
+    type Parameters = ValueParams;
+    type Strategy = BoxedStrategy<Value>;
+
+    fn arbitrary_with(params: ValueParams) -> BoxedStrategy<Value> {
+        assert!(!params.kinds.is_empty(), "No kinds of value to generate");
+
+        let (aggregates, scalars): (Vec<ValueKind>, Vec<ValueKind>) =
+            params.kinds.iter().partition(|kind| kind.is_aggregate());
+
+        // Without scalars, the innermost aggregates are empty
+        let leaf = if scalars.is_empty() {
+            Union::new(aggregates.iter().map(|&kind| empty(kind))).boxed()
+        } else {
+            Union::new(scalars.iter().map(|&kind| scalar(kind, params.string_len))).boxed()
+        };
+
+        if aggregates.is_empty() || params.depth == 0 {
+            return leaf;
+        }
+
+        let width = params.width;
+
+        leaf.prop_recursive(params.depth, 256, width.max(1) as u32, move |inner| {
+            Union::new(
+                aggregates
+                    .iter()
+                    .map(|&kind| aggregate(kind, inner.clone(), width)),
+            )
+        })
+        .boxed()
+    }
+}
+
+fn scalar(kind: ValueKind, len: usize) -> BoxedStrategy<Value> {
+    match kind {
+        ValueKind::Int => any::<i64>().prop_map(Value::Int).boxed(),
+
+        ValueKind::Str => line(len).prop_map(Value::Str).boxed(),
+
+        ValueKind::Err => line(len).prop_map(Value::Err).boxed(),
+
+        ValueKind::BStr => proptest::option::of(string(len))
+            .prop_map(Value::BStr)
+            .boxed(),
+
+        ValueKind::Null => Just(Value::Null).boxed(),
+
+        ValueKind::Bool => any::<bool>().prop_map(Value::Bool).boxed(),
+
+        // NaN payloads can't be encoded, so there's only one NaN
+        ValueKind::Double => any::<f64>()
+            .prop_map(|datum| Value::Double(if datum.is_nan() { f64::NAN } else { datum }))
+            .boxed(),
+
+        ValueKind::BigNum => (
+            any::<bool>(),
+            vec(proptest::char::range('0', '9'), 1..=len.max(1)),
+        )
+            .prop_map(|(negative, digits)| {
+                let sign = if negative { "-" } else { "" };
+
+                Value::BigNum(format!(
+                    "{}{}",
+                    sign,
+                    digits.into_iter().collect::<String>()
+                ))
+            })
+            .boxed(),
+
+        ValueKind::BErr => string(len).prop_map(Value::BErr).boxed(),
+
+        ValueKind::Verbatim => (vec(proptest::char::range('a', 'z'), 3), string(len))
+            .prop_map(|(format, datum)| Value::Verbatim(format.into_iter().collect(), datum))
+            .boxed(),
+
+        _ => unreachable!("{:?} is an aggregate", kind),
+    }
+}
+
+fn empty(kind: ValueKind) -> BoxedStrategy<Value> {
+    match kind {
+        ValueKind::Array => {
+            prop_oneof![Just(Value::Array(None)), Just(Value::Array(Some(vec![])))].boxed()
+        }
+
+        ValueKind::Map => Just(Value::Map(vec![])).boxed(),
+
+        ValueKind::Set => Just(Value::Set(vec![])).boxed(),
+
+        ValueKind::Push => Just(Value::Push(vec![])).boxed(),
+
+        _ => unreachable!("{:?} isn't an aggregate", kind),
+    }
+}
+
+fn aggregate(kind: ValueKind, inner: BoxedStrategy<Value>, width: usize) -> BoxedStrategy<Value> {
+    match kind {
+        ValueKind::Array => proptest::option::of(vec(inner, 0..=width))
+            .prop_map(Value::Array)
+            .boxed(),
+
+        ValueKind::Map => vec((inner.clone(), inner), 0..=width)
+            .prop_map(Value::Map)
+            .boxed(),
+
+        ValueKind::Set => vec(inner, 0..=width).prop_map(Value::Set).boxed(),
+
+        ValueKind::Push => vec(inner, 0..=width).prop_map(Value::Push).boxed(),
+
+        _ => unreachable!("{:?} isn't an aggregate", kind),
+    }
+}
+
+fn string(len: usize) -> impl Strategy<Value = String> {
+    vec(any::<char>(), 0..=len).prop_map(|chars| chars.into_iter().collect())
+}
+
+/// A string without CR or LF, for simple strings and errors.
+fn line(len: usize) -> impl Strategy<Value = String> {
+    let c = any::<char>().prop_filter("CR and LF end the line", |&c| c != '\r' && c != '\n');
+
+    vec(c, 0..=len).prop_map(|chars| chars.into_iter().collect())
+}
@@ -0,0 +1,244 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use super::Value;
+
+/// Why a [Value](Value) can't be encoded as valid RESP.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum ValueErrorKind {
+    /// A simple string or error contains CR or LF, which would end it early.
+    LineBreak,
+    /// A big number isn't an optionally negative string of digits.
+    InvalidBigNum,
+    /// The format of a verbatim string isn't three bytes long.
+    InvalidVerbatimFormat,
+}
+
+/// A [Value](Value) which can't be encoded as valid RESP.
+///
+/// # Examples
+///
+/// ```
+/// # use lib_resp::{Value, ValueErrorKind};
+/// let value = Value::array(Some(vec![Value::int(1), Value::str("a\r\nb")]));
+///
+/// let error = value.validate().unwrap_err();
+///
+/// assert_eq!(error.kind(), ValueErrorKind::LineBreak);
+/// assert_eq!(error.path(), &[1]);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ValueError {
+    kind: ValueErrorKind,
+    path: Vec<usize>,
+}
+
+impl ValueError {
+    /// Returns why the value is invalid.
+    pub fn kind(&self) -> ValueErrorKind {
+        self.kind
+    }
+
+    /// Returns the indices leading to the invalid value, empty if it's the value itself.
+    ///
+    /// NOTE: The pairs of maps are flattened, so the key of the `n`th pair is at `2n`, and its
+    /// value at `2n + 1`.
+    pub fn path(&self) -> &[usize] {
+        &self.path
+    }
+}
+
+impl Display for ValueError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let reason = match self.kind {
+            ValueErrorKind::LineBreak => "Simple string or error contains a line break",
+            ValueErrorKind::InvalidBigNum => "Big number isn't an integer",
+            ValueErrorKind::InvalidVerbatimFormat => "Verbatim string format isn't 3 bytes",
+        };
+
+        if self.path.is_empty() {
+            write!(f, "{}", reason)
+        } else {
+            write!(f, "{} at {:?}", reason, self.path)
+        }
+    }
+}
+
+impl Error for ValueError {}
+
+impl Value {
+    /// Constructs a new simple string, checking it doesn't contain CR or LF.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lib_resp::Value;
+    /// assert_eq!(Value::try_str("OK"), Ok(Value::str("OK")));
+    ///
+    /// assert!(Value::try_str("O\r\nK").is_err());
+    /// ```
+    pub fn try_str<T: ToString>(value: T) -> Result<Self, ValueError> {
+        let value = Value::str(value);
+
+        value.validate()?;
+
+        Ok(value)
+    }
+
+    /// Constructs a new error, checking it doesn't contain CR or LF.
+    pub fn try_err<T: ToString>(error: T) -> Result<Self, ValueError> {
+        let error = Value::err(error);
+
+        error.validate()?;
+
+        Ok(error)
+    }
+
+    /// Constructs a new simple string, replacing CR and LF with spaces.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lib_resp::Value;
+    /// assert_eq!(Value::str_lossy("line 1\r\nline 2"), Value::str("line 1  line 2"));
+    /// ```
+    pub fn str_lossy<T: ToString>(value: T) -> Self {
+        Value::Str(replace_line_breaks(&value.to_string()))
+    }
+
+    /// Constructs a new error, replacing CR and LF with spaces.
+    pub fn err_lossy<T: ToString>(error: T) -> Self {
+        Value::Err(replace_line_breaks(&error.to_string()))
+    }
+
+    /// Constructs a new simple string, escaping CR and LF as `\r` and `\n`. Backslashes are
+    /// escaped too, so the original can be recovered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lib_resp::Value;
+    /// assert_eq!(Value::str_escaped("a\\b\r\n"), Value::str(r"a\\b\r\n"));
+    /// ```
+    pub fn str_escaped<T: ToString>(value: T) -> Self {
+        Value::Str(escape_line_breaks(&value.to_string()))
+    }
+
+    /// Constructs a new error, escaping CR and LF as `\r` and `\n`. Backslashes are escaped too,
+    /// so the original can be recovered.
+    pub fn err_escaped<T: ToString>(error: T) -> Self {
+        Value::Err(escape_line_breaks(&error.to_string()))
+    }
+
+    /// Checks that a `Value`, and everything in it, can be encoded as valid RESP.
+    ///
+    /// Values which fail this check can still be [encoded](Value::encode), but the result will
+    /// desynchronize the peer.
+    ///
+    /// NOTE: The check doesn't recurse, so it's safe on arbitrarily deep values.
+    pub fn validate(&self) -> Result<(), ValueError> {
+        // Values left to check, with their depth and their index in their parent
+        let mut values = vec![(self, 0, 0)];
+        let mut path = Vec::new();
+
+        while let Some((value, depth, index)) = values.pop() {
+            if depth > 0 {
+                path.truncate(depth - 1);
+                path.push(index);
+            }
+
+            if let Err(kind) = check(value) {
+                return Err(ValueError { kind, path });
+            }
+
+            match *value {
+                Value::Array(Some(ref items)) | Value::Set(ref items) | Value::Push(ref items) => {
+                    values.extend(
+                        items
+                            .iter()
+                            .enumerate()
+                            .rev()
+                            .map(|(i, item)| (item, depth + 1, i)),
+                    );
+                }
+
+                Value::Map(ref pairs) => {
+                    for (i, (key, item)) in pairs.iter().enumerate().rev() {
+                        values.push((item, depth + 1, 2 * i + 1));
+                        values.push((key, depth + 1, 2 * i));
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Encodes a `Value`, unless it's [invalid](Value::validate).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lib_resp::Value;
+    /// assert_eq!(Value::str("OK").try_encode().unwrap(), "+OK\r\n");
+    ///
+    /// assert!(Value::str("O\r\nK").try_encode().is_err());
+    /// ```
+    pub fn try_encode(&self) -> Result<String, ValueError> {
+        self.validate()?;
+
+        Ok(self.encode())
+    }
+
+    /// Encodes a `Value` as a vector of bytes, unless it's [invalid](Value::validate).
+    pub fn try_encode_bytes(&self) -> Result<Vec<u8>, ValueError> {
+        self.try_encode().map(String::into_bytes)
+    }
+}
+
+/// Checks a single value, ignoring its items.
+fn check(value: &Value) -> Result<(), ValueErrorKind> {
+    match *value {
+        Value::Str(ref datum) | Value::Err(ref datum) if datum.contains(['\r', '\n']) => {
+            Err(ValueErrorKind::LineBreak)
+        }
+
+        Value::BigNum(ref datum) => {
+            let digits = datum.strip_prefix('-').unwrap_or(datum);
+
+            if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(ValueErrorKind::InvalidBigNum);
+            }
+
+            Ok(())
+        }
+
+        Value::Verbatim(ref format, _) if format.len() != 3 => {
+            Err(ValueErrorKind::InvalidVerbatimFormat)
+        }
+
+        _ => Ok(()),
+    }
+}
+
+fn replace_line_breaks(datum: &str) -> String {
+    datum.replace(['\r', '\n'], " ")
+}
+
+fn escape_line_breaks(datum: &str) -> String {
+    let mut escaped = String::with_capacity(datum.len());
+
+    for c in datum.chars() {
+        match c {
+            '\\' => escaped.push_str(r"\\"),
+            '\r' => escaped.push_str(r"\r"),
+            '\n' => escaped.push_str(r"\n"),
+
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
@@ -1,9 +1,15 @@
-use std::str;
-use std::convert::From;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::convert::{From, Infallible, TryFrom};
+use std::error::Error;
 use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::str;
+
+use super::PrettyOptions;
 
 /// In-memory representation of a RESP value.
-#[derive(Clone, Eq, PartialEq)]
+#[derive(Clone)]
 pub enum Value {
     /// An integer.
     Int(i64),
@@ -15,6 +21,84 @@ pub enum Value {
     BStr(Option<String>),
     /// An array.
     Array(Option<Vec<Value>>),
+    /// A null (RESP3).
+    Null,
+    /// A boolean (RESP3).
+    Bool(bool),
+    /// A double (RESP3).
+    Double(f64),
+    /// A big number (RESP3), as its decimal representation.
+    BigNum(String),
+    /// A blob error (RESP3).
+    BErr(String),
+    /// A verbatim string (RESP3), consisting of a three character format (e.g. `txt`) and the
+    /// string itself.
+    Verbatim(String, String),
+    /// A map (RESP3), as key-value pairs in the order they were received.
+    Map(Vec<(Value, Value)>),
+    /// A set (RESP3).
+    Set(Vec<Value>),
+    /// An out-of-band push (RESP3).
+    Push(Vec<Value>),
+}
+
+/// The variants of a [Value](Value), without their data.
+///
+/// Kinds are ordered as they're declared, which is how [Value](Value)s of different kinds are
+/// ordered too.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ValueKind {
+    Int,
+    Str,
+    Err,
+    BStr,
+    Array,
+    Null,
+    Bool,
+    Double,
+    BigNum,
+    BErr,
+    Verbatim,
+    Map,
+    Set,
+    Push,
+}
+
+impl ValueKind {
+    /// The kinds of value in RESP2.
+    pub const RESP2: &'static [ValueKind] = &[
+        ValueKind::Int,
+        ValueKind::Str,
+        ValueKind::Err,
+        ValueKind::BStr,
+        ValueKind::Array,
+    ];
+
+    /// The kinds of value in RESP3, i.e. every kind.
+    pub const RESP3: &'static [ValueKind] = &[
+        ValueKind::Int,
+        ValueKind::Str,
+        ValueKind::Err,
+        ValueKind::BStr,
+        ValueKind::Array,
+        ValueKind::Null,
+        ValueKind::Bool,
+        ValueKind::Double,
+        ValueKind::BigNum,
+        ValueKind::BErr,
+        ValueKind::Verbatim,
+        ValueKind::Map,
+        ValueKind::Set,
+        ValueKind::Push,
+    ];
+
+    /// Checks if values of this kind contain other values.
+    pub fn is_aggregate(self) -> bool {
+        matches!(
+            self,
+            ValueKind::Array | ValueKind::Map | ValueKind::Set | ValueKind::Push
+        )
+    }
 }
 
 impl Value {
@@ -26,6 +110,11 @@ impl Value {
     const ENCODED_NULL_ARRAY: &'static str = "*-1\r\n";
     const ENCODED_EMPTY_ARRAY: &'static str = "*0\r\n";
 
+    // RESP3 constants
+    const ENCODED_NULL: &'static str = "_\r\n";
+    const ENCODED_TRUE: &'static str = "#t\r\n";
+    const ENCODED_FALSE: &'static str = "#f\r\n";
+
     /// Encodes a `Value` as a string.
     ///
     /// # Examples
@@ -139,7 +228,59 @@ impl Value {
                     }
                 },
             },
+
+            &Value::Null => Value::ENCODED_NULL.to_owned(),
+
+            &Value::Bool(true) => Value::ENCODED_TRUE.to_owned(),
+
+            &Value::Bool(false) => Value::ENCODED_FALSE.to_owned(),
+
+            &Value::Double(datum) => {
+                let datum_str = if datum.is_nan() {
+                    "nan".to_owned()
+                } else {
+                    // Infinities are formatted as `inf` and `-inf`, as RESP3 expects
+                    datum.to_string()
+                };
+
+                format!(",{}\r\n", datum_str)
+            }
+
+            &Value::BigNum(ref datum) => format!("({}\r\n", datum),
+
+            &Value::BErr(ref datum) => format!("!{}\r\n{}\r\n", datum.len(), datum),
+
+            &Value::Verbatim(ref format, ref datum) => {
+                format!("={}\r\n{}:{}\r\n", datum.len() + 4, format, datum)
+            }
+
+            &Value::Map(ref pairs) => {
+                let mut encoded = format!("%{}\r\n", pairs.len());
+
+                for &(ref key, ref value) in pairs {
+                    encoded.push_str(&key.encode());
+
+                    encoded.push_str(&value.encode());
+                }
+
+                encoded
+            }
+
+            &Value::Set(ref data) => Value::encode_aggregate('~', data),
+
+            &Value::Push(ref data) => Value::encode_aggregate('>', data),
+        }
+    }
+
+    /// Encodes an aggregate of values, with the given type prefix.
+    fn encode_aggregate(prefix: char, data: &[Value]) -> String {
+        let mut encoded = format!("{}{}\r\n", prefix, data.len());
+
+        for value in data {
+            encoded.push_str(&value.encode());
         }
+
+        encoded
     }
 
     /// Encodes a `Value` as a vector of bytes.
@@ -159,7 +300,7 @@ impl Value {
 
     /// Checks if a `Value` is null.
     ///
-    /// NOTE: Only the `Array` and `BStr` types can represent a null value.
+    /// NOTE: Only the `Array`, `BStr` and `Null` types can represent a null value.
     ///
     /// # Examples
     ///
@@ -179,7 +320,7 @@ impl Value {
     #[inline]
     pub fn is_null(&self) -> bool {
         match self {
-            &Value::Array(None) | &Value::BStr(None) => true,
+            &Value::Array(None) | &Value::BStr(None) | &Value::Null => true,
 
             // No other types can represent null values
             _ => false,
@@ -230,6 +371,86 @@ impl Value {
 
                 &Some(ref items) => items.is_empty(),
             },
+
+            &Value::Null => true,
+
+            &Value::Bool(_) | &Value::Double(_) | &Value::BigNum(_) => false,
+
+            &Value::BErr(ref value) | &Value::Verbatim(_, ref value) => value.is_empty(),
+
+            &Value::Map(ref pairs) => pairs.is_empty(),
+
+            &Value::Set(ref items) | &Value::Push(ref items) => items.is_empty(),
+        }
+    }
+
+    /// Returns the kind of a `Value`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lib_resp::{Value, ValueKind};
+    /// assert_eq!(Value::BStr(None).kind(), ValueKind::BStr);
+    /// ```
+    pub fn kind(&self) -> ValueKind {
+        match *self {
+            Value::Int(_) => ValueKind::Int,
+            Value::Str(_) => ValueKind::Str,
+            Value::Err(_) => ValueKind::Err,
+            Value::BStr(_) => ValueKind::BStr,
+            Value::Array(_) => ValueKind::Array,
+            Value::Null => ValueKind::Null,
+            Value::Bool(_) => ValueKind::Bool,
+            Value::Double(_) => ValueKind::Double,
+            Value::BigNum(_) => ValueKind::BigNum,
+            Value::BErr(_) => ValueKind::BErr,
+            Value::Verbatim(..) => ValueKind::Verbatim,
+            Value::Map(_) => ValueKind::Map,
+            Value::Set(_) => ValueKind::Set,
+            Value::Push(_) => ValueKind::Push,
+        }
+    }
+
+    /// Sorts the items of sets and the pairs of maps, everywhere in a `Value`, so that values
+    /// which only differ in their order compare equal.
+    ///
+    /// NOTE: Arrays are left alone, as their order is meaningful. RESP2 replies to e.g.
+    /// `SMEMBERS` are arrays, so they need to be sorted by hand.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lib_resp::Value;
+    /// let mut members = Value::Set(vec![Value::b_str(Some("b")), Value::b_str(Some("a"))]);
+    ///
+    /// members.canonicalize();
+    ///
+    /// assert_eq!(
+    ///     members,
+    ///     Value::Set(vec![Value::b_str(Some("a")), Value::b_str(Some("b"))])
+    /// );
+    /// ```
+    pub fn canonicalize(&mut self) {
+        match *self {
+            Value::Array(Some(ref mut values)) | Value::Push(ref mut values) => {
+                values.iter_mut().for_each(Value::canonicalize);
+            }
+
+            Value::Set(ref mut values) => {
+                values.iter_mut().for_each(Value::canonicalize);
+                values.sort();
+            }
+
+            Value::Map(ref mut pairs) => {
+                for &mut (ref mut key, ref mut value) in pairs.iter_mut() {
+                    key.canonicalize();
+                    value.canonicalize();
+                }
+
+                pairs.sort();
+            }
+
+            _ => {}
         }
     }
 
@@ -324,6 +545,40 @@ impl Value {
     pub fn array(values: Option<Vec<Value>>) -> Self {
         Value::Array(values)
     }
+
+    /// Constructs a flat array of keys and values, the RESP2 form of a map.
+    ///
+    /// NOTE: Converting a `HashMap` with `From` gives a RESP3 [Map](Value::Map) instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lib_resp::Value;
+    /// let config = Value::flat_map(vec![("maxmemory", "0"), ("port", "6379")]);
+    ///
+    /// assert_eq!(
+    ///     config,
+    ///     Value::array(Some(vec![
+    ///         Value::b_str(Some("maxmemory")),
+    ///         Value::b_str(Some("0")),
+    ///         Value::b_str(Some("port")),
+    ///         Value::b_str(Some("6379")),
+    ///     ]))
+    /// );
+    /// ```
+    pub fn flat_map<I, K, V>(pairs: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<Value>,
+        V: Into<Value>,
+    {
+        Value::Array(Some(
+            pairs
+                .into_iter()
+                .flat_map(|(key, value)| vec![key.into(), value.into()])
+                .collect(),
+        ))
+    }
 }
 
 impl Debug for Value {
@@ -366,48 +621,499 @@ impl Debug for Value {
                     &None => write!(f, "-1]"),
                 }
             }
+
+            &Value::Null => write!(f, "Null"),
+
+            &Value::Bool(datum) => write!(f, "Bool({})", datum),
+
+            &Value::Double(datum) => write!(f, "Double({})", datum),
+
+            &Value::BigNum(ref datum) => write!(f, "BigNum({})", datum),
+
+            &Value::BErr(ref datum) => write!(f, r#"BErr("{}")"#, datum),
+
+            &Value::Verbatim(ref format, ref datum) => {
+                write!(f, r#"Verbatim({}, "{}")"#, format, datum)
+            }
+
+            &Value::Map(ref pairs) => {
+                write!(f, "Map[{}](", pairs.len())?;
+
+                for (i, &(ref key, ref value)) in pairs.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    write!(f, "{:?} => {:?}", key, value)?;
+                }
+
+                write!(f, ")")
+            }
+
+            &Value::Set(ref data) => debug_aggregate(f, "Set", data),
+
+            &Value::Push(ref data) => debug_aggregate(f, "Push", data),
+        }
+    }
+}
+
+fn debug_aggregate(f: &mut Formatter, name: &str, data: &[Value]) -> FmtResult {
+    write!(f, "{}[{}](", name, data.len())?;
+
+    for (i, datum) in data.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
         }
+
+        write!(f, "{:?}", datum)?;
     }
+
+    write!(f, ")")
 }
 
+/// Formats a `Value` like `redis-cli` does, see [Value::pretty](Value::pretty).
 impl Display for Value {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
-        match self {
-            &Value::Int(ref datum) => write!(f, "(integer) {}", datum),
+        self.pretty(&PrettyOptions::default()).fmt(f)
+    }
+}
 
-            &Value::Str(ref datum) => write!(f, "{}", datum),
+impl PartialEq for Value {
+    fn eq(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => a == b,
 
-            &Value::Err(ref datum) => write!(f, "(error) {}", datum),
+            (Value::Str(a), Value::Str(b)) => a == b,
 
-            &Value::BStr(ref value) => match value {
-                &Some(ref datum) => write!(f, r#""{}""#, datum),
+            (Value::Err(a), Value::Err(b)) => a == b,
 
-                &None => write!(f, r#""""#),
-            },
+            (Value::BStr(a), Value::BStr(b)) => a == b,
 
-            &Value::Array(ref value) => match value {
-                &Some(ref data) => {
-                    for (i, datum) in data.iter().enumerate() {
-                        let n = i + 1;
+            (Value::Array(a), Value::Array(b)) => a == b,
 
-                        write!(f, "{}) {}", n, datum)?;
+            (Value::Null, Value::Null) => true,
 
-                        if n < data.len() {
-                            write!(f, "\r\n")?;
-                        }
-                    }
+            (Value::Bool(a), Value::Bool(b)) => a == b,
 
-                    Ok(())
-                }
+            // Doubles are compared by representation, so `NaN` equals itself and `Eq` holds
+            (Value::Double(a), Value::Double(b)) => a.to_bits() == b.to_bits(),
 
-                &None => write!(f, "(empty list or set)"),
-            },
+            (Value::BigNum(a), Value::BigNum(b)) => a == b,
+
+            (Value::BErr(a), Value::BErr(b)) => a == b,
+
+            (Value::Verbatim(a_format, a), Value::Verbatim(b_format, b)) => {
+                a_format == b_format && a == b
+            }
+
+            (Value::Map(a), Value::Map(b)) => a == b,
+
+            (Value::Set(a), Value::Set(b)) => a == b,
+
+            (Value::Push(a), Value::Push(b)) => a == b,
+
+            _ => false,
         }
     }
 }
 
+impl Eq for Value {}
+
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.kind().hash(state);
+
+        match *self {
+            Value::Int(datum) => datum.hash(state),
+
+            Value::Str(ref datum)
+            | Value::Err(ref datum)
+            | Value::BigNum(ref datum)
+            | Value::BErr(ref datum) => datum.hash(state),
+
+            Value::BStr(ref datum) => datum.hash(state),
+
+            Value::Array(ref values) => values.hash(state),
+
+            Value::Null => {}
+
+            Value::Bool(datum) => datum.hash(state),
+
+            // Like equality, by representation
+            Value::Double(datum) => datum.to_bits().hash(state),
+
+            Value::Verbatim(ref format, ref datum) => {
+                format.hash(state);
+                datum.hash(state);
+            }
+
+            Value::Map(ref pairs) => pairs.hash(state),
+
+            Value::Set(ref values) | Value::Push(ref values) => values.hash(state),
+        }
+    }
+}
+
+/// Values are ordered by their [kind](ValueKind) first, then by their contents.
+///
+/// Nulls come before anything else of their kind, and doubles are ordered by
+/// [total_cmp](f64::total_cmp), so `-0.0 < 0.0`, and `NaN`s come before or after everything else
+/// depending on their sign.
+impl Ord for Value {
+    fn cmp(&self, other: &Value) -> Ordering {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => a.cmp(b),
+
+            (Value::Str(a), Value::Str(b)) => a.cmp(b),
+
+            (Value::Err(a), Value::Err(b)) => a.cmp(b),
+
+            (Value::BStr(a), Value::BStr(b)) => a.cmp(b),
+
+            (Value::Array(a), Value::Array(b)) => a.cmp(b),
+
+            (Value::Null, Value::Null) => Ordering::Equal,
+
+            (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+
+            (Value::Double(a), Value::Double(b)) => a.total_cmp(b),
+
+            (Value::BigNum(a), Value::BigNum(b)) => a.cmp(b),
+
+            (Value::BErr(a), Value::BErr(b)) => a.cmp(b),
+
+            (Value::Verbatim(a_format, a), Value::Verbatim(b_format, b)) => {
+                a_format.cmp(b_format).then_with(|| a.cmp(b))
+            }
+
+            (Value::Map(a), Value::Map(b)) => a.cmp(b),
+
+            (Value::Set(a), Value::Set(b)) => a.cmp(b),
+
+            (Value::Push(a), Value::Push(b)) => a.cmp(b),
+
+            (a, b) => a.kind().cmp(&b.kind()),
+        }
+    }
+}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Value) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl From<i64> for Value {
     fn from(value: i64) -> Self {
         Value::int(value)
     }
 }
+
+// `u8` is missing on purpose, so that `Vec<u8>` is a bulk string rather than an array
+macro_rules! impl_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Value {
+                fn from(value: $t) -> Self {
+                    Value::Int(i64::from(value))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int!(i8, i16, i32, u16, u32);
+
+// Integers outside the range of `i64` become big numbers, like they do in Redis
+macro_rules! impl_from_wide_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Value {
+                fn from(value: $t) -> Self {
+                    match i64::try_from(value) {
+                        Ok(datum) => Value::Int(datum),
+
+                        Err(_) => Value::BigNum(value.to_string()),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_wide_int!(isize, u64, usize, i128, u128);
+
+impl<'a> From<&'a str> for Value {
+    fn from(value: &'a str) -> Self {
+        Value::BStr(Some(value.to_string()))
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::BStr(Some(value))
+    }
+}
+
+/// NOTE: Bulk strings are stored as `String`s, so invalid UTF-8 is replaced with `U+FFFD`.
+impl<'a> From<&'a [u8]> for Value {
+    fn from(value: &'a [u8]) -> Self {
+        Value::BStr(Some(String::from_utf8_lossy(value).into_owned()))
+    }
+}
+
+/// NOTE: Bulk strings are stored as `String`s, so invalid UTF-8 is replaced with `U+FFFD`.
+impl From<Vec<u8>> for Value {
+    fn from(value: Vec<u8>) -> Self {
+        match String::from_utf8(value) {
+            Ok(datum) => Value::BStr(Some(datum)),
+
+            Err(e) => Value::from(e.as_bytes()),
+        }
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+/// `None` becomes a null bulk string, the RESP2 null.
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(value) => value.into(),
+
+            None => Value::BStr(None),
+        }
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(values: Vec<T>) -> Self {
+        Value::Array(Some(values.into_iter().map(Into::into).collect()))
+    }
+}
+
+/// Converts to a RESP3 [Map](Value::Map).
+///
+/// NOTE: Maps are RESP3 only, RESP2 peers expect a flat array of keys and values instead, see
+/// [Value::flat_map](Value::flat_map).
+impl<K: Into<Value>, V: Into<Value>, S: BuildHasher> From<HashMap<K, V, S>> for Value {
+    fn from(map: HashMap<K, V, S>) -> Self {
+        Value::Map(
+            map.into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+        )
+    }
+}
+
+/// A [Value](Value) which can't be converted to the requested type.
+///
+/// # Examples
+///
+/// ```
+/// # use lib_resp::Value;
+/// # use std::convert::TryFrom;
+/// assert_eq!(i64::try_from(Value::b_str(Some("42"))), Ok(42));
+///
+/// let error = i64::try_from(Value::err("ERR no such key")).unwrap_err();
+///
+/// assert_eq!(error.value(), &Value::err("ERR no such key"));
+/// assert_eq!(error.target(), "i64");
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct FromValueError {
+    value: Value,
+    target: &'static str,
+}
+
+impl FromValueError {
+    fn new(value: Value, target: &'static str) -> Self {
+        FromValueError { value, target }
+    }
+
+    /// Returns the value which couldn't be converted.
+    ///
+    /// NOTE: When converting aggregates, this is the item which couldn't be converted.
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    /// Returns the value which couldn't be converted, consuming the error.
+    pub fn into_value(self) -> Value {
+        self.value
+    }
+
+    /// Returns the name of the type the value couldn't be converted to.
+    pub fn target(&self) -> &'static str {
+        self.target
+    }
+}
+
+impl Display for FromValueError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self.value {
+            Value::Err(ref error) | Value::BErr(ref error) => {
+                write!(f, "Can't convert error {:?} to {}", error, self.target)
+            }
+
+            ref value => write!(f, "Can't convert {:?} to {}", value.kind(), self.target),
+        }
+    }
+}
+
+impl Error for FromValueError {}
+
+// Lets `Value` itself be the target of aggregate conversions
+impl From<Infallible> for FromValueError {
+    fn from(never: Infallible) -> Self {
+        match never {}
+    }
+}
+
+/// Integers can be converted from integers, big numbers and strings of digits, as Redis often
+/// replies with numbers as bulk strings.
+macro_rules! impl_try_from_value_int {
+    ($($t:ty),*) => {
+        $(
+            impl TryFrom<Value> for $t {
+                type Error = FromValueError;
+
+                fn try_from(value: Value) -> Result<Self, FromValueError> {
+                    let converted = match value {
+                        Value::Int(datum) => <$t>::try_from(datum).ok(),
+
+                        Value::Str(ref datum)
+                        | Value::BStr(Some(ref datum))
+                        | Value::BigNum(ref datum)
+                        | Value::Verbatim(_, ref datum) => datum.parse().ok(),
+
+                        _ => None,
+                    };
+
+                    converted.ok_or_else(|| FromValueError::new(value, stringify!($t)))
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_value_int!(i8, i16, i32, i64, isize, u16, u32, u64, usize, i128, u128);
+
+impl TryFrom<Value> for String {
+    type Error = FromValueError;
+
+    fn try_from(value: Value) -> Result<Self, FromValueError> {
+        into_string(value, "String")
+    }
+}
+
+impl TryFrom<Value> for Vec<u8> {
+    type Error = FromValueError;
+
+    fn try_from(value: Value) -> Result<Self, FromValueError> {
+        into_string(value, "Vec<u8>").map(String::into_bytes)
+    }
+}
+
+/// Booleans can also be converted from the integers `0` and `1`, which RESP2 uses instead.
+impl TryFrom<Value> for bool {
+    type Error = FromValueError;
+
+    fn try_from(value: Value) -> Result<Self, FromValueError> {
+        match value {
+            Value::Bool(datum) => Ok(datum),
+            Value::Int(0) => Ok(false),
+            Value::Int(1) => Ok(true),
+
+            value => Err(FromValueError::new(value, "bool")),
+        }
+    }
+}
+
+/// Any null, i.e. a null bulk string, a null array or a RESP3 null, becomes `None`.
+///
+/// NOTE: `Option<Value>` uses the standard library's conversion instead, which is always `Some`.
+impl<T: TryFrom<Value, Error = FromValueError>> TryFrom<Value> for Option<T> {
+    type Error = FromValueError;
+
+    fn try_from(value: Value) -> Result<Self, FromValueError> {
+        match value {
+            Value::BStr(None) | Value::Array(None) | Value::Null => Ok(None),
+
+            value => T::try_from(value).map(Some),
+        }
+    }
+}
+
+/// Arrays, sets and pushes can be converted to vectors.
+impl<T> TryFrom<Value> for Vec<T>
+where
+    T: TryFrom<Value>,
+    T::Error: Into<FromValueError>,
+{
+    type Error = FromValueError;
+
+    fn try_from(value: Value) -> Result<Self, FromValueError> {
+        match value {
+            Value::Array(Some(values)) | Value::Set(values) | Value::Push(values) => values
+                .into_iter()
+                .map(|value| T::try_from(value).map_err(Into::into))
+                .collect(),
+
+            value => Err(FromValueError::new(value, "Vec")),
+        }
+    }
+}
+
+/// Maps, and arrays of alternating keys and values (RESP2's maps), can be converted to hash maps.
+impl<K, V, S> TryFrom<Value> for HashMap<K, V, S>
+where
+    K: TryFrom<Value> + Eq + Hash,
+    K::Error: Into<FromValueError>,
+    V: TryFrom<Value>,
+    V::Error: Into<FromValueError>,
+    S: BuildHasher + Default,
+{
+    type Error = FromValueError;
+
+    fn try_from(value: Value) -> Result<Self, FromValueError> {
+        let pairs = match value {
+            Value::Map(pairs) => pairs,
+
+            Value::Array(Some(values)) if values.len() % 2 == 0 => {
+                let mut values = values.into_iter();
+                let mut pairs = Vec::with_capacity(values.len() / 2);
+
+                while let (Some(key), Some(value)) = (values.next(), values.next()) {
+                    pairs.push((key, value));
+                }
+
+                pairs
+            }
+
+            value => return Err(FromValueError::new(value, "HashMap")),
+        };
+
+        pairs
+            .into_iter()
+            .map(|(key, value)| {
+                Ok((
+                    K::try_from(key).map_err(Into::into)?,
+                    V::try_from(value).map_err(Into::into)?,
+                ))
+            })
+            .collect()
+    }
+}
+
+fn into_string(value: Value, target: &'static str) -> Result<String, FromValueError> {
+    match value {
+        Value::Str(datum) | Value::BStr(Some(datum)) | Value::Verbatim(_, datum) => Ok(datum),
+
+        value => Err(FromValueError::new(value, target)),
+    }
+}
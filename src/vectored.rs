@@ -0,0 +1,304 @@
+use std::io::{self, IoSlice, Write};
+use std::ops::Range;
+
+use super::Value;
+
+/// Payloads up to this many bytes are copied next to their headers instead of borrowed, as a
+/// slice per small string costs more than the copy.
+const MAX_COPIED_LEN: usize = 64;
+
+enum Part<'a> {
+    /// Bytes of the scratch buffer.
+    Scratch(Range<usize>),
+    /// A payload borrowed from the value.
+    Borrowed(&'a [u8]),
+}
+
+impl<'a> Part<'a> {
+    fn bytes<'b>(&'b self, scratch: &'b [u8]) -> &'b [u8] {
+        match *self {
+            Part::Scratch(ref range) => &scratch[range.clone()],
+
+            Part::Borrowed(bytes) => bytes,
+        }
+    }
+}
+
+/// How much of the parts has been written.
+struct Cursor {
+    /// The number of bytes written.
+    written: usize,
+    /// The first part which hasn't been written completely.
+    part: usize,
+    /// The number of bytes of that part which have been written.
+    offset: usize,
+}
+
+impl Cursor {
+    /// Moves past `n` more bytes, starting from the current part rather than the first.
+    fn advance(&mut self, n: usize, parts: &[Part], scratch: &[u8]) {
+        self.written += n;
+
+        let mut n = n;
+
+        while n > 0 {
+            let left = parts[self.part].bytes(scratch).len() - self.offset;
+
+            if n < left {
+                self.offset += n;
+
+                return;
+            }
+
+            n -= left;
+
+            self.part += 1;
+            self.offset = 0;
+        }
+    }
+
+    /// Returns the slices which are left to write.
+    fn slices<'b>(&self, parts: &'b [Part], scratch: &'b [u8]) -> Vec<IoSlice<'b>> {
+        let mut slices = Vec::with_capacity(parts.len() - self.part);
+
+        for (i, part) in parts[self.part..].iter().enumerate() {
+            let bytes = part.bytes(scratch);
+
+            slices.push(IoSlice::new(if i == 0 {
+                &bytes[self.offset..]
+            } else {
+                bytes
+            }));
+        }
+
+        slices
+    }
+}
+
+/// A [Value](Value) encoded as a list of slices, for [Write::write_vectored], see
+/// [Value::encode_vectored](Value::encode_vectored).
+///
+/// Headers and small payloads are written to a scratch buffer, and larger payloads are borrowed
+/// from the value rather than copied. The encoder keeps track of how much has been written, so a
+/// partial write can be resumed.
+pub struct VectoredEncoder<'a> {
+    scratch: Vec<u8>,
+    parts: Vec<Part<'a>>,
+    len: usize,
+    cursor: Cursor,
+}
+
+impl Value {
+    /// Encodes a `Value` without copying large payloads, for [Write::write_vectored].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lib_resp::Value;
+    /// let value = Value::array(Some(vec![
+    ///     Value::int(1),
+    ///     Value::b_str(Some("a".repeat(1024))),
+    /// ]));
+    ///
+    /// let mut encoder = value.encode_vectored();
+    ///
+    /// // The header, the borrowed payload and the trailing CRLF
+    /// assert_eq!(encoder.slices().len(), 3);
+    ///
+    /// let mut encoded = Vec::new();
+    ///
+    /// encoder.write_to(&mut encoded).unwrap();
+    ///
+    /// assert_eq!(encoded, value.encode_bytes());
+    /// ```
+    pub fn encode_vectored(&self) -> VectoredEncoder<'_> {
+        let mut encoder = VectoredEncoder {
+            scratch: Vec::new(),
+            parts: Vec::new(),
+            len: 0,
+            cursor: Cursor {
+                written: 0,
+                part: 0,
+                offset: 0,
+            },
+        };
+
+        encoder.encode(self);
+
+        encoder
+    }
+}
+
+impl<'a> VectoredEncoder<'a> {
+    /// Returns the number of bytes written so far.
+    pub fn written(&self) -> usize {
+        self.cursor.written
+    }
+
+    /// Returns the number of bytes left to write.
+    pub fn remaining(&self) -> usize {
+        self.len - self.cursor.written
+    }
+
+    /// Checks if every byte has been written.
+    pub fn is_done(&self) -> bool {
+        self.cursor.written == self.len
+    }
+
+    /// Returns the slices which are left to write.
+    pub fn slices(&self) -> Vec<IoSlice<'_>> {
+        self.cursor.slices(&self.parts, &self.scratch)
+    }
+
+    /// Marks `n` more bytes as written, e.g. after a call to [Write::write_vectored].
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer than `n` bytes are left to write.
+    pub fn advance(&mut self, n: usize) {
+        assert!(
+            n <= self.remaining(),
+            "advancing past the end of the encoded value"
+        );
+
+        self.cursor.advance(n, &self.parts, &self.scratch);
+    }
+
+    /// Writes what's left of the value to `dst`, retrying partial and interrupted writes.
+    ///
+    /// NOTE: If `dst` fails, e.g. with `WouldBlock`, the bytes written until then are kept track
+    /// of, so calling this again resumes the write.
+    pub fn write_to<W: Write>(&mut self, dst: &mut W) -> io::Result<()> {
+        let (parts, scratch, cursor) = (&self.parts, &self.scratch, &mut self.cursor);
+
+        // The slices are only built once, after a write the ones which were written are skipped,
+        // and the one written partway is cut short
+        let first = cursor.part;
+        let mut slices = cursor.slices(parts, scratch);
+
+        while cursor.written < self.len {
+            match dst.write_vectored(&slices[cursor.part - first..]) {
+                Ok(0) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "failed to write the whole value",
+                    ));
+                }
+
+                Ok(n) => {
+                    assert!(
+                        n <= self.len - cursor.written,
+                        "advancing past the end of the encoded value"
+                    );
+
+                    cursor.advance(n, parts, scratch);
+
+                    if cursor.offset > 0 {
+                        let bytes = parts[cursor.part].bytes(scratch);
+
+                        slices[cursor.part - first] = IoSlice::new(&bytes[cursor.offset..]);
+                    }
+                }
+
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn encode(&mut self, value: &'a Value) {
+        match *value {
+            Value::Str(ref datum) => {
+                self.push_scratch(b"+");
+                self.push_payload(datum.as_bytes());
+                self.push_scratch(b"\r\n");
+            }
+
+            Value::Err(ref datum) => {
+                self.push_scratch(b"-");
+                self.push_payload(datum.as_bytes());
+                self.push_scratch(b"\r\n");
+            }
+
+            Value::BStr(Some(ref datum)) => {
+                self.push_header('$', datum.len());
+                self.push_payload(datum.as_bytes());
+                self.push_scratch(b"\r\n");
+            }
+
+            Value::BErr(ref datum) => {
+                self.push_header('!', datum.len());
+                self.push_payload(datum.as_bytes());
+                self.push_scratch(b"\r\n");
+            }
+
+            Value::Verbatim(ref format, ref datum) => {
+                self.push_header('=', datum.len() + 4);
+                self.push_scratch(format.as_bytes());
+                self.push_scratch(b":");
+                self.push_payload(datum.as_bytes());
+                self.push_scratch(b"\r\n");
+            }
+
+            Value::Array(Some(ref values)) => self.encode_aggregate('*', values),
+
+            Value::Set(ref values) => self.encode_aggregate('~', values),
+
+            Value::Push(ref values) => self.encode_aggregate('>', values),
+
+            Value::Map(ref pairs) => {
+                self.push_header('%', pairs.len());
+
+                for (key, value) in pairs {
+                    self.encode(key);
+                    self.encode(value);
+                }
+            }
+
+            // Nothing worth borrowing
+            _ => self.push_scratch(value.encode().as_bytes()),
+        }
+    }
+
+    fn encode_aggregate(&mut self, prefix: char, values: &'a [Value]) {
+        self.push_header(prefix, values.len());
+
+        for value in values {
+            self.encode(value);
+        }
+    }
+
+    fn push_header(&mut self, prefix: char, len: usize) {
+        self.push_scratch(format!("{}{}\r\n", prefix, len).as_bytes());
+    }
+
+    /// Copies `bytes` to the scratch buffer, extending the last part if it's also scratch.
+    fn push_scratch(&mut self, bytes: &[u8]) {
+        let start = self.scratch.len();
+
+        self.scratch.extend_from_slice(bytes);
+
+        let end = self.scratch.len();
+
+        match self.parts.last_mut() {
+            Some(&mut Part::Scratch(ref mut range)) if range.end == start => range.end = end,
+
+            _ => self.parts.push(Part::Scratch(start..end)),
+        }
+
+        self.len += bytes.len();
+    }
+
+    fn push_payload(&mut self, bytes: &'a [u8]) {
+        if bytes.len() <= MAX_COPIED_LEN {
+            return self.push_scratch(bytes);
+        }
+
+        self.parts.push(Part::Borrowed(bytes));
+
+        self.len += bytes.len();
+    }
+}
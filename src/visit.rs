@@ -0,0 +1,273 @@
+use std::mem;
+
+use super::Value;
+
+/// Callbacks for [walking](Value::walk) a [Value](Value) tree.
+///
+/// Every callback does nothing by default. Aggregates call their `enter_` callback, then visit
+/// their items, then call their `leave_` callback. The items of maps are visited as keys and
+/// values, alternately.
+///
+/// # Examples
+///
+/// ```
+/// # use lib_resp::{Value, Visitor};
+/// #[derive(Default)]
+/// struct Depth {
+///     depth: usize,
+///     max: usize,
+/// }
+///
+/// impl Visitor for Depth {
+///     fn enter_array(&mut self, _len: Option<usize>) {
+///         self.depth += 1;
+///         self.max = self.max.max(self.depth);
+///     }
+///
+///     fn leave_array(&mut self) {
+///         self.depth -= 1;
+///     }
+/// }
+///
+/// let value = Value::array(Some(vec![Value::array(Some(vec![])), Value::int(1)]));
+///
+/// let mut depth = Depth::default();
+///
+/// value.walk(&mut depth);
+///
+/// assert_eq!(depth.max, 2);
+/// ```
+pub trait Visitor {
+    /// Visits an integer.
+    fn visit_int(&mut self, _datum: i64) {}
+
+    /// Visits a simple string.
+    fn visit_str(&mut self, _datum: &str) {}
+
+    /// Visits an error.
+    fn visit_err(&mut self, _error: &str) {}
+
+    /// Visits a bulk string, `None` if it's null.
+    fn visit_b_str(&mut self, _datum: Option<&str>) {}
+
+    /// Visits a null (RESP3).
+    fn visit_null(&mut self) {}
+
+    /// Visits a boolean (RESP3).
+    fn visit_bool(&mut self, _datum: bool) {}
+
+    /// Visits a double (RESP3).
+    fn visit_double(&mut self, _datum: f64) {}
+
+    /// Visits a big number (RESP3).
+    fn visit_big_num(&mut self, _datum: &str) {}
+
+    /// Visits a blob error (RESP3).
+    fn visit_b_err(&mut self, _error: &str) {}
+
+    /// Visits a verbatim string (RESP3).
+    fn visit_verbatim(&mut self, _format: &str, _datum: &str) {}
+
+    /// Enters an array of `len` items, `None` if it's null.
+    fn enter_array(&mut self, _len: Option<usize>) {}
+
+    /// Leaves an array.
+    fn leave_array(&mut self) {}
+
+    /// Enters a map of `len` pairs (RESP3).
+    fn enter_map(&mut self, _len: usize) {}
+
+    /// Leaves a map (RESP3).
+    fn leave_map(&mut self) {}
+
+    /// Enters a set of `len` items (RESP3).
+    fn enter_set(&mut self, _len: usize) {}
+
+    /// Leaves a set (RESP3).
+    fn leave_set(&mut self) {}
+
+    /// Enters a push of `len` items (RESP3).
+    fn enter_push(&mut self, _len: usize) {}
+
+    /// Leaves a push (RESP3).
+    fn leave_push(&mut self) {}
+}
+
+/// A step of a walk, kept on the heap rather than the native stack.
+enum Step<'a> {
+    Visit(&'a Value),
+    Leave(&'a Value),
+}
+
+impl Value {
+    /// Walks a `Value` tree depth first, calling the `visitor` for each value.
+    ///
+    /// NOTE: The walk doesn't recurse, so it's safe on arbitrarily deep values.
+    pub fn walk<V: Visitor>(&self, visitor: &mut V) {
+        let mut steps = vec![Step::Visit(self)];
+
+        while let Some(step) = steps.pop() {
+            let value = match step {
+                Step::Visit(value) => value,
+
+                Step::Leave(value) => {
+                    match *value {
+                        Value::Array(_) => visitor.leave_array(),
+                        Value::Map(_) => visitor.leave_map(),
+                        Value::Set(_) => visitor.leave_set(),
+                        Value::Push(_) => visitor.leave_push(),
+
+                        _ => unreachable!("{:?} isn't an aggregate", value.kind()),
+                    }
+
+                    continue;
+                }
+            };
+
+            match *value {
+                Value::Int(datum) => visitor.visit_int(datum),
+                Value::Str(ref datum) => visitor.visit_str(datum),
+                Value::Err(ref error) => visitor.visit_err(error),
+                Value::BStr(ref datum) => visitor.visit_b_str(datum.as_ref().map(String::as_str)),
+                Value::Null => visitor.visit_null(),
+                Value::Bool(datum) => visitor.visit_bool(datum),
+                Value::Double(datum) => visitor.visit_double(datum),
+                Value::BigNum(ref datum) => visitor.visit_big_num(datum),
+                Value::BErr(ref error) => visitor.visit_b_err(error),
+                Value::Verbatim(ref format, ref datum) => visitor.visit_verbatim(format, datum),
+
+                Value::Array(ref values) => {
+                    visitor.enter_array(values.as_ref().map(Vec::len));
+
+                    steps.push(Step::Leave(value));
+
+                    if let Some(ref values) = *values {
+                        steps.extend(values.iter().rev().map(Step::Visit));
+                    }
+                }
+
+                Value::Map(ref pairs) => {
+                    visitor.enter_map(pairs.len());
+
+                    steps.push(Step::Leave(value));
+
+                    for (key, value) in pairs.iter().rev() {
+                        steps.push(Step::Visit(value));
+                        steps.push(Step::Visit(key));
+                    }
+                }
+
+                Value::Set(ref values) => {
+                    visitor.enter_set(values.len());
+
+                    steps.push(Step::Leave(value));
+                    steps.extend(values.iter().rev().map(Step::Visit));
+                }
+
+                Value::Push(ref values) => {
+                    visitor.enter_push(values.len());
+
+                    steps.push(Step::Leave(value));
+                    steps.extend(values.iter().rev().map(Step::Visit));
+                }
+            }
+        }
+    }
+
+    /// Walks a `Value` tree depth first, calling `f` with each value before its items.
+    ///
+    /// If `f` replaces an aggregate, the items of the replacement are walked instead.
+    ///
+    /// NOTE: The walk doesn't recurse, so it's safe on arbitrarily deep values.
+    ///
+    /// # Examples
+    ///
+    /// Redacting a secret wherever it's nested:
+    ///
+    /// ```
+    /// # use lib_resp::Value;
+    /// let mut reply = Value::array(Some(vec![
+    ///     Value::b_str(Some("user")),
+    ///     Value::array(Some(vec![Value::b_str(Some("hunter2"))])),
+    /// ]));
+    ///
+    /// reply.walk_mut(|value| {
+    ///     if let Value::BStr(Some(ref mut datum)) = *value {
+    ///         if datum == "hunter2" {
+    ///             *datum = "<redacted>".to_owned();
+    ///         }
+    ///     }
+    /// });
+    ///
+    /// assert_eq!(reply.pointer("/1/0"), Some(&Value::b_str(Some("<redacted>"))));
+    /// ```
+    pub fn walk_mut<F: FnMut(&mut Value)>(&mut self, mut f: F) {
+        let mut values = vec![self];
+
+        while let Some(value) = values.pop() {
+            f(value);
+
+            push_items(value, &mut values);
+        }
+    }
+
+    /// Replaces every leaf of a `Value` tree, i.e. every value other than a (non-null) array,
+    /// map, set or push, with the result of `f`.
+    ///
+    /// Leaves are replaced in order, and the leaves `f` returns aren't walked again.
+    ///
+    /// NOTE: The walk doesn't recurse, so it's safe on arbitrarily deep values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use lib_resp::Value;
+    /// let value = Value::array(Some(vec![Value::int(1), Value::Set(vec![Value::int(2)])]));
+    ///
+    /// let doubled = value.map_leaves(|leaf| match leaf {
+    ///     Value::Int(datum) => Value::Int(datum * 2),
+    ///
+    ///     leaf => leaf,
+    /// });
+    ///
+    /// assert_eq!(
+    ///     doubled,
+    ///     Value::array(Some(vec![Value::int(2), Value::Set(vec![Value::int(4)])]))
+    /// );
+    /// ```
+    pub fn map_leaves<F: FnMut(Value) -> Value>(mut self, mut f: F) -> Self {
+        {
+            let mut values = vec![&mut self];
+
+            while let Some(value) = values.pop() {
+                if value.as_array().is_some() || value.as_map().is_some() {
+                    push_items(value, &mut values);
+                } else {
+                    let leaf = mem::replace(value, Value::Null);
+
+                    *value = f(leaf);
+                }
+            }
+        }
+
+        self
+    }
+}
+
+/// Pushes the items of an aggregate, so that they're popped in order.
+fn push_items<'a>(value: &'a mut Value, values: &mut Vec<&'a mut Value>) {
+    match *value {
+        Value::Array(Some(ref mut items))
+        | Value::Set(ref mut items)
+        | Value::Push(ref mut items) => values.extend(items.iter_mut().rev()),
+
+        Value::Map(ref mut pairs) => {
+            for &mut (ref mut key, ref mut value) in pairs.iter_mut().rev() {
+                values.push(value);
+                values.push(key);
+            }
+        }
+
+        _ => {}
+    }
+}
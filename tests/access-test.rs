@@ -0,0 +1,215 @@
+extern crate lib_resp;
+
+use lib_resp::Value;
+
+mod test_as {
+    use super::*;
+
+    #[test]
+    fn int() {
+        assert_eq!(Value::int(-1).as_int(), Some(-1));
+        assert_eq!(Value::str("1").as_int(), None);
+        assert_eq!(Value::Double(1.0).as_int(), None);
+    }
+
+    #[test]
+    fn str() {
+        assert_eq!(Value::str("OK").as_str(), Some("OK"));
+        assert_eq!(Value::b_str(Some("foo")).as_str(), Some("foo"));
+        assert_eq!(
+            Value::Verbatim("txt".to_owned(), "bar".to_owned()).as_str(),
+            Some("bar")
+        );
+
+        assert_eq!(Value::BStr(None).as_str(), None);
+        assert_eq!(Value::err("ERR").as_str(), None);
+        assert_eq!(Value::BErr("ERR".to_owned()).as_str(), None);
+        assert_eq!(Value::int(1).as_str(), None);
+    }
+
+    #[test]
+    fn bytes() {
+        assert_eq!(Value::b_str(Some("foo")).as_bytes(), Some(&b"foo"[..]));
+        assert_eq!(Value::BStr(None).as_bytes(), None);
+    }
+
+    #[test]
+    fn array() {
+        let items = vec![Value::int(1), Value::int(2)];
+
+        assert_eq!(
+            Value::array(Some(items.clone())).as_array(),
+            Some(&items[..])
+        );
+        assert_eq!(Value::Set(items.clone()).as_array(), Some(&items[..]));
+        assert_eq!(Value::Push(items.clone()).as_array(), Some(&items[..]));
+
+        assert_eq!(Value::Array(None).as_array(), None);
+        assert_eq!(Value::Map(vec![]).as_array(), None);
+
+        assert_eq!(Value::array(Some(items.clone())).into_array(), Some(items));
+        assert_eq!(Value::Null.into_array(), None);
+    }
+
+    #[test]
+    fn map() {
+        let pairs = vec![(Value::str("a"), Value::int(1))];
+
+        assert_eq!(Value::Map(pairs.clone()).as_map(), Some(&pairs[..]));
+        assert_eq!(Value::array(Some(vec![])).as_map(), None);
+    }
+}
+
+mod test_is_error {
+    use super::*;
+
+    #[test]
+    fn errors() {
+        assert!(Value::err("ERR").is_error());
+        assert!(Value::BErr("ERR".to_owned()).is_error());
+
+        assert!(!Value::str("ERR").is_error());
+        assert!(!Value::Null.is_error());
+    }
+}
+
+mod test_items {
+    use super::*;
+
+    #[test]
+    fn get() {
+        let value = Value::array(Some(vec![Value::int(1), Value::int(2)]));
+
+        assert_eq!(value.get(1), Some(&Value::int(2)));
+        assert_eq!(value.get(2), None);
+        assert_eq!(Value::int(1).get(0), None);
+    }
+
+    #[test]
+    fn index() {
+        let value = Value::array(Some(vec![Value::int(1), Value::Set(vec![Value::str("a")])]));
+
+        assert_eq!(value[0], Value::int(1));
+        assert_eq!(value[1][0], Value::str("a"));
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn index_out_of_bounds() {
+        let _ = &Value::array(Some(vec![]))[0];
+    }
+
+    #[test]
+    #[should_panic(expected = "Can't index into Str")]
+    fn index_scalar() {
+        let _ = &Value::str("OK")[0];
+    }
+
+    #[test]
+    fn iter() {
+        let value = Value::array(Some(vec![Value::int(1), Value::int(2)]));
+
+        assert_eq!(
+            value.iter().collect::<Vec<_>>(),
+            vec![&Value::int(1), &Value::int(2)]
+        );
+
+        let mut sum = 0;
+
+        for item in &value {
+            sum += item.as_int().unwrap();
+        }
+
+        assert_eq!(sum, 3);
+
+        assert_eq!(Value::Array(None).iter().count(), 0);
+        assert_eq!(Value::str("OK").iter().count(), 0);
+    }
+
+    #[test]
+    fn len() {
+        assert_eq!(Value::array(Some(vec![Value::Null; 3])).len(), 3);
+        assert_eq!(Value::Map(vec![(Value::Null, Value::Null)]).len(), 1);
+        assert_eq!(Value::b_str(Some("foo")).len(), 3);
+        assert_eq!(Value::str("").len(), 0);
+        assert_eq!(Value::int(0).len(), 1);
+
+        for null in &[Value::Null, Value::BStr(None), Value::Array(None)] {
+            assert_eq!(null.len(), 0);
+        }
+    }
+
+    #[test]
+    #[allow(clippy::len_zero)]
+    fn len_agrees_with_is_empty() {
+        let values = vec![
+            Value::int(0),
+            Value::str(""),
+            Value::err("ERR"),
+            Value::BStr(None),
+            Value::b_str(Some("")),
+            Value::Array(None),
+            Value::array(Some(vec![Value::Null])),
+            Value::Null,
+            Value::Bool(false),
+            Value::Double(0.0),
+            Value::BigNum("1".to_owned()),
+            Value::BErr(String::new()),
+            Value::Verbatim("txt".to_owned(), String::new()),
+            Value::Map(vec![]),
+            Value::Set(vec![]),
+            Value::Push(vec![Value::Null]),
+        ];
+
+        for value in values {
+            assert_eq!(value.len() == 0, value.is_empty(), "{:?}", value);
+        }
+    }
+}
+
+mod test_pointer {
+    use super::*;
+
+    fn value() -> Value {
+        Value::array(Some(vec![
+            Value::int(0),
+            Value::b_str(Some("one")),
+            Value::array(Some(vec![Value::str("two")])),
+            Value::Map(vec![
+                (Value::b_str(Some("a")), Value::int(1)),
+                (Value::int(2), Value::Set(vec![Value::int(3)])),
+                (Value::str("~/"), Value::Null),
+            ]),
+        ]))
+    }
+
+    #[test]
+    fn indices() {
+        let value = value();
+
+        assert_eq!(value.pointer(""), Some(&value));
+        assert_eq!(value.pointer("/1"), Some(&Value::b_str(Some("one"))));
+        assert_eq!(value.pointer("/2/0"), Some(&Value::str("two")));
+    }
+
+    #[test]
+    fn keys() {
+        let value = value();
+
+        assert_eq!(value.pointer("/3/a"), Some(&Value::int(1)));
+        assert_eq!(value.pointer("/3/2/0"), Some(&Value::int(3)));
+        assert_eq!(value.pointer("/3/~0~1"), Some(&Value::Null));
+    }
+
+    #[test]
+    fn missing() {
+        let value = value();
+
+        assert_eq!(value.pointer("/4"), None);
+        assert_eq!(value.pointer("/x"), None);
+        assert_eq!(value.pointer("/0/0"), None);
+        assert_eq!(value.pointer("/3/b"), None);
+        assert_eq!(value.pointer("1"), None);
+        assert_eq!(value.pointer("/"), None);
+    }
+}
@@ -0,0 +1,322 @@
+extern crate lib_resp;
+
+use std::env;
+use std::fs;
+use std::io::{BufReader, ErrorKind};
+use std::path::PathBuf;
+
+use lib_resp::{
+    repair_aof, rewrite_aof, AofFileType, AofReader, AofWriter, Manifest, ManifestEntry, Value,
+};
+
+const AOF: &[u8] = b"*2\r\n$6\r\nSELECT\r\n$1\r\n0\r\n\
+*3\r\n$3\r\nSET\r\n$3\r\nfoo\r\n$3\r\nbar\r\n\
+*2\r\n$4\r\nINCR\r\n$3\r\nnum\r\n";
+
+/// An RDB preamble with a variety of types and encodings, some of which contain `0xff`.
+const RDB: &[u8] = b"REDIS0011\
+\xfa\x09redis-ver\x057.2.4\
+\xfa\x0aredis-bits\xc0\x40\
+\xfe\x00\xfb\x04\x01\
+\x00\x03foo\x03bar\
+\xfc\xff\xff\xff\xff\xff\xff\x00\x00\x00\x01k\xc1\xff\xff\
+\x00\x03big\x80\x00\x00\x00\x02\xff\xff\
+\x00\x03lzf\xc3\x02\x05\xff\xff\
+\x12\x04list\x01\x02\x04\xff\xff\xff\xff\
+\x05\x04zset\x01\x01a\xff\xff\xff\xff\xff\xff\xff\xff\
+\x04\x04hash\x01\x01a\x01b\
+\xff\xde\xad\xbe\xef\xde\xad\xbe\xef";
+
+fn command(args: &[&str]) -> Value {
+    Value::array(Some(
+        args.iter().map(|arg| Value::b_str(Some(*arg))).collect(),
+    ))
+}
+
+fn read(bytes: &[u8]) -> AofReader<BufReader<&[u8]>> {
+    AofReader::new(BufReader::new(bytes))
+}
+
+fn path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("lib-resp-{}-{}.aof", name, std::process::id()))
+}
+
+mod test_reader {
+    use super::*;
+
+    #[test]
+    fn commands() {
+        let mut reader = read(AOF);
+
+        let commands: Vec<Value> = reader.by_ref().map(Result::unwrap).collect();
+
+        assert_eq!(
+            commands,
+            vec![
+                command(&["SELECT", "0"]),
+                command(&["SET", "foo", "bar"]),
+                command(&["INCR", "num"]),
+            ]
+        );
+        assert_eq!(reader.offset(), AOF.len() as u64);
+        assert_eq!(reader.truncated(), None);
+    }
+
+    #[test]
+    fn empty() {
+        let mut reader = read(b"");
+
+        assert!(reader.next().is_none());
+        assert_eq!(reader.truncated(), None);
+    }
+
+    #[test]
+    fn truncated() {
+        let mut bytes = AOF.to_vec();
+        bytes.extend_from_slice(b"*3\r\n$3\r\nSET\r\n$3\r\nba");
+
+        // Small reads, so that the truncated command spans several
+        let mut reader = AofReader::new(BufReader::with_capacity(4, &bytes[..]));
+
+        assert_eq!(reader.by_ref().filter(Result::is_ok).count(), 3);
+        assert_eq!(reader.truncated(), Some(AOF.len() as u64));
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn annotations() {
+        let mut bytes = b"#TS:1628217470\r\n".to_vec();
+        bytes.extend_from_slice(AOF);
+        bytes.extend_from_slice(b"#TS:1628217471\r\n");
+
+        let mut reader = read(&bytes);
+
+        assert_eq!(reader.by_ref().filter(Result::is_ok).count(), 3);
+        assert_eq!(reader.offset(), bytes.len() as u64);
+    }
+
+    #[test]
+    fn bad_format() {
+        let mut bytes = AOF.to_vec();
+        bytes.extend_from_slice(b"+OK\r\n");
+
+        let mut reader = read(&bytes);
+
+        for _ in 0..3 {
+            assert!(reader.next().unwrap().is_ok());
+        }
+
+        let err = reader.next().unwrap().unwrap_err();
+
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(err.to_string().ends_with(&format!("offset {}", AOF.len())));
+
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn rdb_preamble() {
+        let mut bytes = RDB.to_vec();
+        bytes.extend_from_slice(AOF);
+
+        // Small reads, so that the preamble spans several
+        let mut reader = AofReader::new(BufReader::with_capacity(4, &bytes[..]));
+
+        let commands: Vec<Value> = reader.by_ref().map(Result::unwrap).collect();
+
+        assert_eq!(commands.len(), 3);
+        assert_eq!(commands[0], command(&["SELECT", "0"]));
+        assert_eq!(reader.offset(), bytes.len() as u64);
+        assert_eq!(reader.truncated(), None);
+    }
+
+    #[test]
+    fn rdb_base() {
+        // The base file of a multi-part AOF can be an RDB file
+        let mut reader = read(RDB);
+
+        assert!(reader.next().is_none());
+        assert_eq!(reader.offset(), RDB.len() as u64);
+    }
+
+    #[test]
+    fn truncated_rdb_preamble() {
+        for len in 1..RDB.len() {
+            let mut reader = read(&RDB[..len]);
+
+            assert_eq!(
+                reader.next().unwrap().unwrap_err().kind(),
+                ErrorKind::UnexpectedEof,
+                "{}",
+                len
+            );
+            assert!(reader.next().is_none());
+        }
+    }
+
+    #[test]
+    fn bad_rdb_preamble() {
+        let mut reader = read(b"REDIS0011\x63\x01k\x01v\xff12345678");
+
+        assert_eq!(
+            reader.next().unwrap().unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+    }
+}
+
+mod test_writer {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut writer = AofWriter::new(Vec::new());
+
+        writer.command(&["SELECT", "0"]).unwrap();
+        writer.write(&command(&["SET", "foo", "bar"])).unwrap();
+        writer.command(&["INCR", "num"]).unwrap();
+
+        assert_eq!(writer.into_inner(), AOF);
+    }
+
+    #[test]
+    fn invalid() {
+        let mut writer = AofWriter::new(Vec::new());
+
+        assert_eq!(
+            writer.write(&Value::str("OK")).unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+        assert!(writer.write(&Value::array(Some(vec![]))).is_err());
+        assert!(writer
+            .write(&Value::array(Some(vec![Value::int(1)])))
+            .is_err());
+
+        assert!(writer.get_ref().is_empty());
+    }
+
+    #[test]
+    fn append() {
+        let path = path("append");
+
+        let _ = fs::remove_file(&path);
+
+        for args in &[["SET", "a", "1"], ["SET", "b", "2"]] {
+            let mut writer = AofWriter::append(&path).unwrap();
+
+            writer.command(args).unwrap();
+            writer.flush().unwrap();
+        }
+
+        let commands: Vec<Value> = AofReader::open(&path)
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            commands,
+            vec![command(&["SET", "a", "1"]), command(&["SET", "b", "2"])]
+        );
+    }
+
+    #[test]
+    fn rewrite() {
+        let path = path("rewrite");
+
+        fs::write(&path, AOF).unwrap();
+
+        rewrite_aof(&path, vec![command(&["SET", "foo", "baz"])]).unwrap();
+
+        assert_eq!(
+            fs::read(&path).unwrap(),
+            command(&["SET", "foo", "baz"]).encode_bytes()
+        );
+
+        // A failed rewrite leaves the file untouched
+        assert!(rewrite_aof(&path, vec![Value::int(1)]).is_err());
+
+        assert_eq!(
+            fs::read(&path).unwrap(),
+            command(&["SET", "foo", "baz"]).encode_bytes()
+        );
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn repair() {
+        let path = path("repair");
+
+        let mut bytes = AOF.to_vec();
+        bytes.extend_from_slice(b"*2\r\n$4\r\nINCR");
+
+        fs::write(&path, &bytes).unwrap();
+
+        assert_eq!(repair_aof(&path).unwrap(), Some(AOF.len() as u64));
+        assert_eq!(fs::read(&path).unwrap(), AOF);
+
+        assert_eq!(repair_aof(&path).unwrap(), None);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
+
+mod test_manifest {
+    use super::*;
+
+    const MANIFEST: &str = "file appendonly.aof.2.incr.aof seq 2 type i\n\
+                            file appendonly.aof.1.base.rdb seq 1 type b\n\
+                            file appendonly.aof.0.base.aof seq 0 type h\n\
+                            file appendonly.aof.1.incr.aof seq 1 type i startoffset 0\n";
+
+    #[test]
+    fn parse() {
+        let manifest = Manifest::parse(MANIFEST).unwrap();
+
+        assert_eq!(manifest.entries.len(), 4);
+        assert_eq!(
+            manifest.entries[1],
+            ManifestEntry {
+                file: "appendonly.aof.1.base.rdb".to_owned(),
+                seq: 1,
+                file_type: AofFileType::Base,
+            }
+        );
+        assert_eq!(manifest.entries[2].file_type, AofFileType::History);
+    }
+
+    #[test]
+    fn files() {
+        let manifest = Manifest::parse(MANIFEST).unwrap();
+
+        let files: Vec<&str> = manifest.files().map(|entry| &entry.file[..]).collect();
+
+        assert_eq!(
+            files,
+            vec![
+                "appendonly.aof.1.base.rdb",
+                "appendonly.aof.1.incr.aof",
+                "appendonly.aof.2.incr.aof",
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        for text in &[
+            "file appendonly.aof.1.incr.aof seq 1",
+            "file appendonly.aof.1.incr.aof seq one type i",
+            "file appendonly.aof.1.incr.aof seq 1 type x",
+            "file appendonly.aof.1.incr.aof seq",
+        ] {
+            assert_eq!(
+                Manifest::parse(text).unwrap_err().kind(),
+                ErrorKind::InvalidData
+            );
+        }
+
+        assert!(Manifest::parse("").unwrap().entries.is_empty());
+    }
+}
@@ -0,0 +1,72 @@
+extern crate lib_resp;
+
+use lib_resp::split_args;
+
+fn args(line: &str) -> Option<Vec<String>> {
+    split_args(line).map(|args| {
+        args.into_iter()
+            .map(|arg| String::from_utf8(arg).unwrap())
+            .collect()
+    })
+}
+
+#[test]
+fn plain() {
+    assert_eq!(
+        args("  SET\tfoo   bar  "),
+        Some(vec!["SET".to_owned(), "foo".to_owned(), "bar".to_owned()])
+    );
+    assert_eq!(args(""), Some(vec![]));
+    assert_eq!(args("   "), Some(vec![]));
+}
+
+#[test]
+fn double_quotes() {
+    assert_eq!(
+        args(r#"SET "hello world" "a\"b\\c\n\r\t\b\a""#),
+        Some(vec![
+            "SET".to_owned(),
+            "hello world".to_owned(),
+            "a\"b\\c\n\r\t\u{8}\u{7}".to_owned(),
+        ])
+    );
+    assert_eq!(args(r#""""#), Some(vec![String::new()]));
+}
+
+#[test]
+fn hex() {
+    assert_eq!(
+        split_args(r#""\x00\xfF\x41""#),
+        Some(vec![b"\x00\xff\x41".to_vec()])
+    );
+
+    // Invalid hex escapes are just escaped characters
+    assert_eq!(args(r#""\xZZ""#), Some(vec!["xZZ".to_owned()]));
+    assert_eq!(args(r#""\x4""#), Some(vec!["x4".to_owned()]));
+}
+
+#[test]
+fn single_quotes() {
+    assert_eq!(
+        args(r#"'it\'s' 'no \n escapes' '\x00'"#),
+        Some(vec![
+            "it's".to_owned(),
+            r"no \n escapes".to_owned(),
+            r"\x00".to_owned(),
+        ])
+    );
+}
+
+#[test]
+fn adjacent() {
+    assert_eq!(args(r#"foo"bar baz""#), Some(vec!["foobar baz".to_owned()]));
+}
+
+#[test]
+fn invalid() {
+    assert_eq!(args(r#""unclosed"#), None);
+    assert_eq!(args(r#"'unclosed"#), None);
+    assert_eq!(args(r#""trailing\"#), None);
+    assert_eq!(args(r#""a"b"#), None);
+    assert_eq!(args(r#"'a'b"#), None);
+}
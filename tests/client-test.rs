@@ -0,0 +1,114 @@
+extern crate lib_resp;
+
+mod common;
+
+use std::io::Write;
+use std::net::TcpListener;
+use std::thread;
+
+use common::MockServer;
+use lib_resp::{Client, Value};
+
+mod test_call {
+    use super::*;
+
+    #[test]
+    fn command() {
+        let server = MockServer::bind();
+        let addr = server.addr();
+
+        let log = server.serve(|args| match args[0].as_str() {
+            "PING" => Value::str("PONG"),
+            "GET" => common::b_str("bar"),
+            _ => Value::err("ERR unknown command"),
+        });
+
+        let mut client = Client::connect(addr).unwrap();
+
+        assert_eq!(client.command(&["PING"]).unwrap(), Value::str("PONG"));
+        assert_eq!(
+            client.command(&["GET", "foo"]).unwrap(),
+            common::b_str("bar")
+        );
+        assert_eq!(
+            client.command(&["FOO"]).unwrap(),
+            Value::err("ERR unknown command")
+        );
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![vec!["PING"], vec!["GET", "foo"], vec!["FOO"]]
+        );
+    }
+
+    #[test]
+    fn pipelined() {
+        let server = MockServer::bind();
+        let addr = server.addr();
+
+        server.serve(|args| common::b_str(&args[1]));
+
+        let mut client = Client::connect(addr).unwrap();
+
+        for i in 0..3 {
+            client
+                .send(&common::array(vec![
+                    common::b_str("ECHO"),
+                    common::b_str(&i.to_string()),
+                ]))
+                .unwrap();
+        }
+
+        for i in 0..3 {
+            assert_eq!(client.recv().unwrap(), common::b_str(&i.to_string()));
+        }
+    }
+
+    /// Replies split across several writes must be reassembled.
+    #[test]
+    fn fragmented_reply() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+
+            let reply = Value::Array(Some(vec![
+                Value::int(-3),
+                common::b_str(&"x".repeat(20000)),
+            ]))
+            .encode_bytes();
+
+            for chunk in reply.chunks(7) {
+                stream.write_all(chunk).unwrap();
+                stream.flush().unwrap();
+            }
+        });
+
+        let mut client = Client::connect(addr).unwrap();
+
+        assert_eq!(
+            client.recv().unwrap(),
+            Value::Array(Some(vec![
+                Value::int(-3),
+                common::b_str(&"x".repeat(20000))
+            ]))
+        );
+    }
+
+    #[test]
+    fn closed() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+
+            stream.write_all(b"$5\r\nfo").unwrap();
+        });
+
+        let mut client = Client::connect(addr).unwrap();
+
+        assert!(client.recv().is_err());
+    }
+}
@@ -0,0 +1,491 @@
+extern crate lib_resp;
+
+mod common;
+
+use std::io;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use common::{array, b_str, MockServer};
+use lib_resp::{key_slot, ClusterClient, ErrorKind, SlotMap, Value};
+
+/// Builds a `CLUSTER SLOTS` reply from `(start, end, master)` ranges.
+fn cluster_slots(ranges: &[(i64, i64, SocketAddr)]) -> Value {
+    array(
+        ranges
+            .iter()
+            .map(|&(start, end, addr)| {
+                array(vec![
+                    Value::int(start),
+                    Value::int(end),
+                    array(vec![
+                        b_str(&addr.ip().to_string()),
+                        Value::int(i64::from(addr.port())),
+                        b_str("e7d1eecce10fd6bb5eb35b9f99a514335d9ba9ca"),
+                    ]),
+                ])
+            })
+            .collect(),
+    )
+}
+
+mod test_key_slot {
+    use super::*;
+
+    #[test]
+    fn plain() {
+        assert_eq!(key_slot(b""), 0);
+        assert_eq!(key_slot(b"foo"), 12182);
+        assert_eq!(key_slot(b"bar"), 5061);
+        assert_eq!(key_slot(b"123456789"), 0x31C3);
+    }
+
+    #[test]
+    fn hash_tags() {
+        assert_eq!(key_slot(b"{foo}"), key_slot(b"foo"));
+        assert_eq!(key_slot(b"{foo}.bar"), key_slot(b"foo"));
+        assert_eq!(key_slot(b"baz{foo}{bar}"), key_slot(b"foo"));
+
+        // Empty or unterminated tags hash the whole key
+        assert_eq!(key_slot(b"{}foo"), 9500);
+        assert_ne!(key_slot(b"{}foo"), key_slot(b"foo"));
+        assert_ne!(key_slot(b"{foo"), key_slot(b"foo"));
+    }
+}
+
+mod test_slot_map {
+    use super::*;
+
+    #[test]
+    fn from_slots() {
+        let reply = array(vec![
+            array(vec![
+                Value::int(0),
+                Value::int(5460),
+                array(vec![b_str("127.0.0.1"), Value::int(30001), b_str("a")]),
+                array(vec![b_str("127.0.0.1"), Value::int(30004), b_str("b")]),
+            ]),
+            array(vec![
+                Value::int(5461),
+                Value::int(16383),
+                array(vec![b_str(""), Value::int(30002), b_str("c")]),
+            ]),
+        ]);
+
+        let slots = SlotMap::from_slots(&reply, "10.0.0.1").unwrap();
+
+        assert_eq!(slots.get(0), Some("127.0.0.1:30001"));
+        assert_eq!(slots.get(5460), Some("127.0.0.1:30001"));
+        assert_eq!(slots.get(5461), Some("10.0.0.1:30002"));
+        assert_eq!(slots.get(16383), Some("10.0.0.1:30002"));
+        assert_eq!(slots.nodes(), vec!["10.0.0.1:30002", "127.0.0.1:30001"]);
+    }
+
+    #[test]
+    fn from_shards() {
+        let node = |port: i64, role: &str, health: &str| {
+            array(vec![
+                b_str("id"),
+                b_str("e7d1eecce10fd6bb5eb35b9f99a514335d9ba9ca"),
+                b_str("port"),
+                Value::int(port),
+                b_str("ip"),
+                b_str("127.0.0.1"),
+                b_str("endpoint"),
+                b_str("127.0.0.1"),
+                b_str("role"),
+                b_str(role),
+                b_str("replication-offset"),
+                Value::int(72156),
+                b_str("health"),
+                b_str(health),
+            ])
+        };
+
+        let reply = array(vec![
+            array(vec![
+                b_str("slots"),
+                array(vec![
+                    Value::int(0),
+                    Value::int(99),
+                    Value::int(200),
+                    Value::int(299),
+                ]),
+                b_str("nodes"),
+                array(vec![
+                    node(30004, "replica", "online"),
+                    node(30001, "master", "online"),
+                ]),
+            ]),
+            array(vec![
+                b_str("slots"),
+                array(vec![Value::int(100), Value::int(199)]),
+                b_str("nodes"),
+                array(vec![node(30002, "master", "failed")]),
+            ]),
+        ]);
+
+        let slots = SlotMap::from_shards(&reply, "10.0.0.1").unwrap();
+
+        assert_eq!(slots.get(0), Some("127.0.0.1:30001"));
+        assert_eq!(slots.get(99), Some("127.0.0.1:30001"));
+        assert_eq!(slots.get(100), None);
+        assert_eq!(slots.get(250), Some("127.0.0.1:30001"));
+        assert_eq!(slots.get(300), None);
+    }
+
+    #[test]
+    fn malformed() {
+        assert!(SlotMap::from_slots(&Value::err("ERR"), "").is_none());
+        assert!(SlotMap::from_slots(&array(vec![array(vec![Value::int(0)])]), "").is_none());
+        assert!(SlotMap::from_slots(
+            &array(vec![array(vec![
+                Value::int(0),
+                Value::int(16384),
+                array(vec![b_str("127.0.0.1"), Value::int(30001)]),
+            ])]),
+            ""
+        )
+        .is_none());
+        assert!(SlotMap::from_shards(&array(vec![array(vec![b_str("slots")])]), "").is_none());
+    }
+
+    #[test]
+    fn insert() {
+        let mut slots = SlotMap::new();
+
+        assert!(slots.is_empty());
+
+        slots.insert(0, 16383, "a:1".to_owned());
+        slots.insert(100, 199, "b:1".to_owned());
+
+        assert_eq!(slots.get(99), Some("a:1"));
+        assert_eq!(slots.get(100), Some("b:1"));
+        assert_eq!(slots.get(199), Some("b:1"));
+        assert_eq!(slots.get(200), Some("a:1"));
+
+        slots.insert(150, 250, "c:1".to_owned());
+
+        assert_eq!(slots.get(149), Some("b:1"));
+        assert_eq!(slots.get(150), Some("c:1"));
+        assert_eq!(slots.get(250), Some("c:1"));
+        assert_eq!(slots.get(251), Some("a:1"));
+        assert_eq!(slots.get(16383), Some("a:1"));
+    }
+}
+
+mod test_cluster_client {
+    use super::*;
+
+    #[test]
+    fn routing() {
+        let (a, b) = (MockServer::bind(), MockServer::bind());
+        let (a_addr, b_addr) = (a.addr(), b.addr());
+
+        let topology = cluster_slots(&[(0, 8191, a_addr), (8192, 16383, b_addr)]);
+
+        let handler = move |args: &[String]| match args[0].as_str() {
+            "CLUSTER" => topology.clone(),
+            "GET" => b_str(&args[1]),
+            _ => common::ok(),
+        };
+
+        let (a_log, b_log) = (a.serve(handler.clone()), b.serve(handler));
+
+        let mut client = ClusterClient::connect(&[a_addr]).unwrap();
+
+        assert_eq!(client.command(&["GET", "bar"]).unwrap(), b_str("bar"));
+        assert_eq!(client.command(&["GET", "foo"]).unwrap(), b_str("foo"));
+
+        assert_eq!(
+            *a_log.lock().unwrap(),
+            vec![vec!["CLUSTER", "SLOTS"], vec!["GET", "bar"]]
+        );
+        assert_eq!(*b_log.lock().unwrap(), vec![vec!["GET", "foo"]]);
+    }
+
+    #[test]
+    fn multi_key() {
+        let (a, b) = (MockServer::bind(), MockServer::bind());
+        let (a_addr, b_addr) = (a.addr(), b.addr());
+
+        let topology = cluster_slots(&[(0, 8191, a_addr), (8192, 16383, b_addr)]);
+
+        let handler = move |args: &[String]| match args[0].as_str() {
+            "CLUSTER" => topology.clone(),
+            _ => common::ok(),
+        };
+
+        let (a_log, b_log) = (a.serve(handler.clone()), b.serve(handler));
+
+        let mut client = ClusterClient::connect(&[a_addr]).unwrap();
+
+        // Both keys hash to 12182
+        assert_eq!(
+            client
+                .command(&["MSET", "foo", "1", "{foo}x", "2"])
+                .unwrap(),
+            common::ok()
+        );
+        assert_eq!(
+            client
+                .command(&["EVAL", "return 1", "2", "{bar}x", "bar", "foo"])
+                .unwrap(),
+            common::ok()
+        );
+
+        for args in &[
+            &["MGET", "bar", "foo"][..],
+            &["DEL", "{bar}x", "bar", "foo"][..],
+            &["MSET", "bar", "foo", "foo", "bar"][..],
+            &["BLPOP", "bar", "foo", "0"][..],
+            &["RENAME", "bar", "foo"][..],
+            &["EVAL", "return 1", "2", "bar", "foo"][..],
+            &["ZUNIONSTORE", "bar", "1", "foo"][..],
+        ] {
+            let reply = client.command(args).unwrap();
+
+            assert_eq!(
+                reply.server_error().map(|e| e.kind().clone()),
+                Some(ErrorKind::CrossSlot),
+                "{:?}",
+                args
+            );
+        }
+
+        // Nothing was sent for the cross slot commands
+        assert_eq!(
+            *a_log.lock().unwrap(),
+            vec![
+                vec!["CLUSTER", "SLOTS"],
+                vec!["EVAL", "return 1", "2", "{bar}x", "bar", "foo"]
+            ]
+        );
+        assert_eq!(
+            *b_log.lock().unwrap(),
+            vec![vec!["MSET", "foo", "1", "{foo}x", "2"]]
+        );
+    }
+
+    /// Commands without known keys go to any node, rather than hashing their arguments
+    #[test]
+    fn keyless() {
+        let a = MockServer::bind();
+        let a_addr = a.addr();
+
+        // "foo" hashes to 12182, which isn't served
+        let a_log = a.serve(move |args| match args[0].as_str() {
+            "CLUSTER" => cluster_slots(&[(0, 8191, a_addr)]),
+            _ => common::ok(),
+        });
+
+        let mut client = ClusterClient::connect(&[a_addr]).unwrap();
+
+        assert_eq!(
+            client.command(&["CONFIG", "GET", "foo"]).unwrap(),
+            common::ok()
+        );
+        assert_eq!(client.command(&["INFO", "foo"]).unwrap(), common::ok());
+
+        // Routed by the stream, "bar" hashes to 5061
+        assert_eq!(
+            client
+                .command(&["XREAD", "COUNT", "foo", "STREAMS", "bar", "0"])
+                .unwrap(),
+            common::ok()
+        );
+
+        assert!(client.command(&["GET", "foo"]).is_err());
+        assert!(client
+            .command(&["XREAD", "STREAMS", "bar", "foo", "0", "0"])
+            .unwrap()
+            .server_error()
+            .is_some());
+
+        assert_eq!(a_log.lock().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn call_slot() {
+        let a = MockServer::bind();
+        let a_addr = a.addr();
+
+        a.serve(move |args| match args[0].as_str() {
+            "CLUSTER" => cluster_slots(&[(0, 16383, a_addr)]),
+            _ => common::ok(),
+        });
+
+        let mut client = ClusterClient::connect(&[a_addr]).unwrap();
+
+        let ping = array(vec![b_str("PING")]);
+
+        assert_eq!(client.call_slot(16383, &ping).unwrap(), common::ok());
+        assert_eq!(
+            client.call_slot(16384, &ping).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn shards_fallback() {
+        let a = MockServer::bind();
+        let a_addr = a.addr();
+
+        let shards = array(vec![array(vec![
+            b_str("slots"),
+            array(vec![Value::int(0), Value::int(16383)]),
+            b_str("nodes"),
+            array(vec![array(vec![
+                b_str("port"),
+                Value::int(i64::from(a_addr.port())),
+                b_str("ip"),
+                b_str("127.0.0.1"),
+                b_str("role"),
+                b_str("master"),
+                b_str("health"),
+                b_str("online"),
+            ])]),
+        ])]);
+
+        a.serve(move |args| match args[1].as_str() {
+            "SLOTS" => Value::err("ERR unknown subcommand 'SLOTS'"),
+            "SHARDS" => shards.clone(),
+            _ => common::ok(),
+        });
+
+        let client = ClusterClient::connect(&[a_addr]).unwrap();
+
+        assert_eq!(client.slots().get(12182), Some(&a_addr.to_string()[..]));
+    }
+
+    #[test]
+    fn moved() {
+        let (a, b) = (MockServer::bind(), MockServer::bind());
+        let (a_addr, b_addr) = (a.addr(), b.addr());
+
+        let migrated = Arc::new(AtomicBool::new(false));
+
+        let handler = {
+            let migrated = migrated.clone();
+
+            move |args: &[String]| match args[0].as_str() {
+                "CLUSTER" if migrated.load(Ordering::SeqCst) => {
+                    cluster_slots(&[(0, 16383, b_addr)])
+                }
+                "CLUSTER" => cluster_slots(&[(0, 16383, a_addr)]),
+                "GET" => b_str(&args[1]),
+                _ => common::ok(),
+            }
+        };
+
+        b.serve(handler.clone());
+
+        let a_log = {
+            let migrated = migrated.clone();
+
+            a.serve(move |args| {
+                if args[0] == "GET" && migrated.load(Ordering::SeqCst) {
+                    Value::err(format!("MOVED {} {}", key_slot(args[1].as_bytes()), b_addr))
+                } else {
+                    handler(args)
+                }
+            })
+        };
+
+        let mut client = ClusterClient::connect(&[a_addr]).unwrap();
+
+        assert_eq!(client.command(&["GET", "foo"]).unwrap(), b_str("foo"));
+        assert_eq!(client.slots().get(12182), Some(&a_addr.to_string()[..]));
+
+        // Every slot moves from A to B
+        migrated.store(true, Ordering::SeqCst);
+        a_log.lock().unwrap().clear();
+
+        assert_eq!(client.command(&["GET", "foo"]).unwrap(), b_str("foo"));
+
+        // The whole topology was refreshed, not just the redirected slot
+        assert_eq!(client.slots().get(12182), Some(&b_addr.to_string()[..]));
+        assert_eq!(client.slots().get(0), Some(&b_addr.to_string()[..]));
+
+        assert_eq!(a_log.lock().unwrap()[0], vec!["GET", "foo"]);
+    }
+
+    #[test]
+    fn ask() {
+        let (a, b) = (MockServer::bind(), MockServer::bind());
+        let (a_addr, b_addr) = (a.addr(), b.addr());
+
+        let a_log = a.serve(move |args| match args[0].as_str() {
+            "CLUSTER" => cluster_slots(&[(0, 16383, a_addr)]),
+            "GET" => Value::err(format!("ASK {} {}", key_slot(args[1].as_bytes()), b_addr)),
+            _ => common::ok(),
+        });
+
+        let b_log = b.serve(|args| match args[0].as_str() {
+            "GET" => b_str(&args[1]),
+            _ => common::ok(),
+        });
+
+        let mut client = ClusterClient::connect(&[a_addr]).unwrap();
+
+        assert_eq!(client.command(&["GET", "foo"]).unwrap(), b_str("foo"));
+
+        assert_eq!(
+            *b_log.lock().unwrap(),
+            vec![vec!["ASKING"], vec!["GET", "foo"]]
+        );
+
+        // ASK redirections are one-off, so the slot map is untouched
+        assert_eq!(client.slots().get(12182), Some(&a_addr.to_string()[..]));
+        assert_eq!(a_log.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn try_again() {
+        let a = MockServer::bind();
+        let a_addr = a.addr();
+
+        let attempts = Arc::new(AtomicUsize::new(0));
+
+        let server_attempts = attempts.clone();
+
+        a.serve(move |args| match args[0].as_str() {
+            "CLUSTER" => cluster_slots(&[(0, 16383, a_addr)]),
+            _ if server_attempts.fetch_add(1, Ordering::SeqCst) < 2 => {
+                Value::err("TRYAGAIN Multiple keys request during rehashing of slot")
+            }
+            _ => common::ok(),
+        });
+
+        let mut client = ClusterClient::connect(&[a_addr]).unwrap();
+
+        assert_eq!(
+            client.command(&["MSET", "{a}b", "1", "{a}c", "2"]).unwrap(),
+            common::ok()
+        );
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn redirect_loop() {
+        let a = MockServer::bind();
+        let a_addr = a.addr();
+
+        a.serve(move |args| match args[0].as_str() {
+            "CLUSTER" => cluster_slots(&[(0, 16383, a_addr)]),
+            _ => Value::err(format!("ASK 12182 {}", a_addr)),
+        });
+
+        let mut client = ClusterClient::connect(&[a_addr]).unwrap();
+
+        assert!(client.command(&["GET", "foo"]).is_err());
+    }
+
+    #[test]
+    fn unreachable() {
+        let addr = MockServer::bind().addr();
+
+        assert!(ClusterClient::connect(&[addr]).is_err());
+    }
+}
@@ -0,0 +1,99 @@
+//! In-process mock servers for tests which need a peer.
+
+#![allow(dead_code)]
+
+use std::io::{BufReader, Write};
+use std::net::{SocketAddr, TcpListener};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use lib_resp::{Decoder, Value};
+
+/// Commands received by a mock server, in order of arrival.
+pub type Log = Arc<Mutex<Vec<Vec<String>>>>;
+
+/// A mock server which has been bound, but isn't serving yet.
+pub struct MockServer {
+    listener: TcpListener,
+}
+
+impl MockServer {
+    pub fn bind() -> Self {
+        MockServer {
+            listener: TcpListener::bind("127.0.0.1:0").unwrap(),
+        }
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.listener.local_addr().unwrap()
+    }
+
+    /// Serves every connection on its own thread, replying to each command with `handler`.
+    pub fn serve<F>(self, handler: F) -> Log
+    where
+        F: Fn(&[String]) -> Value + Send + Sync + 'static,
+    {
+        let log = Log::default();
+        let handler = Arc::new(handler);
+
+        let server_log = log.clone();
+
+        thread::spawn(move || {
+            for stream in self.listener.incoming() {
+                let mut stream = match stream {
+                    Ok(stream) => stream,
+                    Err(_) => return,
+                };
+
+                let handler = handler.clone();
+                let log = server_log.clone();
+
+                thread::spawn(move || {
+                    let mut decoder = Decoder::new(BufReader::new(stream.try_clone().unwrap()));
+
+                    while let Ok(Some(value)) = decoder.decode() {
+                        let args = args(&value);
+
+                        log.lock().unwrap().push(args.clone());
+
+                        let reply = handler(&args);
+
+                        if stream.write_all(&reply.encode_bytes()).is_err() {
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+
+        log
+    }
+}
+
+/// Flattens a command into its arguments.
+pub fn args(value: &Value) -> Vec<String> {
+    match *value {
+        Value::Array(Some(ref items)) => items
+            .iter()
+            .map(|item| match *item {
+                Value::BStr(Some(ref arg)) | Value::Str(ref arg) => arg.clone(),
+                Value::Int(arg) => arg.to_string(),
+                _ => String::new(),
+            })
+            .collect(),
+
+        _ => Vec::new(),
+    }
+}
+
+pub fn ok() -> Value {
+    Value::str("OK")
+}
+
+pub fn b_str(value: &str) -> Value {
+    Value::b_str(Some(value))
+}
+
+pub fn array(values: Vec<Value>) -> Value {
+    Value::Array(Some(values))
+}
@@ -1,7 +1,7 @@
 extern crate lib_resp;
 
 use std::io::BufReader;
-use lib_resp::{Decoder, Value};
+use lib_resp::{Decoder, Protocol, Value};
 
 mod test_decode {
     use super::*;
@@ -166,4 +166,243 @@ mod test_decode {
             ]))))
         );
     }
+
+    /// Values larger than the reader's buffer must be reassembled
+    #[test]
+    fn fragmented() {
+        let value = Value::Array(Some(vec![
+            Value::int(-3),
+            Value::str("OK"),
+            Value::err("ERR"),
+            Value::b_str(Some("foobar")),
+        ]));
+
+        let bytes = {
+            let mut vec = Vec::new();
+
+            vec.append(&mut value.encode_bytes());
+            vec.append(&mut Value::int(3).encode_bytes());
+
+            vec
+        };
+
+        let mut decoder = Decoder::new(BufReader::with_capacity(3, bytes.as_slice()));
+
+        assert_eq!(decoder.decode().ok(), Some(Some(value)));
+        assert_eq!(decoder.decode().ok(), Some(Some(Value::int(3))));
+        assert_eq!(decoder.decode().ok(), Some(None));
+    }
+
+    /// Values split at every possible point are reassembled
+    #[test]
+    fn every_capacity() {
+        let value = Value::Map(vec![
+            (
+                Value::b_str(Some("a\r\nb")),
+                Value::Array(Some(vec![
+                    Value::Null,
+                    Value::BStr(None),
+                    Value::Array(None),
+                ])),
+            ),
+            (
+                Value::Set(vec![Value::Bool(true), Value::Double(1.5)]),
+                Value::Push(vec![
+                    Value::BigNum("-12".to_owned()),
+                    Value::BErr("ERR x".to_owned()),
+                    Value::Verbatim("txt".to_owned(), "hi".to_owned()),
+                ]),
+            ),
+            (Value::Array(Some(vec![])), Value::Map(vec![])),
+        ]);
+
+        let mut bytes = value.encode_bytes();
+
+        bytes.extend_from_slice(b"+OK\r\n");
+
+        for capacity in 1..=bytes.len() {
+            let mut decoder = Decoder::new(BufReader::with_capacity(capacity, &bytes[..]));
+
+            decoder.set_protocol(Protocol::Resp3);
+
+            assert_eq!(
+                decoder.decode().unwrap(),
+                Some(value.clone()),
+                "{}",
+                capacity
+            );
+            assert_eq!(decoder.decode().unwrap(), Some(Value::str("OK")));
+            assert_eq!(decoder.decode().unwrap(), None);
+        }
+    }
+
+    /// Large values read in small chunks are decoded in linear time, rather than being parsed
+    /// again after every read
+    #[test]
+    fn large_fragmented() {
+        let value = Value::Array(Some(vec![Value::b_str(Some("value")); 100_000]));
+
+        let bytes = value.encode_bytes();
+
+        let mut decoder = Decoder::new(BufReader::with_capacity(512, bytes.as_slice()));
+
+        assert_eq!(decoder.decode().unwrap(), Some(value));
+    }
+
+    /// Values nested too deeply are rejected before they're complete
+    #[test]
+    fn too_deep() {
+        let bytes = b"*1\r\n".repeat(1000);
+
+        let mut decoder = Decoder::new(BufReader::with_capacity(16, bytes.as_slice()));
+
+        assert!(decoder.decode().is_err());
+        assert!(decoder.offset() < bytes.len() as u64);
+    }
+
+    #[test]
+    fn truncated() {
+        let bytes = b"+OK\r\n$6\r\nfoo";
+
+        let mut decoder = Decoder::new(BufReader::with_capacity(3, &bytes[..]));
+
+        assert_eq!(decoder.decode().ok(), Some(Some(Value::str("OK"))));
+        assert_eq!(decoder.decode().ok(), Some(None));
+    }
+}
+
+mod test_decode_all {
+    use super::*;
+
+    #[test]
+    fn complete() {
+        let bytes = b"+OK\r\n:3\r\n";
+
+        let mut decoder = Decoder::new(BufReader::with_capacity(3, &bytes[..]));
+
+        assert_eq!(
+            decoder.decode_all().ok(),
+            Some(Some(vec![Value::str("OK"), Value::int(3)]))
+        );
+    }
+
+    #[test]
+    fn truncated() {
+        let bytes = b"+OK\r\n$6\r\nfoo";
+
+        let mut decoder = Decoder::new(BufReader::with_capacity(3, &bytes[..]));
+
+        assert_eq!(decoder.decode_all().ok(), Some(None));
+    }
+}
+
+mod test_protocol {
+    use super::*;
+
+    #[test]
+    fn resp2_by_default() {
+        let mut decoder = Decoder::new(BufReader::new(&b"_\r\n"[..]));
+
+        assert_eq!(decoder.protocol(), Protocol::Resp2);
+        assert!(decoder.decode().is_err());
+    }
+
+    #[test]
+    fn switch() {
+        let bytes = b"+OK\r\n%1\r\n+a\r\n#t\r\n";
+
+        let mut decoder = Decoder::new(BufReader::with_capacity(3, &bytes[..]));
+
+        assert_eq!(decoder.decode().ok(), Some(Some(Value::str("OK"))));
+
+        decoder.set_protocol(Protocol::Resp3);
+
+        assert_eq!(
+            decoder.decode().ok(),
+            Some(Some(Value::Map(vec![(Value::str("a"), Value::Bool(true))])))
+        );
+    }
+}
+
+mod test_offset {
+    use super::*;
+
+    #[test]
+    fn complete() {
+        let bytes = b"+OK\r\n:1\r\n";
+
+        let mut decoder = Decoder::new(BufReader::new(&bytes[..]));
+
+        assert_eq!(decoder.offset(), 0);
+
+        decoder.decode().unwrap();
+
+        assert_eq!(decoder.offset(), 5);
+
+        decoder.decode().unwrap();
+
+        assert_eq!(decoder.offset(), 9);
+    }
+
+    /// Partially read values aren't counted
+    #[test]
+    fn partial() {
+        let bytes = b"+OK\r\n$5\r\nhel";
+
+        let mut decoder = Decoder::new(BufReader::with_capacity(2, &bytes[..]));
+
+        decoder.decode().unwrap();
+
+        assert_eq!(decoder.decode().ok(), Some(None));
+        assert_eq!(decoder.partial(), b"$5\r\nhel");
+        assert_eq!(decoder.offset(), 5);
+    }
+}
+
+mod test_recovery {
+    use super::*;
+
+    fn decode_all(capacity: usize) -> Vec<Option<Value>> {
+        let bytes = b"*2\r\n:1\r\nXY\r\n+OK\r\n";
+
+        let mut decoder = Decoder::new(BufReader::with_capacity(capacity, &bytes[..]));
+
+        let mut values = Vec::new();
+
+        loop {
+            match decoder.decode() {
+                Ok(Some(value)) => values.push(Some(value)),
+
+                Ok(None) => break,
+
+                Err(_) => values.push(None),
+            }
+        }
+
+        assert_eq!(decoder.offset(), bytes.len() as u64);
+
+        values
+    }
+
+    /// The first line of an invalid value is skipped
+    #[test]
+    fn skip_line() {
+        assert_eq!(
+            decode_all(64),
+            vec![None, Some(Value::int(1)), None, Some(Value::str("OK"))]
+        );
+    }
+
+    /// Values which were buffered before the error are still decoded
+    #[test]
+    fn buffered() {
+        for capacity in 1..8 {
+            assert_eq!(
+                decode_all(capacity),
+                vec![None, Some(Value::int(1)), None, Some(Value::str("OK"))],
+                "capacity {}",
+                capacity
+            );
+        }
+    }
 }
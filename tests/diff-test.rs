@@ -0,0 +1,193 @@
+#[macro_use]
+extern crate lib_resp;
+
+use lib_resp::{Difference, Value};
+
+fn changed(path: &str, left: Value, right: Value) -> Difference {
+    Difference::Changed {
+        path: path.to_owned(),
+        left,
+        right,
+    }
+}
+
+fn removed(path: &str, value: Value) -> Difference {
+    Difference::Removed {
+        path: path.to_owned(),
+        value,
+    }
+}
+
+fn added(path: &str, value: Value) -> Difference {
+    Difference::Added {
+        path: path.to_owned(),
+        value,
+    }
+}
+
+mod test_diff {
+    use super::*;
+
+    #[test]
+    fn equal() {
+        assert_eq!(resp![1, [nil, +"OK"]].diff(&resp![1, [nil, +"OK"]]), vec![]);
+    }
+
+    #[test]
+    fn scalars() {
+        assert_eq!(
+            Value::int(1).diff(&Value::int(2)),
+            vec![changed("", Value::int(1), Value::int(2))]
+        );
+        assert_eq!(
+            Value::BStr(None).diff(&Value::b_str(Some("a"))),
+            vec![changed("", Value::BStr(None), Value::b_str(Some("a")))]
+        );
+        assert_eq!(
+            Value::str("OK").diff(&Value::b_str(Some("OK"))),
+            vec![Difference::KindMismatch {
+                path: String::new(),
+                left: Value::str("OK"),
+                right: Value::b_str(Some("OK")),
+            }]
+        );
+    }
+
+    #[test]
+    fn nested() {
+        assert_eq!(
+            resp![1, [2, [3, "a"]]].diff(&resp![1, [2, [4, "a"]]]),
+            vec![changed("/1/1/0", Value::int(3), Value::int(4))]
+        );
+    }
+
+    #[test]
+    fn arrays() {
+        // Inserted in the middle
+        assert_eq!(
+            resp![1, 2, 3].diff(&resp![1, 9, 2, 3]),
+            vec![added("/1", Value::int(9))]
+        );
+
+        // Removed from the start
+        assert_eq!(
+            resp![1, 2, 3].diff(&resp![2, 3]),
+            vec![removed("/0", Value::int(1))]
+        );
+
+        // Changed and removed from the end
+        assert_eq!(
+            resp![1, 2, 3, 4].diff(&resp![1, 5]),
+            vec![
+                changed("/1", Value::int(2), Value::int(5)),
+                removed("/2", Value::int(3)),
+                removed("/3", Value::int(4)),
+            ]
+        );
+
+        // Null arrays are scalars
+        assert_eq!(
+            Value::Array(None).diff(&resp![]),
+            vec![changed("", Value::Array(None), resp![])]
+        );
+    }
+
+    #[test]
+    fn sets() {
+        let left = Value::Set(vec![Value::int(1), Value::int(2), Value::int(3)]);
+
+        assert_eq!(
+            left.diff(&Value::Set(vec![
+                Value::int(3),
+                Value::int(1),
+                Value::int(2)
+            ])),
+            vec![]
+        );
+
+        assert_eq!(
+            resp![Value::Set(vec![Value::int(2), Value::int(1)])]
+                .diff(&resp![Value::Set(vec![Value::int(3), Value::int(1)])]),
+            vec![removed("/0", Value::int(2)), added("/0", Value::int(3))]
+        );
+    }
+
+    #[test]
+    fn maps() {
+        let left = resp! {
+            "a" => 1,
+            "b" => [1, 2],
+            "c/d" => nil,
+        };
+
+        let right = resp! {
+            "b" => [1, 3],
+            "e" => true,
+            "a" => 1,
+        };
+
+        assert_eq!(
+            left.diff(&right),
+            vec![
+                changed("/b/1", Value::int(2), Value::int(3)),
+                removed("/c~1d", Value::BStr(None)),
+                added("/e", Value::Bool(true)),
+            ]
+        );
+
+        // The paths can be looked up
+        assert_eq!(left.pointer("/c~1d"), Some(&Value::BStr(None)));
+    }
+}
+
+mod test_display {
+    use super::*;
+
+    #[test]
+    fn differences() {
+        assert_eq!(
+            changed("/1", Value::int(2), Value::int(3)).to_string(),
+            "~ /1: Int(2) -> Int(3)"
+        );
+        assert_eq!(
+            Difference::KindMismatch {
+                path: String::new(),
+                left: Value::str("OK"),
+                right: Value::Null,
+            }
+            .to_string(),
+            "~ (root): Str(\"OK\") -> Null (Str -> Null)"
+        );
+        assert_eq!(removed("/0", Value::int(1)).to_string(), "- /0: Int(1)");
+        assert_eq!(added("/a", Value::Null).to_string(), "+ /a: Null");
+    }
+}
+
+mod test_assert_resp_eq {
+    use super::*;
+
+    #[test]
+    fn equal() {
+        assert_resp_eq!(resp![1, 2], resp![1, 2]);
+        assert_resp_eq!(
+            Value::Set(vec![Value::int(1), Value::int(2)]),
+            Value::Set(vec![Value::int(2), Value::int(1)]),
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "assertion failed: `(left == right)`\n  ~ /1: Int(2) -> Int(3)\n  + /2: Int(4)"
+    )]
+    fn different() {
+        assert_resp_eq!(resp![1, 2], resp![1, 3, 4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion failed: `(left == right)`: reply to GET\n  ~ (root): ")]
+    fn message() {
+        let key = "GET";
+
+        assert_resp_eq!(Value::Null, resp![], "reply to {}", key);
+    }
+}
@@ -0,0 +1,265 @@
+extern crate lib_resp;
+
+mod common;
+
+use std::io::ErrorKind;
+
+use common::{b_str, ok, MockServer};
+use lib_resp::{Client, HandshakeOptions, Protocol, ServerError, ServerInfo, Value};
+
+fn hello_map() -> Value {
+    Value::Map(vec![
+        (b_str("server"), b_str("redis")),
+        (b_str("version"), b_str("7.2.4")),
+        (b_str("proto"), Value::int(3)),
+        (b_str("id"), Value::int(42)),
+        (b_str("mode"), b_str("standalone")),
+        (b_str("role"), b_str("master")),
+        (b_str("modules"), Value::Array(Some(Vec::new()))),
+    ])
+}
+
+fn hello_array() -> Value {
+    common::array(vec![
+        b_str("server"),
+        b_str("redis"),
+        b_str("version"),
+        b_str("6.0.0"),
+        b_str("proto"),
+        Value::int(2),
+        b_str("id"),
+        Value::int(7),
+        b_str("mode"),
+        b_str("cluster"),
+        b_str("role"),
+        b_str("replica"),
+        b_str("modules"),
+        common::array(vec![b_str("search")]),
+    ])
+}
+
+fn options() -> HandshakeOptions {
+    HandshakeOptions {
+        username: Some("josh".to_owned()),
+        password: Some("hunter2".to_owned()),
+        client_name: Some("worker-1".to_owned()),
+        db: Some(2),
+        ..HandshakeOptions::default()
+    }
+}
+
+mod test_server_info {
+    use super::*;
+
+    #[test]
+    fn map() {
+        assert_eq!(
+            ServerInfo::from_value(&hello_map()),
+            Some(ServerInfo {
+                server: "redis".to_owned(),
+                version: "7.2.4".to_owned(),
+                proto: 3,
+                id: 42,
+                mode: "standalone".to_owned(),
+                role: "master".to_owned(),
+                modules: Vec::new(),
+            })
+        );
+    }
+
+    #[test]
+    fn array() {
+        assert_eq!(
+            ServerInfo::from_value(&hello_array()),
+            Some(ServerInfo {
+                server: "redis".to_owned(),
+                version: "6.0.0".to_owned(),
+                proto: 2,
+                id: 7,
+                mode: "cluster".to_owned(),
+                role: "replica".to_owned(),
+                modules: vec![b_str("search")],
+            })
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(ServerInfo::from_value(&ok()).is_none());
+        assert!(ServerInfo::from_value(&common::array(vec![b_str("server")])).is_none());
+        assert!(
+            ServerInfo::from_value(&common::array(vec![b_str("server"), b_str("redis")])).is_none()
+        );
+    }
+}
+
+mod test_handshake {
+    use super::*;
+
+    #[test]
+    fn resp3() {
+        let server = MockServer::bind();
+        let addr = server.addr();
+
+        let log = server.serve(|args| match args[0].as_str() {
+            "HELLO" => hello_map(),
+            "GET" => Value::Null,
+            _ => ok(),
+        });
+
+        let mut client = Client::connect(addr).unwrap();
+
+        let info = client.handshake(&options()).unwrap().unwrap();
+
+        assert_eq!(info.proto, 3);
+        assert_eq!(client.protocol(), Protocol::Resp3);
+
+        // RESP3 replies are understood from now on
+        assert_eq!(client.command(&["GET", "foo"]).unwrap(), Value::Null);
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                vec!["HELLO", "3", "AUTH", "josh", "hunter2", "SETNAME", "worker-1"],
+                vec!["SELECT", "2"],
+                vec!["GET", "foo"],
+            ]
+        );
+    }
+
+    #[test]
+    fn default_user() {
+        let server = MockServer::bind();
+        let addr = server.addr();
+
+        let log = server.serve(|_| hello_map());
+
+        let mut client = Client::connect(addr).unwrap();
+
+        client
+            .handshake(&HandshakeOptions {
+                password: Some("hunter2".to_owned()),
+                ..HandshakeOptions::default()
+            })
+            .unwrap();
+
+        assert_eq!(
+            log.lock().unwrap()[0],
+            vec!["HELLO", "3", "AUTH", "default", "hunter2"]
+        );
+    }
+
+    #[test]
+    fn noproto() {
+        let server = MockServer::bind();
+        let addr = server.addr();
+
+        let log = server.serve(
+            |args| match (args[0].as_str(), args.get(1).map(|a| &a[..])) {
+                ("HELLO", Some("3")) => Value::err("NOPROTO unsupported protocol version"),
+                ("HELLO", _) => hello_array(),
+                _ => ok(),
+            },
+        );
+
+        let mut client = Client::connect(addr).unwrap();
+
+        let info = client.handshake(&options()).unwrap().unwrap();
+
+        assert_eq!(info.proto, 2);
+        assert_eq!(client.protocol(), Protocol::Resp2);
+
+        let log = log.lock().unwrap();
+
+        assert_eq!(log[0][..2], ["HELLO", "3"]);
+        assert_eq!(log[1][..2], ["HELLO", "2"]);
+        assert_eq!(log[2], vec!["SELECT", "2"]);
+    }
+
+    #[test]
+    fn legacy() {
+        let server = MockServer::bind();
+        let addr = server.addr();
+
+        let log = server.serve(|args| match args[0].as_str() {
+            "HELLO" => Value::err("ERR unknown command 'HELLO'"),
+            _ => ok(),
+        });
+
+        let mut client = Client::connect(addr).unwrap();
+
+        assert_eq!(client.handshake(&options()).unwrap(), None);
+        assert_eq!(client.protocol(), Protocol::Resp2);
+
+        assert_eq!(
+            log.lock().unwrap()[1..],
+            [
+                vec!["AUTH", "josh", "hunter2"],
+                vec!["CLIENT", "SETNAME", "worker-1"],
+                vec!["SELECT", "2"],
+            ]
+        );
+    }
+
+    #[test]
+    fn legacy_password_only() {
+        let server = MockServer::bind();
+        let addr = server.addr();
+
+        let log = server.serve(|args| match args[0].as_str() {
+            "HELLO" => Value::err("ERR unknown command `HELLO`, with args beginning with: `3`, "),
+            _ => ok(),
+        });
+
+        let mut client = Client::connect(addr).unwrap();
+
+        client
+            .handshake(&HandshakeOptions {
+                password: Some("hunter2".to_owned()),
+                ..HandshakeOptions::default()
+            })
+            .unwrap();
+
+        assert_eq!(log.lock().unwrap()[1], vec!["AUTH", "hunter2"]);
+    }
+
+    #[test]
+    fn wrong_password() {
+        let server = MockServer::bind();
+        let addr = server.addr();
+
+        server
+            .serve(|_| Value::err("WRONGPASS invalid username-password pair or user is disabled."));
+
+        let mut client = Client::connect(addr).unwrap();
+
+        let err = client.handshake(&options()).unwrap_err();
+
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+        assert_eq!(
+            err.get_ref()
+                .unwrap()
+                .downcast_ref::<ServerError>()
+                .unwrap()
+                .code(),
+            "WRONGPASS"
+        );
+
+        assert_eq!(client.protocol(), Protocol::Resp2);
+    }
+
+    #[test]
+    fn select_failure() {
+        let server = MockServer::bind();
+        let addr = server.addr();
+
+        server.serve(|args| match args[0].as_str() {
+            "HELLO" => hello_map(),
+            _ => Value::err("ERR DB index is out of range"),
+        });
+
+        let mut client = Client::connect(addr).unwrap();
+
+        assert!(client.handshake(&options()).is_err());
+    }
+}
@@ -0,0 +1,253 @@
+extern crate lib_resp;
+
+use lib_resp::{Info, InfoValue, Keyspace, Value};
+
+const INFO: &str = "# Server\r
+redis_version:7.2.4\r
+redis_git_sha1:00000000\r
+redis_mode:standalone\r
+os:Linux 6.5.0-1015-azure x86_64\r
+run_id:1e5a0b3c6f2d4e8a9b7c0d1e2f3a4b5c6d7e8f90\r
+uptime_in_seconds:86400\r
+executable:/usr/local/bin/redis-server\r
+config_file:\r
+\r
+# Clients\r
+connected_clients:3\r
+\r
+# Memory\r
+used_memory:1048576\r
+used_memory_human:1.00M\r
+used_memory_peak:2097152\r
+maxmemory:0\r
+maxmemory_policy:noeviction\r
+mem_fragmentation_ratio:1.23\r
+\r
+# Replication\r
+role:master\r
+connected_slaves:1\r
+slave0:ip=127.0.0.1,port=6380,state=online,offset=5880,lag=0\r
+master_repl_offset:5880\r
+\r
+# Commandstats\r
+cmdstat_get:calls=10,usec=20,usec_per_call=2.00,rejected_calls=0,failed_calls=0\r
+\r
+# Keyspace\r
+db0:keys=12,expires=3,avg_ttl=1500\r
+db3:keys=1,expires=0,avg_ttl=0\r
+";
+
+fn info() -> Info {
+    Info::parse(INFO)
+}
+
+mod test_parse {
+    use super::*;
+
+    #[test]
+    fn sections() {
+        let info = info();
+
+        let names: Vec<&str> = info
+            .sections
+            .iter()
+            .map(|section| &section.name[..])
+            .collect();
+
+        assert_eq!(
+            names,
+            vec![
+                "Server",
+                "Clients",
+                "Memory",
+                "Replication",
+                "Commandstats",
+                "Keyspace"
+            ]
+        );
+    }
+
+    #[test]
+    fn types() {
+        let info = info();
+
+        assert_eq!(
+            info.get("uptime_in_seconds"),
+            Some(&InfoValue::Int(86400, "86400".to_owned()))
+        );
+        assert_eq!(
+            info.get("mem_fragmentation_ratio"),
+            Some(&InfoValue::Float(1.23, "1.23".to_owned()))
+        );
+        assert_eq!(
+            info.get("redis_version"),
+            Some(&InfoValue::Str("7.2.4".to_owned()))
+        );
+        assert_eq!(
+            info.get("used_memory_human"),
+            Some(&InfoValue::Str("1.00M".to_owned()))
+        );
+        assert_eq!(
+            info.get("run_id"),
+            Some(&InfoValue::Str(
+                "1e5a0b3c6f2d4e8a9b7c0d1e2f3a4b5c6d7e8f90".to_owned()
+            ))
+        );
+        assert_eq!(
+            info.get("config_file"),
+            Some(&InfoValue::Str(String::new()))
+        );
+        assert_eq!(info.get("missing"), None);
+    }
+
+    #[test]
+    fn fields() {
+        let info = info();
+
+        let replica = info.get("slave0").unwrap();
+
+        assert_eq!(
+            replica.get("ip"),
+            Some(&InfoValue::Str("127.0.0.1".to_owned()))
+        );
+        assert_eq!(
+            replica.get("port"),
+            Some(&InfoValue::Int(6380, "6380".to_owned()))
+        );
+        assert_eq!(replica.get("lag"), Some(&InfoValue::Int(0, "0".to_owned())));
+
+        let stats = info
+            .section("commandstats")
+            .unwrap()
+            .get("cmdstat_get")
+            .unwrap();
+
+        assert_eq!(
+            stats.get("usec_per_call").and_then(InfoValue::as_float),
+            Some(2.0)
+        );
+    }
+
+    /// The text of values is kept, even if it doesn't round trip
+    #[test]
+    fn raw() {
+        let info = Info::parse("a:00000000\r\nb:7.2\r\nc:2.00\r\nd:x=01\r\n");
+
+        assert_eq!(info.get("a").unwrap().as_int(), Some(0));
+        assert_eq!(info.get("a").unwrap().as_str(), Some("00000000"));
+        assert_eq!(info.get("b").unwrap().as_float(), Some(7.2));
+        assert_eq!(info.get("b").unwrap().as_str(), Some("7.2"));
+        assert_eq!(info.get("c").unwrap().as_raw(), "2.00");
+
+        let fields = info.get("d").unwrap();
+
+        assert_eq!(fields.as_str(), None);
+        assert_eq!(fields.as_raw(), "x=01");
+        assert_eq!(fields.get("x").unwrap().as_raw(), "01");
+    }
+
+    /// Values containing `=` are only split if every part is a `key=value` pair
+    #[test]
+    fn not_fields() {
+        let info = Info::parse("a:x=1,y\r\nb:=1\r\nc:foo=bar\r\n");
+
+        assert_eq!(info.get("a"), Some(&InfoValue::Str("x=1,y".to_owned())));
+        assert_eq!(info.get("b"), Some(&InfoValue::Str("=1".to_owned())));
+        assert_eq!(
+            info.get("c"),
+            Some(&InfoValue::Fields(
+                vec![("foo".to_owned(), InfoValue::Str("bar".to_owned()))],
+                "foo=bar".to_owned()
+            ))
+        );
+    }
+
+    /// Replies for a single section, e.g. `INFO keyspace` may use plain line endings
+    #[test]
+    fn no_header() {
+        let info = Info::parse("role:slave\nmaster_host:10.0.0.1\n");
+
+        assert_eq!(info.sections[0].name, "");
+        assert_eq!(info.role(), Some("slave"));
+        assert_eq!(info.sections.len(), 1);
+    }
+
+    #[test]
+    fn empty() {
+        assert!(Info::parse("").sections.is_empty());
+    }
+}
+
+mod test_accessors {
+    use super::*;
+
+    #[test]
+    fn common() {
+        let info = info();
+
+        assert_eq!(info.version(), Some("7.2.4"));
+        assert_eq!(Info::parse("redis_version:7.2\r\n").version(), Some("7.2"));
+        assert_eq!(info.uptime(), Some(86400));
+        assert_eq!(info.connected_clients(), Some(3));
+        assert_eq!(info.used_memory(), Some(1048576));
+        assert_eq!(info.used_memory_peak(), Some(2097152));
+        assert_eq!(info.maxmemory(), Some(0));
+        assert_eq!(info.mem_fragmentation_ratio(), Some(1.23));
+        assert_eq!(info.role(), Some("master"));
+        assert_eq!(info.connected_replicas(), Some(1));
+        assert_eq!(info.repl_offset(), Some(5880));
+    }
+
+    #[test]
+    fn keyspace() {
+        assert_eq!(
+            info().keyspace(),
+            vec![
+                Keyspace {
+                    db: 0,
+                    keys: 12,
+                    expires: 3,
+                    avg_ttl: 1500,
+                },
+                Keyspace {
+                    db: 3,
+                    keys: 1,
+                    expires: 0,
+                    avg_ttl: 0,
+                },
+            ]
+        );
+
+        assert!(Info::parse("# Keyspace\r\n").keyspace().is_empty());
+    }
+
+    #[test]
+    fn section() {
+        let info = info();
+
+        assert_eq!(info.section("MEMORY").unwrap().name, "Memory");
+        assert!(info.section("cluster").is_none());
+    }
+}
+
+mod test_from_value {
+    use super::*;
+
+    #[test]
+    fn b_str() {
+        assert_eq!(Info::from_value(&Value::b_str(Some(INFO))), Some(info()));
+    }
+
+    #[test]
+    fn verbatim() {
+        let value = Value::Verbatim("txt".to_owned(), INFO.to_owned());
+
+        assert_eq!(Info::from_value(&value), Some(info()));
+    }
+
+    #[test]
+    fn other() {
+        assert_eq!(Info::from_value(&Value::BStr(None)), None);
+        assert_eq!(Info::from_value(&Value::err("ERR")), None);
+    }
+}
@@ -0,0 +1,195 @@
+extern crate lib_resp;
+
+use lib_resp::Value;
+
+mod test_to_json {
+    use super::*;
+
+    #[test]
+    fn scalars() {
+        assert_eq!(Value::int(-3).to_json(), "-3");
+        assert_eq!(Value::str("OK").to_json(), r#""OK""#);
+        assert_eq!(Value::b_str(Some("foo")).to_json(), r#""foo""#);
+        assert_eq!(Value::Bool(true).to_json(), "true");
+        assert_eq!(Value::Double(1.5).to_json(), "1.5");
+        assert_eq!(Value::Double(2.0).to_json(), "2.0");
+        assert_eq!(
+            Value::BigNum("3492890328409238509324850943850943825024385".to_owned()).to_json(),
+            r#""3492890328409238509324850943850943825024385""#
+        );
+        assert_eq!(
+            Value::Verbatim("txt".to_owned(), "Some string".to_owned()).to_json(),
+            r#""Some string""#
+        );
+    }
+
+    #[test]
+    fn nulls() {
+        assert_eq!(Value::b_str(None::<String>).to_json(), "null");
+        assert_eq!(Value::array(None).to_json(), "null");
+        assert_eq!(Value::Null.to_json(), "null");
+    }
+
+    #[test]
+    fn non_finite() {
+        assert_eq!(Value::Double(f64::INFINITY).to_json(), r#""inf""#);
+        assert_eq!(Value::Double(f64::NAN).to_json(), r#""NaN""#);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Value::err("ERR unknown command").to_json(),
+            r#"{"error":"ERR unknown command"}"#
+        );
+        assert_eq!(
+            Value::BErr("SYNTAX invalid syntax".to_owned()).to_json(),
+            r#"{"error":"SYNTAX invalid syntax"}"#
+        );
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(
+            Value::b_str(Some("\"a\\b\"\r\n\t\u{1}é")).to_json(),
+            r#""\"a\\b\"\r\n\t\u0001é""#
+        );
+    }
+
+    #[test]
+    fn aggregates() {
+        let data = vec![Value::int(1), Value::array(Some(vec![Value::str("a")]))];
+
+        assert_eq!(Value::array(Some(data.clone())).to_json(), r#"[1,["a"]]"#);
+        assert_eq!(Value::Set(data.clone()).to_json(), r#"[1,["a"]]"#);
+        assert_eq!(Value::Push(data).to_json(), r#"[1,["a"]]"#);
+        assert_eq!(Value::array(Some(vec![])).to_json(), "[]");
+    }
+
+    #[test]
+    fn map() {
+        assert_eq!(
+            Value::Map(vec![
+                (Value::b_str(Some("a")), Value::int(1)),
+                (Value::str("b"), Value::Null),
+            ])
+            .to_json(),
+            r#"{"a":1,"b":null}"#
+        );
+    }
+
+    /// Maps with keys which aren't strings become arrays of pairs
+    #[test]
+    fn map_pairs() {
+        assert_eq!(
+            Value::Map(vec![
+                (Value::int(1), Value::str("a")),
+                (Value::str("b"), Value::Bool(false)),
+            ])
+            .to_json(),
+            r#"[[1,"a"],["b",false]]"#
+        );
+        assert_eq!(Value::Map(vec![]).to_json(), "{}");
+    }
+}
+
+mod test_from_json {
+    use super::*;
+
+    #[test]
+    fn scalars() {
+        assert_eq!(Value::from_json("-3").unwrap(), Value::int(-3));
+        assert_eq!(
+            Value::from_json(r#""foo""#).unwrap(),
+            Value::b_str(Some("foo"))
+        );
+        assert_eq!(
+            Value::from_json("null").unwrap(),
+            Value::b_str(None::<String>)
+        );
+        assert_eq!(Value::from_json("true").unwrap(), Value::Bool(true));
+        assert_eq!(Value::from_json("false").unwrap(), Value::Bool(false));
+        assert_eq!(Value::from_json("1.5").unwrap(), Value::Double(1.5));
+        assert_eq!(Value::from_json("-2e3").unwrap(), Value::Double(-2000.0));
+        assert_eq!(
+            Value::from_json("3492890328409238509324850943850943825024385").unwrap(),
+            Value::BigNum("3492890328409238509324850943850943825024385".to_owned())
+        );
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(
+            Value::from_json(r#""\"\\\/\b\f\n\r\t\u00e9\ud83d\ude00""#).unwrap(),
+            Value::b_str(Some("\"\\/\u{8}\u{c}\n\r\té😀"))
+        );
+    }
+
+    #[test]
+    fn aggregates() {
+        assert_eq!(
+            Value::from_json(r#" [ 1 , [ "a" ] , [] ] "#).unwrap(),
+            Value::array(Some(vec![
+                Value::int(1),
+                Value::array(Some(vec![Value::b_str(Some("a"))])),
+                Value::array(Some(vec![])),
+            ]))
+        );
+    }
+
+    #[test]
+    fn objects() {
+        assert_eq!(
+            Value::from_json(r#"{"error": "ERR unknown command"}"#).unwrap(),
+            Value::err("ERR unknown command")
+        );
+        assert_eq!(
+            Value::from_json(r#"{"a": 1, "error": "b"}"#).unwrap(),
+            Value::Map(vec![
+                (Value::b_str(Some("a")), Value::int(1)),
+                (Value::b_str(Some("error")), Value::b_str(Some("b"))),
+            ])
+        );
+        assert_eq!(Value::from_json("{}").unwrap(), Value::Map(vec![]));
+    }
+
+    #[test]
+    fn round_trip() {
+        let value = Value::array(Some(vec![
+            Value::int(1),
+            Value::b_str(Some("two\n")),
+            Value::err("ERR"),
+            Value::Map(vec![(Value::b_str(Some("k")), Value::Bool(true))]),
+        ]));
+
+        assert_eq!(Value::from_json(&value.to_json()).unwrap(), value);
+    }
+
+    #[test]
+    fn invalid() {
+        for json in &[
+            "",
+            "[",
+            "[1,]",
+            "[1 2]",
+            "{1: 2}",
+            r#"{"a" 1}"#,
+            "nul",
+            "-",
+            "1-2",
+            "1e",
+            r#""\x""#,
+            r#""\ud83d""#,
+            "\"\n\"",
+            "1 2",
+            r#""unclosed"#,
+        ] {
+            assert!(Value::from_json(json).is_err(), "{}", json);
+        }
+
+        assert_eq!(
+            Value::from_json("[1, x]").unwrap_err().to_string(),
+            "Invalid JSON at offset 4"
+        );
+    }
+}
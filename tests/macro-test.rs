@@ -0,0 +1,287 @@
+#[macro_use]
+extern crate lib_resp;
+
+use lib_resp::Value;
+
+mod test_arrays {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        assert_eq!(resp![], Value::Array(Some(vec![])));
+        assert_eq!(
+            resp![[], {}],
+            array(vec![array(vec![]), Value::Map(vec![])])
+        );
+    }
+
+    #[test]
+    fn scalars() {
+        assert_eq!(
+            resp![1, (-2), "x", +"OK", -"ERR bad", bulk "y", nil, null],
+            array(vec![
+                Value::Int(1),
+                Value::Int(-2),
+                Value::b_str(Some("x")),
+                Value::str("OK"),
+                Value::err("ERR bad"),
+                Value::b_str(Some("y")),
+                Value::BStr(None),
+                Value::Null,
+            ])
+        );
+    }
+
+    #[test]
+    fn nested() {
+        assert_eq!(
+            resp![1, "x", [nil, +"OK", -"ERR bad"]],
+            array(vec![
+                Value::Int(1),
+                Value::b_str(Some("x")),
+                array(vec![
+                    Value::BStr(None),
+                    Value::str("OK"),
+                    Value::err("ERR bad"),
+                ]),
+            ])
+        );
+
+        assert_eq!(
+            resp![[[1]], [2, [3]],],
+            array(vec![
+                array(vec![array(vec![Value::Int(1)])]),
+                array(vec![Value::Int(2), array(vec![Value::Int(3)])]),
+            ])
+        );
+    }
+
+    #[test]
+    fn many() {
+        let value = resp![
+            0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23,
+            24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45,
+            46, 47
+        ];
+
+        assert_eq!(value, array((0..48).map(Value::Int).collect()));
+    }
+
+    /// Single token elements are built in one step, so there's no recursion limit to hit
+    #[test]
+    fn very_many() {
+        let value = resp![
+            0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23,
+            24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45,
+            46, 47, 48, 49, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61, 62, 63, 64, 65, 66, 67,
+            68, 69, 70, 71, 72, 73, 74, 75, 76, 77, 78, 79, 80, 81, 82, 83, 84, 85, 86, 87, 88, 89,
+            90, 91, 92, 93, 94, 95, 96, 97, 98, 99, 100, 101, 102, 103, 104, 105, 106, 107, 108,
+            109, 110, 111, 112, 113, 114, 115, 116, 117, 118, 119, 120, 121, 122, 123, 124, 125,
+            126, 127, 128, 129, 130, 131, 132, 133, 134, 135, 136, 137, 138, 139, 140, 141, 142,
+            143, 144, 145, 146, 147, 148, 149, 150, 151, 152, 153, 154, 155, 156, 157, 158, 159,
+            160, 161, 162, 163, 164, 165, 166, 167, 168, 169, 170, 171, 172, 173, 174, 175, 176,
+            177, 178, 179, 180, 181, 182, 183, 184, 185, 186, 187, 188, 189, 190, 191, 192, 193,
+            194, 195, 196, 197, 198, 199, 200, 201, 202, 203, 204, 205, 206, 207, 208, 209, 210,
+            211, 212, 213, 214, 215, 216, 217, 218, 219, 220, 221, 222, 223, 224, 225, 226, 227,
+            228, 229, 230, 231, 232, 233, 234, 235, 236, 237, 238, 239, 240, 241, 242, 243, 244,
+            245, 246, 247, 248, 249, 250, 251, 252, 253, 254, 255, 256, 257, 258, 259, 260, 261,
+            262, 263, 264, 265, 266, 267, 268, 269, 270, 271, 272, 273, 274, 275, 276, 277, 278,
+            279, 280, 281, 282, 283, 284, 285, 286, 287, 288, 289, 290, 291, 292, 293, 294, 295,
+            296, 297, 298, 299
+        ];
+
+        assert_eq!(value, array((0..300).map(Value::Int).collect()));
+    }
+}
+
+mod test_maps {
+    use super::*;
+
+    #[test]
+    fn top_level() {
+        assert_eq!(
+            resp! {
+                +"server" => "redis",
+                +"proto" => 3,
+                +"modules" => [],
+            },
+            Value::Map(vec![
+                (Value::str("server"), Value::b_str(Some("redis"))),
+                (Value::str("proto"), Value::Int(3)),
+                (Value::str("modules"), array(vec![])),
+            ])
+        );
+    }
+
+    #[test]
+    fn nested() {
+        assert_eq!(
+            resp![{ 1 => { nil => -"ERR" } }, { [1, 2] => null, "k" => +"v" }],
+            array(vec![
+                Value::Map(vec![(
+                    Value::Int(1),
+                    Value::Map(vec![(Value::BStr(None), Value::err("ERR"))]),
+                )]),
+                Value::Map(vec![
+                    (array(vec![Value::Int(1), Value::Int(2)]), Value::Null),
+                    (Value::b_str(Some("k")), Value::str("v")),
+                ]),
+            ])
+        );
+    }
+
+    #[test]
+    fn very_many() {
+        let value = resp! {
+            0 => "0",
+            1 => "1",
+            2 => "2",
+            3 => "3",
+            4 => "4",
+            5 => "5",
+            6 => "6",
+            7 => "7",
+            8 => "8",
+            9 => "9",
+            10 => "10",
+            11 => "11",
+            12 => "12",
+            13 => "13",
+            14 => "14",
+            15 => "15",
+            16 => "16",
+            17 => "17",
+            18 => "18",
+            19 => "19",
+            20 => "20",
+            21 => "21",
+            22 => "22",
+            23 => "23",
+            24 => "24",
+            25 => "25",
+            26 => "26",
+            27 => "27",
+            28 => "28",
+            29 => "29",
+            30 => "30",
+            31 => "31",
+            32 => "32",
+            33 => "33",
+            34 => "34",
+            35 => "35",
+            36 => "36",
+            37 => "37",
+            38 => "38",
+            39 => "39",
+            40 => "40",
+            41 => "41",
+            42 => "42",
+            43 => "43",
+            44 => "44",
+            45 => "45",
+            46 => "46",
+            47 => "47",
+            48 => "48",
+            49 => "49",
+            50 => "50",
+            51 => "51",
+            52 => "52",
+            53 => "53",
+            54 => "54",
+            55 => "55",
+            56 => "56",
+            57 => "57",
+            58 => "58",
+            59 => "59",
+            60 => "60",
+            61 => "61",
+            62 => "62",
+            63 => "63",
+            64 => "64",
+            65 => "65",
+            66 => "66",
+            67 => "67",
+            68 => "68",
+            69 => "69",
+            70 => "70",
+            71 => "71",
+            72 => "72",
+            73 => "73",
+            74 => "74",
+            75 => "75",
+            76 => "76",
+            77 => "77",
+            78 => "78",
+            79 => "79",
+            80 => "80",
+            81 => "81",
+            82 => "82",
+            83 => "83",
+            84 => "84",
+            85 => "85",
+            86 => "86",
+            87 => "87",
+            88 => "88",
+            89 => "89",
+            90 => "90",
+            91 => "91",
+            92 => "92",
+            93 => "93",
+            94 => "94",
+            95 => "95",
+            96 => "96",
+            97 => "97",
+            98 => "98",
+            99 => "99",
+        };
+
+        assert_eq!(
+            value,
+            Value::Map(
+                (0..100)
+                    .map(|i| (Value::int(i), Value::b_str(Some(i.to_string()))))
+                    .collect()
+            )
+        );
+    }
+}
+
+mod test_interpolation {
+    use super::*;
+
+    #[test]
+    fn expressions() {
+        let key = String::from("key");
+        let n = 20;
+
+        assert_eq!(
+            resp!["SET", key.clone(), n * 2 + 2, +format!("{}!", key)],
+            array(vec![
+                Value::b_str(Some("SET")),
+                Value::b_str(Some("key")),
+                Value::Int(42),
+                Value::str("key!"),
+            ])
+        );
+    }
+
+    #[test]
+    fn values() {
+        let inner = resp![1, 2];
+
+        assert_eq!(
+            resp![inner.clone(), { "inner" => inner }],
+            array(vec![
+                array(vec![Value::Int(1), Value::Int(2)]),
+                Value::Map(vec![(
+                    Value::b_str(Some("inner")),
+                    array(vec![Value::Int(1), Value::Int(2)]),
+                )]),
+            ])
+        );
+    }
+}
+
+fn array(values: Vec<Value>) -> Value {
+    Value::Array(Some(values))
+}
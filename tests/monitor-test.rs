@@ -0,0 +1,177 @@
+extern crate lib_resp;
+
+mod common;
+
+use std::io::{BufReader, ErrorKind, Write};
+use std::net::{SocketAddr, TcpListener};
+use std::thread;
+use std::time::Duration;
+
+use lib_resp::{Client, Decoder, MonitorClient, MonitorEvent, Value};
+
+/// Serves a single client, replying to `MONITOR` with `reply` then hanging up.
+fn server(reply: &'static [u8]) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    thread::spawn(move || {
+        let (mut socket, _) = listener.accept().unwrap();
+
+        let mut decoder = Decoder::new(BufReader::new(socket.try_clone().unwrap()));
+
+        if let Ok(Some(value)) = decoder.decode() {
+            assert_eq!(common::args(&value), vec!["MONITOR"]);
+
+            socket.write_all(reply).unwrap();
+        }
+    });
+
+    addr
+}
+
+fn args(args: &[&[u8]]) -> Vec<Vec<u8>> {
+    args.iter().map(|arg| arg.to_vec()).collect()
+}
+
+mod test_parse {
+    use super::*;
+
+    #[test]
+    fn tcp() {
+        assert_eq!(
+            MonitorEvent::parse(r#"1697000000.123456 [0 127.0.0.1:5000] "SET" "k" "v""#),
+            Some(MonitorEvent {
+                timestamp: Duration::new(1697000000, 123456000),
+                db: 0,
+                client: MonitorClient::Tcp("127.0.0.1:5000".parse().unwrap()),
+                args: args(&[b"SET", b"k", b"v"]),
+            })
+        );
+    }
+
+    #[test]
+    fn ipv6() {
+        let event = MonitorEvent::parse(r#"1697000000.000001 [3 [::1]:5000] "PING""#).unwrap();
+
+        assert_eq!(event.db, 3);
+        assert_eq!(
+            event.client,
+            MonitorClient::Tcp("[::1]:5000".parse().unwrap())
+        );
+        assert_eq!(event.timestamp, Duration::new(1697000000, 1000));
+    }
+
+    #[test]
+    fn lua() {
+        let event = MonitorEvent::parse(r#"1697000000.123456 [0 lua] "get" "k""#).unwrap();
+
+        assert_eq!(event.client, MonitorClient::Lua);
+    }
+
+    #[test]
+    fn unix() {
+        let event =
+            MonitorEvent::parse(r#"1697000000.123456 [0 unix:/tmp/redis.sock] "get" "k""#).unwrap();
+
+        assert_eq!(
+            event.client,
+            MonitorClient::Unix("/tmp/redis.sock".to_owned())
+        );
+    }
+
+    #[test]
+    fn escapes() {
+        let event = MonitorEvent::parse(
+            r#"1697000000.123456 [0 lua] "SET" "a b" "\"q\" \\ \n\r\t\a\b" "v\x00\xff""#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            event.args,
+            args(&[b"SET", b"a b", b"\"q\" \\ \n\r\t\x07\x08", b"v\x00\xff"])
+        );
+    }
+
+    #[test]
+    fn empty_arg() {
+        let event = MonitorEvent::parse(r#"1697000000.123456 [0 lua] "SET" "k" """#).unwrap();
+
+        assert_eq!(event.args, args(&[b"SET", b"k", b""]));
+    }
+
+    #[test]
+    fn invalid() {
+        for line in &[
+            "",
+            "OK",
+            r#"1697000000 [0 lua] "SET""#,
+            r#"1697000000.123456 [0 lua]"#,
+            r#"1697000000.123456 [0 lua] "#,
+            r#"1697000000.123456 [x lua] "SET""#,
+            r#"1697000000.123456 [0 nowhere] "SET""#,
+            r#"1697000000.123456 [0 lua] "SET"#,
+            r#"1697000000.123456 [0 lua] "SET""k""#,
+            r#"1697000000.123456 [0 lua] "\xZZ""#,
+            r#"1697000000.123456 [0 lua] SET"#,
+        ] {
+            assert_eq!(MonitorEvent::parse(line), None, "{}", line);
+        }
+    }
+
+    #[test]
+    fn from_value() {
+        let line = r#"1697000000.123456 [0 lua] "PING""#;
+
+        assert_eq!(
+            MonitorEvent::from_value(&Value::str(line)),
+            MonitorEvent::parse(line)
+        );
+        assert_eq!(MonitorEvent::from_value(&Value::b_str(Some(line))), None);
+    }
+}
+
+mod test_monitor {
+    use super::*;
+
+    #[test]
+    fn events() {
+        let addr = server(
+            b"+OK\r\n\
+              +1697000000.123456 [0 127.0.0.1:5000] \"SET\" \"k\" \"v\"\r\n\
+              +1697000001.000000 [1 lua] \"DEL\" \"k\"\r\n",
+        );
+
+        let events: Vec<MonitorEvent> = Client::connect(addr)
+            .unwrap()
+            .monitor()
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].args, args(&[b"SET", b"k", b"v"]));
+        assert_eq!(events[1].db, 1);
+        assert_eq!(events[1].client, MonitorClient::Lua);
+    }
+
+    #[test]
+    fn error() {
+        let addr = server(b"-NOPERM this user has no permissions to run the 'monitor' command\r\n");
+
+        let err = Client::connect(addr).unwrap().monitor().err().unwrap();
+
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+    }
+
+    #[test]
+    fn invalid() {
+        let addr = server(b"+OK\r\n:1\r\n");
+
+        let mut monitor = Client::connect(addr).unwrap().monitor().unwrap();
+
+        assert_eq!(
+            monitor.next().unwrap().unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+    }
+}
@@ -14,6 +14,20 @@ mod test_parse {
         assert_eq!(Parser::parse(b":-3\r\n").unwrap().1, Value::int(-3));
     }
 
+    /// Tests parsing the smallest and largest integers, and one out of range
+    #[test]
+    fn int_bounds() {
+        assert_eq!(
+            Parser::parse(b":-9223372036854775808\r\n").unwrap().1,
+            Value::int(i64::MIN)
+        );
+        assert_eq!(
+            Parser::parse(b":9223372036854775807\r\n").unwrap().1,
+            Value::int(i64::MAX)
+        );
+        assert!(Parser::parse(b":9223372036854775808\r\n").is_err());
+    }
+
     /// Tests parsing some simple strings
     #[test]
     fn str() {
@@ -202,3 +216,225 @@ mod test_parse {
         }
     }
 }
+
+/// Tests the `parse_protocol` method
+mod test_parse_protocol {
+    use super::*;
+    use lib_resp::Protocol;
+
+    fn parse(data: &[u8]) -> Value {
+        Parser::parse_protocol(data, Protocol::Resp3).unwrap().1
+    }
+
+    /// Tests RESP2 types are still parsed as RESP3
+    #[test]
+    fn resp2() {
+        assert_eq!(parse(b":-3\r\n"), Value::int(-3));
+        assert_eq!(parse(b"+OK\r\n"), Value::str("OK"));
+        assert_eq!(parse(b"-ERR\r\n"), Value::err("ERR"));
+        assert_eq!(parse(b"$3\r\nfoo\r\n"), Value::b_str(Some("foo")));
+        assert_eq!(parse(b"*1\r\n$-1\r\n"), Value::Array(Some(vec![Value::BStr(None)])));
+    }
+
+    #[test]
+    fn null() {
+        assert_eq!(parse(b"_\r\n"), Value::Null);
+    }
+
+    #[test]
+    fn bool() {
+        assert_eq!(parse(b"#t\r\n"), Value::Bool(true));
+        assert_eq!(parse(b"#f\r\n"), Value::Bool(false));
+    }
+
+    #[test]
+    fn double() {
+        assert_eq!(parse(b",1.23\r\n"), Value::Double(1.23));
+        assert_eq!(parse(b",-10\r\n"), Value::Double(-10.0));
+        assert_eq!(parse(b",1.5e3\r\n"), Value::Double(1500.0));
+        assert_eq!(parse(b",inf\r\n"), Value::Double(f64::INFINITY));
+        assert_eq!(parse(b",-inf\r\n"), Value::Double(f64::NEG_INFINITY));
+
+        match parse(b",nan\r\n") {
+            Value::Double(datum) => assert!(datum.is_nan()),
+            value => panic!("expected a double, got {:?}", value),
+        }
+    }
+
+    #[test]
+    fn big_num() {
+        assert_eq!(
+            parse(b"(3492890328409238509324850943850943825024385\r\n"),
+            Value::BigNum("3492890328409238509324850943850943825024385".to_owned())
+        );
+        assert_eq!(parse(b"(-1\r\n"), Value::BigNum("-1".to_owned()));
+    }
+
+    #[test]
+    fn b_err() {
+        assert_eq!(
+            parse(b"!21\r\nSYNTAX invalid syntax\r\n"),
+            Value::BErr("SYNTAX invalid syntax".to_owned())
+        );
+    }
+
+    #[test]
+    fn verbatim() {
+        assert_eq!(
+            parse(b"=15\r\ntxt:Some string\r\n"),
+            Value::Verbatim("txt".to_owned(), "Some string".to_owned())
+        );
+        assert_eq!(
+            parse(b"=4\r\nmkd:\r\n"),
+            Value::Verbatim("mkd".to_owned(), String::new())
+        );
+    }
+
+    #[test]
+    fn map() {
+        assert_eq!(parse(b"%0\r\n"), Value::Map(Vec::new()));
+        assert_eq!(
+            parse(b"%2\r\n+first\r\n:1\r\n+second\r\n%1\r\n_\r\n#t\r\n"),
+            Value::Map(vec![
+                (Value::str("first"), Value::int(1)),
+                (
+                    Value::str("second"),
+                    Value::Map(vec![(Value::Null, Value::Bool(true))])
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn set() {
+        assert_eq!(
+            parse(b"~3\r\n+a\r\n:1\r\n,2.5\r\n"),
+            Value::Set(vec![Value::str("a"), Value::int(1), Value::Double(2.5)])
+        );
+    }
+
+    #[test]
+    fn push() {
+        assert_eq!(
+            parse(b">3\r\n$7\r\nmessage\r\n$4\r\nchan\r\n$5\r\nhello\r\n"),
+            Value::Push(vec![
+                Value::b_str(Some("message")),
+                Value::b_str(Some("chan")),
+                Value::b_str(Some("hello")),
+            ])
+        );
+    }
+
+    /// Tests RESP3 types nested in RESP2 aggregates
+    #[test]
+    fn nested() {
+        assert_eq!(
+            parse(b"*2\r\n_\r\n~1\r\n#f\r\n"),
+            Value::Array(Some(vec![Value::Null, Value::Set(vec![Value::Bool(false)])]))
+        );
+    }
+
+    /// These tests ensure RESP3 types are rejected when parsing RESP2
+    #[test]
+    fn resp2_only() {
+        for data in [
+            &b"_\r\n"[..],
+            b"#t\r\n",
+            b",1.5\r\n",
+            b"(1\r\n",
+            b"!3\r\nERR\r\n",
+            b"=7\r\ntxt:foo\r\n",
+            b"%0\r\n",
+            b"~0\r\n",
+            b">0\r\n",
+            b"*1\r\n_\r\n",
+        ].iter()
+        {
+            assert!(Parser::parse(data).is_err());
+            assert!(!Parser::parse_protocol(data, Protocol::Resp2)
+                .unwrap_err()
+                .is_incomplete());
+        }
+    }
+
+    /// These tests ensure erroneous data returns an error
+    #[test]
+    fn errors() {
+        for data in [
+            &b"_x\r\n"[..],
+            b"#x\r\n",
+            b",foo\r\n",
+            b"(\r\n",
+            b"(12a\r\n",
+            b"!-1\r\n",
+            b"=3\r\ntxt\r\n",
+            b"=7\r\ntxt;foo\r\n",
+            b"%-1\r\n",
+            b"~-1\r\n",
+            b">-1\r\n",
+        ].iter()
+        {
+            let err = Parser::parse_protocol(data, Protocol::Resp3).unwrap_err();
+
+            assert!(!err.is_incomplete(), "{:?}", data);
+        }
+    }
+
+    /// These tests ensure deeply nested aggregates are rejected, rather than overflow the stack
+    #[test]
+    fn nesting() {
+        let nested = |depth: usize, header: &[u8]| {
+            let mut data = header.repeat(depth);
+
+            data.extend_from_slice(b":1\r\n");
+
+            data
+        };
+
+        let (_, mut value) =
+            Parser::parse_protocol(&nested(128, b"*1\r\n"), Protocol::Resp3).unwrap();
+
+        for _ in 0..128 {
+            value = match value {
+                Value::Array(Some(mut values)) => values.remove(0),
+
+                value => panic!("Expected an array, got {:?}", value),
+            };
+        }
+
+        assert_eq!(value, Value::int(1));
+
+        for header in &[&b"*1\r\n"[..], b"~1\r\n", b">1\r\n", b"%1\r\n"] {
+            let data = nested(129, header);
+            let err = Parser::parse_protocol(&data, Protocol::Resp3).unwrap_err();
+
+            assert!(!err.is_incomplete(), "{:?}", header);
+        }
+
+        // Rejected before the end of the data is reached
+        let data = b"*1\r\n".repeat(1_000_000);
+
+        assert!(!Parser::parse(&data).unwrap_err().is_incomplete());
+    }
+
+    /// These tests ensure incomplete data returns `Err::Incomplete`
+    #[test]
+    fn incomplete() {
+        for data in [
+            &b"_"[..],
+            b"#t",
+            b",1.5\r",
+            b"(12",
+            b"!3\r\nER",
+            b"=7\r\ntxt:fo",
+            b"%1\r\n+a\r\n",
+            b"~2\r\n+a\r\n",
+            b">1\r\n",
+        ].iter()
+        {
+            let err = Parser::parse_protocol(data, Protocol::Resp3).unwrap_err();
+
+            assert!(err.is_incomplete(), "{:?}", data);
+        }
+    }
+}
@@ -0,0 +1,466 @@
+extern crate lib_resp;
+
+mod common;
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use common::{b_str, MockServer};
+use lib_resp::{Pool, PoolOptions, Value};
+
+fn echo_server() -> SocketAddr {
+    let server = MockServer::bind();
+    let addr = server.addr();
+
+    server.serve(|args| match args[0].as_str() {
+        "PING" => Value::str("PONG"),
+        _ => b_str(&args[1]),
+    });
+
+    addr
+}
+
+/// Accepts connections, and reads from them, but never replies.
+fn hung_server() -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream = stream.unwrap();
+
+            thread::spawn(move || io::copy(&mut &stream, &mut io::sink()));
+        }
+    });
+
+    addr
+}
+
+fn options(min_size: usize, max_size: usize) -> PoolOptions {
+    PoolOptions {
+        min_size,
+        max_size,
+        checkout_timeout: Duration::from_millis(100),
+        ..PoolOptions::default()
+    }
+}
+
+mod test_pool {
+    use super::*;
+
+    #[test]
+    fn invalid_size() {
+        let addr = echo_server();
+
+        assert!(Pool::new(addr, options(0, 0)).is_err());
+        assert!(Pool::new(addr, options(2, 1)).is_err());
+        assert!(Pool::new(
+            addr,
+            PoolOptions {
+                check_timeout: Duration::from_secs(0),
+                ..options(0, 1)
+            }
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn min_size() {
+        let pool = Pool::new(echo_server(), options(2, 4)).unwrap();
+
+        assert_eq!(pool.size(), 2);
+        assert_eq!(pool.idle(), 2);
+    }
+
+    #[test]
+    fn reuse() {
+        let pool = Pool::new(echo_server(), options(0, 4)).unwrap();
+
+        let local_addr = {
+            let mut client = pool.get().unwrap();
+
+            assert_eq!(client.command(&["ECHO", "foo"]).unwrap(), b_str("foo"));
+
+            client.get_ref().local_addr().unwrap()
+        };
+
+        assert_eq!(pool.idle(), 1);
+
+        let client = pool.get().unwrap();
+
+        assert_eq!(client.get_ref().local_addr().unwrap(), local_addr);
+        assert_eq!(pool.size(), 1);
+    }
+
+    #[test]
+    fn max_size() {
+        let pool = Pool::new(echo_server(), options(0, 2)).unwrap();
+
+        let a = pool.get().unwrap();
+        let _b = pool.get().unwrap();
+
+        assert!(pool.get().is_err());
+
+        // Returning a connection wakes up a waiting checkout
+        let (tx, rx) = mpsc::channel();
+
+        let waiter = {
+            let pool = pool.clone();
+
+            thread::spawn(move || {
+                tx.send(()).unwrap();
+
+                pool.get().is_ok()
+            })
+        };
+
+        rx.recv().unwrap();
+
+        drop(a);
+
+        assert!(waiter.join().unwrap());
+        assert_eq!(pool.size(), 2);
+    }
+
+    #[test]
+    fn threads() {
+        let pool = Pool::new(echo_server(), options(1, 3)).unwrap();
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let pool = pool.clone();
+
+                thread::spawn(move || {
+                    for j in 0..10 {
+                        let arg = format!("{}-{}", i, j);
+
+                        let mut client = pool.get().unwrap();
+
+                        assert_eq!(client.command(&["ECHO", &arg]).unwrap(), b_str(&arg));
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert!(pool.size() <= 3);
+    }
+
+    #[test]
+    fn discard() {
+        let pool = Pool::new(echo_server(), options(0, 1)).unwrap();
+
+        pool.get().unwrap().discard();
+
+        assert_eq!(pool.size(), 0);
+        assert!(pool.get().is_ok());
+    }
+}
+
+/// Connections must never be handed out with replies meant for someone else.
+mod test_recovery {
+    use super::*;
+
+    #[test]
+    fn pending_replies() {
+        let pool = Pool::new(echo_server(), options(0, 1)).unwrap();
+
+        let local_addr = {
+            let mut client = pool.get().unwrap();
+
+            for arg in &["a", "b", "c"] {
+                client
+                    .send(&common::array(vec![b_str("ECHO"), b_str(arg)]))
+                    .unwrap();
+            }
+
+            assert_eq!(client.recv().unwrap(), b_str("a"));
+            assert_eq!(client.pending(), 2);
+
+            client.get_ref().local_addr().unwrap()
+        };
+
+        // The replies were discarded, and the connection kept
+        assert_eq!(pool.size(), 1);
+        assert_eq!(pool.idle(), 1);
+
+        let mut client = pool.get().unwrap();
+
+        assert_eq!(client.get_ref().local_addr().unwrap(), local_addr);
+        assert_eq!(client.command(&["ECHO", "d"]).unwrap(), b_str("d"));
+    }
+
+    #[test]
+    fn hung_pending_replies() {
+        let pool = Pool::new(
+            hung_server(),
+            PoolOptions {
+                check_timeout: Duration::from_millis(50),
+                test_on_checkout: false,
+                ..options(1, 1)
+            },
+        )
+        .unwrap();
+
+        let start = Instant::now();
+
+        {
+            let mut client = pool.get().unwrap();
+
+            client.send(&common::array(vec![b_str("GET")])).unwrap();
+        }
+
+        assert!(start.elapsed() < Duration::from_secs(1));
+
+        // The connection was closed, and is replaced by the next checkout
+        assert_eq!(pool.size(), 0);
+        assert!(pool.get().unwrap().is_idle());
+        assert_eq!(pool.size(), 1);
+    }
+
+    #[test]
+    fn unread_replies() {
+        let pool = Pool::new(echo_server(), options(0, 1)).unwrap();
+
+        {
+            let client = pool.get().unwrap();
+
+            // Bypass the client, so the reply isn't accounted for
+            client
+                .get_ref()
+                .write_all(&common::array(vec![b_str("ECHO"), b_str("a")]).encode_bytes())
+                .unwrap();
+
+            thread::sleep(Duration::from_millis(50));
+
+            assert_eq!(client.pending(), 0);
+            assert!(!client.is_idle());
+        }
+
+        assert_eq!(pool.size(), 0);
+
+        let mut client = pool.get().unwrap();
+
+        assert_eq!(client.command(&["ECHO", "b"]).unwrap(), b_str("b"));
+    }
+
+    #[test]
+    fn failed_connection() {
+        let pool = Pool::new(echo_server(), options(1, 1)).unwrap();
+
+        {
+            let mut client = pool.get().unwrap();
+
+            client
+                .get_ref()
+                .set_read_timeout(Some(Duration::from_millis(10)))
+                .unwrap();
+
+            // Nothing was sent, so the read times out
+            assert!(client.recv().is_err());
+        }
+
+        // The connection was closed, and is replaced by maintenance to keep the minimum size
+        assert_eq!(pool.size(), 0);
+
+        pool.maintain().unwrap();
+
+        assert_eq!(pool.size(), 1);
+        assert_eq!(pool.idle(), 1);
+    }
+
+    /// Returning a connection neither waits for a new one to be opened, nor clears the read
+    /// timeout set by its user
+    #[test]
+    fn read_timeout() {
+        let pool = Pool::new(echo_server(), options(0, 1)).unwrap();
+
+        let timeout = Some(Duration::from_millis(500));
+
+        {
+            let mut client = pool.get().unwrap();
+
+            client.get_ref().set_read_timeout(timeout).unwrap();
+
+            client
+                .send(&common::array(vec![b_str("ECHO"), b_str("a")]))
+                .unwrap();
+        }
+
+        let client = pool.get().unwrap();
+
+        assert_eq!(client.get_ref().read_timeout().unwrap(), timeout);
+    }
+}
+
+mod test_health {
+    use super::*;
+
+    /// Serves PINGs on the first connection until told to close it, then serves later
+    /// connections normally.
+    fn flaky_server() -> (SocketAddr, mpsc::Sender<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            let (first, _) = listener.accept().unwrap();
+
+            rx.recv().unwrap();
+
+            drop(first);
+
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+
+                thread::spawn(move || {
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    let mut line = String::new();
+
+                    // Each PING is 3 lines: "*1", "$4", "PING"
+                    while reader.read_line(&mut line).unwrap_or(0) > 0 {
+                        if line.ends_with("PING\r\n") {
+                            stream.write_all(b"+PONG\r\n").unwrap();
+                        }
+
+                        line.clear();
+                    }
+                });
+            }
+        });
+
+        (addr, tx)
+    }
+
+    #[test]
+    fn validate_on_checkout() {
+        let (addr, close) = flaky_server();
+
+        let pool = Pool::new(addr, options(1, 1)).unwrap();
+
+        close.send(()).unwrap();
+
+        thread::sleep(Duration::from_millis(50));
+
+        let mut client = pool.get().unwrap();
+
+        assert_eq!(client.command(&["PING"]).unwrap(), Value::str("PONG"));
+        assert_eq!(pool.size(), 1);
+    }
+
+    #[test]
+    fn hung_on_checkout() {
+        let pool = Pool::new(
+            hung_server(),
+            PoolOptions {
+                check_timeout: Duration::from_millis(50),
+                ..options(1, 1)
+            },
+        )
+        .unwrap();
+
+        let start = Instant::now();
+
+        // The PING fails by the check timeout, then a fresh connection is opened
+        assert!(pool.get().is_ok());
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    /// Healthy connections are reused, however short the checkout timeout
+    #[test]
+    fn zero_checkout_timeout() {
+        let pool = Pool::new(
+            echo_server(),
+            PoolOptions {
+                checkout_timeout: Duration::from_secs(0),
+                ..options(1, 1)
+            },
+        )
+        .unwrap();
+
+        let local_addr = pool.get().unwrap().get_ref().local_addr().unwrap();
+
+        let client = pool.get().unwrap();
+
+        assert_eq!(client.get_ref().local_addr().unwrap(), local_addr);
+    }
+
+    #[test]
+    fn maintain() {
+        let (addr, close) = flaky_server();
+
+        let pool = Pool::new(addr, options(1, 1)).unwrap();
+
+        close.send(()).unwrap();
+
+        thread::sleep(Duration::from_millis(50));
+
+        pool.maintain().unwrap();
+
+        // The dead connection was replaced to keep the minimum size
+        assert_eq!(pool.size(), 1);
+        assert_eq!(pool.idle(), 1);
+
+        let mut client = pool.get().unwrap();
+
+        assert_eq!(client.command(&["PING"]).unwrap(), Value::str("PONG"));
+    }
+
+    #[test]
+    fn idle_timeout() {
+        let pool = Pool::new(
+            echo_server(),
+            PoolOptions {
+                idle_timeout: Some(Duration::from_millis(20)),
+                ..options(1, 3)
+            },
+        )
+        .unwrap();
+
+        {
+            let _a = pool.get().unwrap();
+            let _b = pool.get().unwrap();
+            let _c = pool.get().unwrap();
+        }
+
+        assert_eq!(pool.idle(), 3);
+
+        thread::sleep(Duration::from_millis(50));
+
+        pool.maintain().unwrap();
+
+        // Connections up to the minimum size are kept
+        assert_eq!(pool.size(), 1);
+        assert_eq!(pool.idle(), 1);
+    }
+
+    #[test]
+    fn health_check_interval() {
+        let (addr, close) = flaky_server();
+
+        let pool = Pool::new(
+            addr,
+            PoolOptions {
+                test_on_checkout: false,
+                health_check_interval: Some(Duration::from_millis(20)),
+                ..options(1, 1)
+            },
+        )
+        .unwrap();
+
+        close.send(()).unwrap();
+
+        thread::sleep(Duration::from_millis(200));
+
+        let mut client = pool.get().unwrap();
+
+        assert_eq!(client.command(&["PING"]).unwrap(), Value::str("PONG"));
+    }
+}
@@ -0,0 +1,414 @@
+#[macro_use]
+extern crate lib_resp;
+
+use lib_resp::{PrettyOptions, Value};
+
+fn pretty(value: &Value) -> String {
+    value.pretty(&PrettyOptions::default()).to_string()
+}
+
+mod test_scalars {
+    use super::*;
+
+    #[test]
+    fn resp2() {
+        assert_eq!(pretty(&Value::int(-1)), "(integer) -1");
+        assert_eq!(pretty(&Value::str("OK")), "OK");
+        assert_eq!(pretty(&Value::err("ERR bad")), "(error) ERR bad");
+        assert_eq!(pretty(&Value::b_str(Some("foo"))), "\"foo\"");
+        assert_eq!(pretty(&Value::b_str(Some(""))), "\"\"");
+        assert_eq!(pretty(&Value::BStr(None)), "(nil)");
+        assert_eq!(pretty(&Value::Array(None)), "(nil)");
+    }
+
+    #[test]
+    fn resp3() {
+        assert_eq!(pretty(&Value::Null), "(nil)");
+        assert_eq!(pretty(&Value::Bool(true)), "(true)");
+        assert_eq!(pretty(&Value::Bool(false)), "(false)");
+        assert_eq!(pretty(&Value::Double(1.5)), "(double) 1.5");
+        assert_eq!(pretty(&Value::Double(f64::INFINITY)), "(double) inf");
+        assert_eq!(pretty(&Value::Double(f64::NAN)), "(double) nan");
+        assert_eq!(
+            pretty(&Value::BigNum("123456789012345678901234567890".to_owned())),
+            "(big number) 123456789012345678901234567890"
+        );
+        assert_eq!(
+            pretty(&Value::BErr("ERR bad".to_owned())),
+            "(error) ERR bad"
+        );
+        assert_eq!(
+            pretty(&Value::Verbatim("txt".to_owned(), "a\nb".to_owned())),
+            "a\nb"
+        );
+    }
+
+    #[test]
+    fn escaping() {
+        assert_eq!(
+            pretty(&Value::b_str(Some("\"quoted\" \\ \r\n\t\x07\x08"))),
+            r#""\"quoted\" \\ \r\n\t\a\b""#
+        );
+        assert_eq!(pretty(&Value::b_str(Some("\x00\x7f"))), r#""\x00\x7f""#);
+        assert_eq!(pretty(&Value::b_str(Some("\u{20ac}"))), r#""\xe2\x82\xac""#);
+    }
+}
+
+mod test_aggregates {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        assert_eq!(pretty(&resp![]), "(empty array)");
+        assert_eq!(pretty(&Value::Map(vec![])), "(empty hash)");
+        assert_eq!(pretty(&Value::Set(vec![])), "(empty set)");
+        assert_eq!(pretty(&Value::Push(vec![])), "(empty push)");
+    }
+
+    #[test]
+    fn nested() {
+        let value = resp![1, ["a", ["b", nil]], [], "c"];
+
+        assert_eq!(
+            pretty(&value),
+            "1) (integer) 1
+2) 1) \"a\"
+   2) 1) \"b\"
+      2) (nil)
+3) (empty array)
+4) \"c\""
+        );
+    }
+
+    #[test]
+    fn width() {
+        let value = resp![1, 2, 3, 4, 5, 6, 7, 8, 9, ["a", "b"]];
+
+        assert_eq!(
+            pretty(&value),
+            " 1) (integer) 1
+ 2) (integer) 2
+ 3) (integer) 3
+ 4) (integer) 4
+ 5) (integer) 5
+ 6) (integer) 6
+ 7) (integer) 7
+ 8) (integer) 8
+ 9) (integer) 9
+10) 1) \"a\"
+    2) \"b\""
+        );
+    }
+
+    #[test]
+    fn resp3() {
+        let value = Value::Map(vec![
+            (Value::b_str(Some("a")), Value::int(1)),
+            (
+                Value::b_str(Some("b")),
+                Value::Set(vec![Value::str("x"), Value::str("y")]),
+            ),
+        ]);
+
+        // Like redis-cli, nested lines are only indented past the number, not the key
+        assert_eq!(
+            pretty(&value),
+            "1# \"a\" => (integer) 1
+2# \"b\" => 1~ x
+   2~ y"
+        );
+
+        let push = Value::Push(vec![Value::b_str(Some("message")), Value::Bool(true)]);
+
+        assert_eq!(pretty(&push), "1) \"message\"\n2) (true)");
+    }
+}
+
+mod test_truncation {
+    use super::*;
+
+    #[test]
+    fn strings() {
+        let options = PrettyOptions {
+            max_string_len: Some(3),
+            ..PrettyOptions::default()
+        };
+
+        assert_eq!(
+            Value::b_str(Some("abcdef")).pretty(&options).to_string(),
+            "\"abc\"… 3 more"
+        );
+        assert_eq!(
+            Value::b_str(Some("abc")).pretty(&options).to_string(),
+            "\"abc\""
+        );
+        assert_eq!(
+            Value::err("ERR bad").pretty(&options).to_string(),
+            "(error) ERR… 4 more"
+        );
+
+        // Characters aren't split
+        assert_eq!(
+            Value::b_str(Some("ab\u{e9}")).pretty(&options).to_string(),
+            "\"ab\"… 2 more"
+        );
+    }
+
+    #[test]
+    fn aggregates() {
+        let options = PrettyOptions {
+            max_items: Some(2),
+            ..PrettyOptions::default()
+        };
+
+        let value = resp![1, [2, 3, 4], 5, 6];
+
+        assert_eq!(
+            value.pretty(&options).to_string(),
+            "1) (integer) 1
+2) 1) (integer) 2
+   2) (integer) 3
+   … 1 more
+… 2 more"
+        );
+
+        let map = Value::Map(vec![
+            (Value::int(1), Value::int(2)),
+            (Value::int(3), Value::int(4)),
+            (Value::int(5), Value::int(6)),
+        ]);
+
+        assert_eq!(
+            map.pretty(&options).to_string(),
+            "1# (integer) 1 => (integer) 2\n2# (integer) 3 => (integer) 4\n… 1 more"
+        );
+
+        let none = PrettyOptions {
+            max_items: Some(0),
+            ..PrettyOptions::default()
+        };
+
+        assert_eq!(resp![1, 2].pretty(&none).to_string(), "… 2 more");
+    }
+}
+
+mod test_colors {
+    use super::*;
+
+    #[test]
+    fn types() {
+        let options = PrettyOptions {
+            colors: true,
+            ..PrettyOptions::default()
+        };
+
+        let value = resp![1, "a", -"ERR", nil];
+
+        assert_eq!(
+            value.pretty(&options).to_string(),
+            "\x1b[90m1)\x1b[0m \x1b[33m(integer) 1\x1b[0m
+\x1b[90m2)\x1b[0m \x1b[32m\"a\"\x1b[0m
+\x1b[90m3)\x1b[0m \x1b[31m(error) ERR\x1b[0m
+\x1b[90m4)\x1b[0m \x1b[90m(nil)\x1b[0m"
+        );
+    }
+}
+
+mod test_raw {
+    use super::*;
+
+    fn raw(value: &Value) -> String {
+        let options = PrettyOptions {
+            raw: true,
+            colors: true,
+            ..PrettyOptions::default()
+        };
+
+        value.pretty(&options).to_string()
+    }
+
+    #[test]
+    fn scalars() {
+        assert_eq!(raw(&Value::int(-1)), "-1");
+        assert_eq!(raw(&Value::b_str(Some("a\"b\n"))), "a\"b\n");
+        assert_eq!(raw(&Value::err("ERR bad")), "ERR bad");
+        assert_eq!(raw(&Value::BStr(None)), "");
+        assert_eq!(raw(&Value::Double(f64::NAN)), "nan");
+    }
+
+    #[test]
+    fn aggregates() {
+        let value = resp![1, ["a", nil], [], "b"];
+
+        // Nested items aren't distinguished, and empty aggregates are empty lines
+        assert_eq!(raw(&value), "1\na\n\n\nb");
+
+        let options = PrettyOptions {
+            raw: true,
+            max_items: Some(1),
+            ..PrettyOptions::default()
+        };
+
+        assert_eq!(value.pretty(&options).to_string(), "1\n… 3 more");
+    }
+}
+
+mod test_transcripts {
+    use super::*;
+
+    use lib_resp::{Parser, Protocol};
+
+    /// A reply, and how `redis-cli` shows it by default and with `--raw`.
+    struct Transcript {
+        command: &'static str,
+        reply: &'static [u8],
+        tty: &'static str,
+        raw: &'static str,
+    }
+
+    const TRANSCRIPTS: &[Transcript] = &[
+        Transcript {
+            command: "SET foo bar",
+            reply: b"+OK\r\n",
+            tty: "OK",
+            raw: "OK",
+        },
+        Transcript {
+            command: "EXISTS foo",
+            reply: b":1\r\n",
+            tty: "(integer) 1",
+            raw: "1",
+        },
+        Transcript {
+            command: "FOO",
+            reply: b"-ERR unknown command 'FOO', with args beginning with: \r\n",
+            tty: "(error) ERR unknown command 'FOO', with args beginning with: ",
+            raw: "ERR unknown command 'FOO', with args beginning with: ",
+        },
+        Transcript {
+            command: "GET missing",
+            reply: b"$-1\r\n",
+            tty: "(nil)",
+            raw: "",
+        },
+        Transcript {
+            command: "GET empty",
+            reply: b"$0\r\n\r\n",
+            tty: "\"\"",
+            raw: "",
+        },
+        Transcript {
+            command: "LRANGE missing 0 -1",
+            reply: b"*0\r\n",
+            tty: "(empty array)",
+            raw: "",
+        },
+        Transcript {
+            command: "BLPOP missing 1",
+            reply: b"*-1\r\n",
+            tty: "(nil)",
+            raw: "",
+        },
+        Transcript {
+            command: "MGET a missing c",
+            reply: b"*3\r\n$1\r\n1\r\n$-1\r\n$4\r\nx\ny\"\r\n",
+            tty: "1) \"1\"\n2) (nil)\n3) \"x\\ny\\\"\"",
+            raw: "1\n\nx\ny\"",
+        },
+        Transcript {
+            command: "SCAN 0",
+            reply: b"*2\r\n$1\r\n0\r\n*0\r\n",
+            tty: "1) \"0\"\n2) (empty array)",
+            raw: "0\n",
+        },
+        // RESP3, after HELLO 3
+        Transcript {
+            command: "GET missing",
+            reply: b"_\r\n",
+            tty: "(nil)",
+            raw: "",
+        },
+        Transcript {
+            command: "HGETALL missing",
+            reply: b"%0\r\n",
+            tty: "(empty hash)",
+            raw: "",
+        },
+        Transcript {
+            command: "SMEMBERS missing",
+            reply: b"~0\r\n",
+            tty: "(empty set)",
+            raw: "",
+        },
+        Transcript {
+            command: "HGETALL hash",
+            reply: b"%2\r\n$1\r\na\r\n$1\r\n1\r\n$1\r\nb\r\n$1\r\n2\r\n",
+            tty: "1# \"a\" => \"1\"\n2# \"b\" => \"2\"",
+            raw: "a 1\nb 2",
+        },
+        Transcript {
+            command: "SMEMBERS set",
+            reply: b"~2\r\n$1\r\na\r\n$1\r\nb\r\n",
+            tty: "1~ \"a\"\n2~ \"b\"",
+            raw: "a\nb",
+        },
+        Transcript {
+            command: "EVAL \"return true\" 0",
+            reply: b"#t\r\n",
+            tty: "(true)",
+            raw: "(true)",
+        },
+        Transcript {
+            command: "ZSCORE zset a",
+            reply: b",3.14\r\n",
+            tty: "(double) 3.14",
+            raw: "3.14",
+        },
+        Transcript {
+            command: "DEBUG PROTOCOL bignum",
+            reply: b"(1234567999999999999999999999999999999\r\n",
+            tty: "(big number) 1234567999999999999999999999999999999",
+            raw: "1234567999999999999999999999999999999",
+        },
+        Transcript {
+            command: "DEBUG PROTOCOL verbatim",
+            reply: b"=29\r\ntxt:This is a verbatim\nstring\r\n",
+            tty: "This is a verbatim\nstring",
+            raw: "This is a verbatim\nstring",
+        },
+        Transcript {
+            command: "DEBUG PROTOCOL null",
+            reply: b"_\r\n",
+            tty: "(nil)",
+            raw: "",
+        },
+    ];
+
+    #[test]
+    fn tty() {
+        for transcript in TRANSCRIPTS {
+            let (_, value) = Parser::parse_protocol(transcript.reply, Protocol::Resp3).unwrap();
+
+            assert_eq!(value.to_string(), transcript.tty, "{}", transcript.command);
+        }
+    }
+
+    #[test]
+    fn raw() {
+        let options = PrettyOptions {
+            raw: true,
+            ..PrettyOptions::default()
+        };
+
+        for transcript in TRANSCRIPTS {
+            let (_, value) = Parser::parse_protocol(transcript.reply, Protocol::Resp3).unwrap();
+
+            assert_eq!(
+                value.pretty(&options).to_string(),
+                transcript.raw,
+                "{}",
+                transcript.command
+            );
+        }
+    }
+}
@@ -0,0 +1,227 @@
+extern crate lib_resp;
+
+mod common;
+
+use std::io::{BufReader, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use common::{array, b_str, ok, MockServer};
+use lib_resp::{Action, Client, Decoder, Exchange, Protocol, Proxy, Value};
+
+type Exchanges = Arc<Mutex<Vec<Exchange>>>;
+
+/// Starts a proxy in front of a mock server, returning the proxy's address.
+fn start<F>(filter: F) -> (SocketAddr, common::Log, Exchanges)
+where
+    F: Fn(&Value) -> Action + Send + Sync + 'static,
+{
+    let server = MockServer::bind();
+    let upstream = server.addr();
+
+    let log = server.serve(|args| match args[0].as_str() {
+        "HELLO" => Value::Map(vec![(b_str("proto"), Value::int(3))]),
+        "GET" => b_str("bar"),
+        "INCR" => Value::int(1),
+        "NULL" => Value::Null,
+        _ => ok(),
+    });
+
+    let exchanges = Exchanges::default();
+
+    let proxy = {
+        let exchanges = exchanges.clone();
+
+        Proxy::bind("127.0.0.1:0", upstream)
+            .unwrap()
+            .filter(filter)
+            .log(move |exchange| exchanges.lock().unwrap().push(exchange.clone()))
+    };
+
+    let addr = proxy.local_addr().unwrap();
+
+    thread::spawn(move || proxy.run());
+
+    (addr, log, exchanges)
+}
+
+/// Waits for `n` exchanges to be logged, they're logged after the reply is sent.
+fn logged(exchanges: &Exchanges, n: usize) -> Vec<Exchange> {
+    for _ in 0..100 {
+        let exchanges = exchanges.lock().unwrap();
+
+        if exchanges.len() >= n {
+            return exchanges.clone();
+        }
+
+        drop(exchanges);
+
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    panic!("Timed out waiting for exchanges");
+}
+
+fn command(args: &[&str]) -> Value {
+    array(args.iter().map(|arg| b_str(arg)).collect())
+}
+
+#[test]
+fn forward() {
+    let (addr, log, exchanges) = start(|_| Action::Forward);
+
+    let mut client = Client::connect(addr).unwrap();
+
+    assert_eq!(client.command(&["GET", "foo"]).unwrap(), b_str("bar"));
+    assert_eq!(client.command(&["INCR", "n"]).unwrap(), Value::int(1));
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![vec!["GET", "foo"], vec!["INCR", "n"]]
+    );
+
+    let exchanges = logged(&exchanges, 2);
+
+    assert_eq!(exchanges.len(), 2);
+    assert_eq!(exchanges[0].connection, 0);
+    assert_eq!(exchanges[0].client, client.get_ref().local_addr().unwrap());
+    assert_eq!(exchanges[0].request, command(&["GET", "foo"]));
+    assert_eq!(exchanges[0].action, Action::Forward);
+    assert_eq!(exchanges[0].reply, b_str("bar"));
+}
+
+/// Frames are forwarded byte for byte, not re-encoded
+#[test]
+fn unchanged() {
+    let (addr, _, _) = start(|_| Action::Forward);
+
+    let mut socket = TcpStream::connect(addr).unwrap();
+
+    // A non-canonical, but valid, encoding
+    socket
+        .write_all(b"*2\r\n$3\r\nGET\r\n$03\r\nfoo\r\n")
+        .unwrap();
+
+    let mut reply = [0; 9];
+    socket.read_exact(&mut reply).unwrap();
+
+    assert_eq!(&reply, b"$3\r\nbar\r\n");
+}
+
+#[test]
+fn rewrite() {
+    let (addr, log, exchanges) = start(|request| {
+        if *request == command(&["KEYS", "*"]) {
+            Action::Rewrite(command(&["SCAN", "0"]))
+        } else {
+            Action::Forward
+        }
+    });
+
+    let mut client = Client::connect(addr).unwrap();
+
+    assert_eq!(client.command(&["KEYS", "*"]).unwrap(), ok());
+
+    assert_eq!(*log.lock().unwrap(), vec![vec!["SCAN", "0"]]);
+    assert_eq!(logged(&exchanges, 1)[0].request, command(&["KEYS", "*"]));
+}
+
+/// Blocked requests are replied to in order, even when pipelined
+#[test]
+fn block() {
+    let (addr, log, exchanges) = start(|request| {
+        if *request == command(&["FLUSHALL"]) {
+            Action::Block(Value::err("ERR blocked"))
+        } else {
+            Action::Forward
+        }
+    });
+
+    let mut client = Client::connect(addr).unwrap();
+
+    client.send(&command(&["GET", "foo"])).unwrap();
+    client.send(&command(&["FLUSHALL"])).unwrap();
+    client.send(&command(&["INCR", "n"])).unwrap();
+
+    assert_eq!(client.recv().unwrap(), b_str("bar"));
+    assert_eq!(client.recv().unwrap(), Value::err("ERR blocked"));
+    assert_eq!(client.recv().unwrap(), Value::int(1));
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![vec!["GET", "foo"], vec!["INCR", "n"]]
+    );
+    assert_eq!(
+        logged(&exchanges, 3)[1].action,
+        Action::Block(Value::err("ERR blocked"))
+    );
+}
+
+/// Each connection has its own ID, in the order they're accepted
+#[test]
+fn connections() {
+    let (addr, _, exchanges) = start(|_| Action::Forward);
+
+    let mut first = Client::connect(addr).unwrap();
+
+    assert_eq!(first.command(&["GET", "foo"]).unwrap(), b_str("bar"));
+
+    logged(&exchanges, 1);
+
+    let mut second = Client::connect(addr).unwrap();
+
+    assert_eq!(second.command(&["GET", "foo"]).unwrap(), b_str("bar"));
+
+    logged(&exchanges, 2);
+
+    drop(first);
+
+    let mut third = Client::connect(addr).unwrap();
+
+    assert_eq!(third.command(&["GET", "foo"]).unwrap(), b_str("bar"));
+
+    logged(&exchanges, 3);
+
+    assert_eq!(second.command(&["INCR", "n"]).unwrap(), Value::int(1));
+
+    let connections: Vec<_> = logged(&exchanges, 4)
+        .iter()
+        .map(|exchange| exchange.connection)
+        .collect();
+
+    assert_eq!(connections, vec![0, 1, 2, 1]);
+}
+
+/// Replies are decoded as RESP3 after `HELLO 3`
+#[test]
+fn hello() {
+    let (addr, _, exchanges) = start(|_| Action::Forward);
+
+    let mut client = Client::connect(addr).unwrap();
+
+    client.set_protocol(Protocol::Resp3);
+
+    assert_eq!(
+        client.command(&["HELLO", "3"]).unwrap(),
+        Value::Map(vec![(b_str("proto"), Value::int(3))])
+    );
+    assert_eq!(client.command(&["NULL"]).unwrap(), Value::Null);
+
+    assert_eq!(logged(&exchanges, 2)[1].reply, Value::Null);
+}
+
+#[test]
+fn invalid() {
+    let (addr, _, _) = start(|_| Action::Forward);
+
+    let socket = TcpStream::connect(addr).unwrap();
+
+    (&socket).write_all(b"PING\r\n").unwrap();
+
+    let mut decoder = Decoder::new(BufReader::new(socket));
+
+    // The connection is closed
+    assert_eq!(decoder.decode().unwrap(), None);
+}
@@ -0,0 +1,291 @@
+extern crate lib_resp;
+
+mod common;
+
+use std::io::{BufReader, ErrorKind, Write};
+use std::net::{SocketAddr, TcpListener};
+use std::sync::mpsc::{channel, Receiver};
+use std::thread;
+
+use common::{args, b_str};
+use lib_resp::{Client, Decoder, Psync, Replica, ReplicaOptions, ReplicatedCommand, Value};
+
+const REPLID: &str = "8e19b1a3d1c3e4a2f0b7c6d5e4f3a2b1c0d9e8f7";
+
+const MARK: &str = "0123456789abcdefghij0123456789abcdefghij";
+
+/// Serves a single replica, replying to `PSYNC` with `stream`.
+///
+/// Every command received is sent on the returned channel.
+fn master(stream: Vec<u8>) -> (SocketAddr, Receiver<Vec<String>>) {
+    serve(stream, false)
+}
+
+/// Like `master`, but hangs up after replying to `PSYNC`.
+fn closing_master(stream: Vec<u8>) -> (SocketAddr, Receiver<Vec<String>>) {
+    serve(stream, true)
+}
+
+fn serve(stream: Vec<u8>, close: bool) -> (SocketAddr, Receiver<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let (tx, rx) = channel();
+
+    thread::spawn(move || {
+        let (mut socket, _) = listener.accept().unwrap();
+
+        let mut decoder = Decoder::new(BufReader::new(socket.try_clone().unwrap()));
+
+        while let Ok(Some(value)) = decoder.decode() {
+            let args = args(&value);
+
+            let reply = match args[0].as_str() {
+                "PING" => b"+PONG\r\n".to_vec(),
+                "REPLCONF" if args[1] == "ACK" => Vec::new(),
+                "REPLCONF" => b"+OK\r\n".to_vec(),
+                "PSYNC" => stream.clone(),
+                _ => b"-ERR unknown command\r\n".to_vec(),
+            };
+
+            let psync = args[0] == "PSYNC";
+
+            if tx.send(args).is_err() || socket.write_all(&reply).is_err() || (psync && close) {
+                return;
+            }
+        }
+    });
+
+    (addr, rx)
+}
+
+fn command(args: &[&str]) -> Value {
+    Value::Array(Some(args.iter().map(|arg| b_str(arg)).collect()))
+}
+
+fn commands() -> Vec<u8> {
+    let mut bytes = command(&["SELECT", "0"]).encode_bytes();
+    bytes.extend(command(&["SET", "foo", "bar"]).encode_bytes());
+    bytes
+}
+
+fn check_commands(replica: &mut Replica, offset: i64) {
+    let select = command(&["SELECT", "0"]);
+    let set = command(&["SET", "foo", "bar"]);
+
+    let select_offset = offset + select.encode_bytes().len() as i64;
+    let set_offset = select_offset + set.encode_bytes().len() as i64;
+
+    assert_eq!(
+        replica.next().unwrap().unwrap(),
+        ReplicatedCommand {
+            offset: select_offset,
+            command: select,
+        }
+    );
+    assert_eq!(
+        replica.next().unwrap().unwrap(),
+        ReplicatedCommand {
+            offset: set_offset,
+            command: set,
+        }
+    );
+    assert_eq!(replica.offset(), set_offset);
+}
+
+mod test_sync {
+    use super::*;
+
+    #[test]
+    fn full_resync() {
+        // Masters send newlines to keep the connection alive while the RDB is being saved
+        let mut stream = format!("+FULLRESYNC {} 100\r\n\n\n$9\r\nREDIS0011", REPLID).into_bytes();
+        stream.extend(commands());
+
+        let (addr, rx) = master(stream);
+
+        let mut rdb = Vec::new();
+
+        let mut replica = Replica::sync(
+            Client::connect(addr).unwrap(),
+            &ReplicaOptions {
+                listening_port: 6380,
+                ..ReplicaOptions::default()
+            },
+            &mut rdb,
+        )
+        .unwrap();
+
+        assert_eq!(
+            *replica.psync(),
+            Psync::FullResync {
+                replid: REPLID.to_owned(),
+                offset: 100,
+            }
+        );
+        assert_eq!(replica.replid(), REPLID);
+        assert_eq!(rdb, b"REDIS0011");
+
+        check_commands(&mut replica, 100);
+
+        let received: Vec<Vec<String>> = rx.iter().take(4).collect();
+
+        assert_eq!(
+            received,
+            vec![
+                vec!["PING"],
+                vec!["REPLCONF", "listening-port", "6380"],
+                vec!["REPLCONF", "capa", "psync2"],
+                vec!["PSYNC", "?", "-1"],
+            ]
+        );
+    }
+
+    #[test]
+    fn eof_mark() {
+        let mut stream = format!(
+            "+FULLRESYNC {} 0\r\n$EOF:{}\r\nREDIS0011{}",
+            REPLID, MARK, MARK
+        )
+        .into_bytes();
+        stream.extend(commands());
+
+        let (addr, rx) = master(stream);
+
+        let mut rdb = Vec::new();
+
+        let mut replica = Replica::sync(
+            Client::connect(addr).unwrap(),
+            &ReplicaOptions {
+                eof: true,
+                ..ReplicaOptions::default()
+            },
+            &mut rdb,
+        )
+        .unwrap();
+
+        assert_eq!(rdb, b"REDIS0011");
+
+        check_commands(&mut replica, 0);
+
+        assert_eq!(
+            rx.iter().nth(2).unwrap(),
+            vec!["REPLCONF", "capa", "eof", "capa", "psync2"]
+        );
+    }
+
+    #[test]
+    fn continue_stream() {
+        let mut stream = b"+CONTINUE\r\n".to_vec();
+        stream.extend(commands());
+
+        let (addr, rx) = master(stream);
+
+        let mut replica = Replica::connect(
+            addr,
+            &ReplicaOptions {
+                replid: Some(REPLID.to_owned()),
+                offset: 41,
+                ..ReplicaOptions::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(*replica.psync(), Psync::Continue { replid: None });
+        assert_eq!(replica.replid(), REPLID);
+        assert_eq!(replica.offset(), 41);
+
+        check_commands(&mut replica, 41);
+
+        assert_eq!(rx.iter().nth(3).unwrap(), vec!["PSYNC", REPLID, "42"]);
+    }
+
+    #[test]
+    fn continue_new_replid() {
+        let (addr, _rx) = master(b"+CONTINUE abc\r\n".to_vec());
+
+        let replica = Replica::connect(
+            addr,
+            &ReplicaOptions {
+                replid: Some(REPLID.to_owned()),
+                offset: 41,
+                ..ReplicaOptions::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            *replica.psync(),
+            Psync::Continue {
+                replid: Some("abc".to_owned()),
+            }
+        );
+        assert_eq!(replica.replid(), "abc");
+    }
+
+    #[test]
+    fn error() {
+        let (addr, _rx) =
+            master(b"-NOMASTERLINK Can't SYNC while not connected with my master\r\n".to_vec());
+
+        let err = Replica::connect(addr, &ReplicaOptions::default())
+            .err()
+            .unwrap();
+
+        assert!(err.to_string().starts_with("NOMASTERLINK"));
+    }
+
+    #[test]
+    fn truncated_rdb() {
+        let (addr, _rx) =
+            closing_master(format!("+FULLRESYNC {} 0\r\n$100\r\nREDIS", REPLID).into_bytes());
+
+        let err = Replica::connect(addr, &ReplicaOptions::default())
+            .err()
+            .unwrap();
+
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn closed() {
+        let mut stream = format!("+FULLRESYNC {} 0\r\n$0\r\n", REPLID).into_bytes();
+        stream.extend(commands());
+
+        let (addr, _rx) = closing_master(stream);
+
+        let mut replica = Replica::connect(addr, &ReplicaOptions::default()).unwrap();
+
+        assert_eq!(replica.by_ref().count(), 2);
+        assert!(replica.next().is_none());
+    }
+}
+
+mod test_stream {
+    use super::*;
+
+    #[test]
+    fn ack() {
+        let mut stream = format!("+FULLRESYNC {} 100\r\n$0\r\n", REPLID).into_bytes();
+        stream.extend(command(&["REPLCONF", "GETACK", "*"]).encode_bytes());
+
+        let (addr, rx) = master(stream);
+
+        let mut replica = Replica::connect(addr, &ReplicaOptions::default()).unwrap();
+
+        let getack = replica.next().unwrap().unwrap();
+
+        assert_eq!(getack.command, command(&["REPLCONF", "GETACK", "*"]));
+
+        replica.ack().unwrap();
+
+        assert_eq!(
+            rx.iter().nth(4).unwrap(),
+            vec![
+                "REPLCONF".to_owned(),
+                "ACK".to_owned(),
+                getack.offset.to_string()
+            ]
+        );
+    }
+}
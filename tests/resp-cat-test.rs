@@ -0,0 +1,108 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn resp_cat(args: &[&str], input: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_resp-cat"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child.stdin.take().unwrap().write_all(input).unwrap();
+
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+const INPUT: &[u8] = b"*2\r\n$3\r\nGET\r\n$3\r\nfoo\r\n:5\r\n";
+
+#[test]
+fn display() {
+    let output = resp_cat(&[], INPUT);
+
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "1) \"GET\"\n2) \"foo\"\n(integer) 5\n");
+}
+
+#[test]
+fn debug() {
+    let output = resp_cat(&["--format", "debug"], b":5\r\n");
+
+    assert_eq!(stdout(&output), "Int(5)\n");
+}
+
+#[test]
+fn json() {
+    let output = resp_cat(&["-f", "json"], INPUT);
+
+    assert_eq!(stdout(&output), "[\"GET\",\"foo\"]\n5\n");
+}
+
+#[test]
+fn offsets() {
+    let output = resp_cat(&["-o"], INPUT);
+
+    assert_eq!(
+        stdout(&output),
+        "@0 (22 bytes)\n1) \"GET\"\n2) \"foo\"\n@22 (4 bytes)\n(integer) 5\n"
+    );
+
+    let output = resp_cat(&["-o", "-f", "json"], INPUT);
+
+    assert_eq!(
+        stdout(&output),
+        "{\"offset\":0,\"size\":22,\"value\":[\"GET\",\"foo\"]}\n\
+         {\"offset\":22,\"size\":4,\"value\":5}\n"
+    );
+}
+
+#[test]
+fn resp3() {
+    assert!(!resp_cat(&[], b"#t\r\n").status.success());
+
+    assert_eq!(stdout(&resp_cat(&["-3"], b"#t\r\n")), "(true)\n");
+}
+
+#[test]
+fn invalid() {
+    let output = resp_cat(&[], b":1\r\nXX\r\n:2\r\n");
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "(integer) 1\n");
+    assert_eq!(stderr(&output), "resp-cat: invalid RESP at offset 4\n");
+}
+
+#[test]
+fn keep_going() {
+    let output = resp_cat(&["--keep-going"], b":1\r\nXX\r\n:2\r\n");
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "(integer) 1\n(integer) 2\n");
+    assert_eq!(stderr(&output), "resp-cat: invalid RESP at offset 4\n");
+}
+
+#[test]
+fn truncated() {
+    let output = resp_cat(&[], b":1\r\n$5\r\nab");
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "(integer) 1\n");
+    assert_eq!(stderr(&output), "resp-cat: truncated value at offset 4\n");
+}
+
+#[test]
+fn usage() {
+    let output = resp_cat(&["--format", "xml"], b"");
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("Usage: resp-cat"));
+}
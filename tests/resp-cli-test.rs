@@ -0,0 +1,261 @@
+#![cfg(feature = "cli")]
+
+extern crate lib_resp;
+
+mod common;
+
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::process::{Command, Output, Stdio};
+use std::thread;
+
+use common::{array, b_str, ok, MockServer};
+use lib_resp::Value;
+
+/// Replies to `HELLO` in the requested protocol.
+fn hello(version: &str) -> Value {
+    let pairs = vec![
+        (b_str("server"), b_str("redis")),
+        (b_str("version"), b_str("7.2.4")),
+        (b_str("proto"), Value::int(version.parse().unwrap())),
+    ];
+
+    match version {
+        "3" => Value::Map(pairs),
+        _ => array(pairs.into_iter().flat_map(|(k, v)| vec![k, v]).collect()),
+    }
+}
+
+fn server() -> (String, common::Log) {
+    let server = MockServer::bind();
+    let port = server.addr().port().to_string();
+
+    let log = server.serve(|args| match args[0].as_str() {
+        "HELLO" => hello(&args[1]),
+        "GET" if args[1] == "missing" => Value::b_str(None::<String>),
+        "GET" => b_str("bar"),
+        "INCR" => Value::int(2),
+        "LRANGE" => array(vec![b_str("a"), b_str("b")]),
+        "DOUBLE" => Value::Double(1.5),
+        "FAIL" => Value::err("ERR failed"),
+        _ => ok(),
+    });
+
+    (port, log)
+}
+
+fn resp_cli(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_resp-cli"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn command() {
+    let (port, log) = server();
+
+    let output = resp_cli(&["-p", &port, "GET", "foo"], "");
+
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "\"bar\"\n");
+    assert_eq!(*log.lock().unwrap(), vec![vec!["GET", "foo"]]);
+}
+
+#[test]
+fn stdin() {
+    let (port, log) = server();
+
+    let output = resp_cli(
+        &["-p", &port],
+        "SET foo \"hello world\"\n\nINCR n\nLRANGE l 0 -1\nFAIL\nquit\nGET foo\n",
+    );
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "OK\n(integer) 2\n1) \"a\"\n2) \"b\"\n(error) ERR failed\n"
+    );
+    assert_eq!(log.lock().unwrap()[0], vec!["SET", "foo", "hello world"]);
+    assert_eq!(log.lock().unwrap().len(), 4);
+}
+
+#[test]
+fn raw() {
+    let (port, _) = server();
+
+    let output = resp_cli(
+        &["-p", &port, "--raw"],
+        "GET foo\nINCR n\nLRANGE l 0 -1\nGET missing\n",
+    );
+
+    assert_eq!(stdout(&output), "bar\n2\na\nb\n\n");
+}
+
+#[test]
+fn setup() {
+    let (port, log) = server();
+
+    resp_cli(&["-p", &port, "-a", "hunter2", "-n", "2", "GET", "foo"], "");
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            vec!["HELLO", "2", "AUTH", "default", "hunter2"],
+            vec!["SELECT", "2"],
+            vec!["GET", "foo"],
+        ]
+    );
+}
+
+#[test]
+fn resp3() {
+    let (port, log) = server();
+
+    let output = resp_cli(
+        &[
+            "-p", &port, "-3", "--user", "josh", "-a", "hunter2", "DOUBLE",
+        ],
+        "",
+    );
+
+    assert_eq!(stdout(&output), "(double) 1.5\n");
+    assert_eq!(
+        log.lock().unwrap()[0],
+        vec!["HELLO", "3", "AUTH", "josh", "hunter2"]
+    );
+}
+
+/// Servers without RESP3 are spoken to in RESP2
+#[test]
+fn noproto() {
+    let server = MockServer::bind();
+    let port = server.addr().port().to_string();
+
+    let log = server.serve(|args| match (args[0].as_str(), args.get(1)) {
+        ("HELLO", Some(version)) if version == "3" => Value::err("NOPROTO unsupported"),
+        ("HELLO", _) => hello("2"),
+        _ => b_str("1.5"),
+    });
+
+    let output = resp_cli(&["-p", &port, "-3", "GET", "pi"], "");
+
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "\"1.5\"\n");
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![vec!["HELLO", "3"], vec!["HELLO", "2"], vec!["GET", "pi"]]
+    );
+}
+
+/// Servers without `HELLO` are authenticated with `AUTH`
+#[test]
+fn unknown_hello() {
+    let server = MockServer::bind();
+    let port = server.addr().port().to_string();
+
+    let log = server.serve(|args| match args[0].as_str() {
+        "HELLO" => Value::err("ERR unknown command 'HELLO'"),
+        _ => ok(),
+    });
+
+    let output = resp_cli(&["-p", &port, "-a", "hunter2", "PING"], "");
+
+    assert!(output.status.success());
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            vec!["HELLO", "2", "AUTH", "default", "hunter2"],
+            vec!["AUTH", "hunter2"],
+            vec!["PING"],
+        ]
+    );
+}
+
+#[test]
+fn failed_setup() {
+    let server = MockServer::bind();
+    let port = server.addr().port().to_string();
+
+    server.serve(|_| Value::err("WRONGPASS invalid username-password pair"));
+
+    let output = resp_cli(&["-p", &port, "-a", "hunter2", "PING"], "");
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("WRONGPASS"));
+}
+
+/// Arguments which aren't valid UTF-8 are sent as is
+#[test]
+fn binary_args() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port().to_string();
+
+    let expected = b"*3\r\n$3\r\nSET\r\n$1\r\nk\r\n$2\r\n\xff\x00\r\n";
+
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+
+        let mut received = vec![0; expected.len()];
+
+        stream.read_exact(&mut received).unwrap();
+        stream.write_all(b"+OK\r\n").unwrap();
+
+        received
+    });
+
+    let output = resp_cli(&["-p", &port], "SET k \"\\xff\\x00\"\n");
+
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "OK\n");
+    assert_eq!(server.join().unwrap(), &expected[..]);
+}
+
+#[test]
+fn invalid_args() {
+    let (port, log) = server();
+
+    let output = resp_cli(&["-p", &port], "GET \"foo\nGET foo\n");
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "\"bar\"\n");
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "Invalid argument(s)\n"
+    );
+    assert_eq!(log.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn connection_refused() {
+    let port = {
+        let server = MockServer::bind();
+
+        server.addr().port().to_string()
+    };
+
+    let output = resp_cli(&["-p", &port, "PING"], "");
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .starts_with("Could not connect to 127.0.0.1:"));
+}
@@ -0,0 +1,97 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn resp_encode(args: &[&str], input: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_resp-encode"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child.stdin.take().unwrap().write_all(input).unwrap();
+
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn commands() {
+    let output = resp_encode(&[], b"SET foo \"bar baz\"\n\n  \nGET \"\\x00\\xff\"\n");
+
+    assert!(output.status.success());
+    assert_eq!(
+        output.stdout,
+        &b"*3\r\n$3\r\nSET\r\n$3\r\nfoo\r\n$7\r\nbar baz\r\n*2\r\n$3\r\nGET\r\n$2\r\n\x00\xff\r\n"
+            [..]
+    );
+}
+
+#[test]
+fn json() {
+    let output = resp_encode(
+        &["--json"],
+        b"[\"GET\", \"foo\"]\n{\"error\": \"ERR\"}\nnull\n",
+    );
+
+    assert!(output.status.success());
+    assert_eq!(
+        output.stdout,
+        &b"*2\r\n$3\r\nGET\r\n$3\r\nfoo\r\n-ERR\r\n$-1\r\n"[..]
+    );
+}
+
+#[test]
+fn invalid() {
+    let output = resp_encode(&[], b"GET foo\nSET \"foo\n");
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "resp-encode: line 2: Unbalanced quotes\n"
+    );
+
+    let output = resp_encode(&["-j"], b"[1,\n");
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "resp-encode: line 1: Invalid JSON at offset 3\n"
+    );
+}
+
+#[test]
+fn json_commands() {
+    // Arguments are always bulk strings, like commands read from lines
+    let output = resp_encode(
+        &["-j"],
+        b"[\"SET\", \"k\", 1]\n[\"ZADD\", \"z\", 1.5, true]\n",
+    );
+
+    assert!(output.status.success());
+    assert_eq!(
+        output.stdout,
+        resp_encode(&[], b"SET k 1\nZADD z 1.5 true\n").stdout
+    );
+
+    for (input, error) in &[
+        (
+            "[\"SET\", \"k\", [1]]",
+            "Arguments must be strings, numbers or booleans",
+        ),
+        (
+            "[\"SET\", \"k\", {\"a\": 1}]",
+            "Arguments must be strings, numbers or booleans",
+        ),
+        ("[\"SET\", \"k\", null]", "Arguments can't be null"),
+        ("[]", "Commands can't be empty"),
+    ] {
+        let output = resp_encode(&["-j"], input.as_bytes());
+
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8(output.stderr).unwrap(),
+            format!("resp-encode: line 1: {}\n", error)
+        );
+    }
+}
@@ -0,0 +1,207 @@
+extern crate lib_resp;
+
+use lib_resp::{ErrorKind, Redirect, ServerError, Value};
+
+mod test_parse {
+    use super::*;
+
+    #[test]
+    fn codes() {
+        let cases = [
+            ("ERR unknown command 'foo'", ErrorKind::Generic),
+            (
+                "WRONGTYPE Operation against a key holding the wrong kind of value",
+                ErrorKind::WrongType,
+            ),
+            (
+                "NOSCRIPT No matching script. Please use EVAL.",
+                ErrorKind::NoScript,
+            ),
+            ("BUSY Redis is busy running a script.", ErrorKind::Busy),
+            (
+                "READONLY You can't write against a read only replica.",
+                ErrorKind::ReadOnly,
+            ),
+            (
+                "LOADING Redis is loading the dataset in memory",
+                ErrorKind::Loading,
+            ),
+            ("NOAUTH Authentication required.", ErrorKind::NoAuth),
+            (
+                "WRONGPASS invalid username-password pair",
+                ErrorKind::WrongPass,
+            ),
+            (
+                "NOPERM this user has no permissions to run the 'get' command",
+                ErrorKind::NoPerm,
+            ),
+            ("NOPROTO unsupported protocol version", ErrorKind::NoProto),
+            (
+                "OOM command not allowed when used memory > 'maxmemory'.",
+                ErrorKind::OutOfMemory,
+            ),
+            (
+                "EXECABORT Transaction discarded because of previous errors.",
+                ErrorKind::ExecAbort,
+            ),
+            ("MASTERDOWN Link with MASTER is down", ErrorKind::MasterDown),
+            (
+                "CROSSSLOT Keys in request don't hash to the same slot",
+                ErrorKind::CrossSlot,
+            ),
+            (
+                "TRYAGAIN Multiple keys request during rehashing of slot",
+                ErrorKind::TryAgain,
+            ),
+            ("CLUSTERDOWN The cluster is down", ErrorKind::ClusterDown),
+        ];
+
+        for &(error, ref kind) in cases.iter() {
+            assert_eq!(ServerError::parse(error).kind(), kind);
+        }
+    }
+
+    #[test]
+    fn code_and_message() {
+        let error = ServerError::parse("ERR unknown command 'foo'");
+
+        assert_eq!(error.code(), "ERR");
+        assert_eq!(error.message(), "unknown command 'foo'");
+
+        let error = ServerError::parse("ERR");
+
+        assert_eq!(error.code(), "ERR");
+        assert_eq!(error.message(), "");
+    }
+
+    #[test]
+    fn other() {
+        let error = ServerError::parse("FOO bar baz");
+
+        assert_eq!(error.kind(), &ErrorKind::Other("FOO".to_owned()));
+        assert_eq!(error.message(), "bar baz");
+
+        // Codes are case sensitive
+        let error = ServerError::parse("err FOO");
+
+        assert_eq!(error.kind(), &ErrorKind::Other("err".to_owned()));
+    }
+
+    #[test]
+    fn moved() {
+        let error = ServerError::parse("MOVED 3999 127.0.0.1:6381");
+
+        assert_eq!(
+            error.kind(),
+            &ErrorKind::Moved(Redirect {
+                slot: 3999,
+                host: "127.0.0.1".to_owned(),
+                port: 6381,
+            })
+        );
+    }
+
+    #[test]
+    fn ask() {
+        let error = ServerError::parse("ASK 3999 redis-3.example.com:6379");
+
+        assert_eq!(
+            error.kind(),
+            &ErrorKind::Ask(Redirect {
+                slot: 3999,
+                host: "redis-3.example.com".to_owned(),
+                port: 6379,
+            })
+        );
+    }
+
+    #[test]
+    fn redirect_ipv6() {
+        let error = ServerError::parse("MOVED 16383 ::1:30001");
+
+        let redirect = error.redirect().unwrap();
+
+        assert_eq!(redirect.host, "::1");
+        assert_eq!(redirect.port, 30001);
+    }
+
+    #[test]
+    fn redirect_unknown_endpoint() {
+        let error = ServerError::parse("MOVED 0 :6380");
+
+        let redirect = error.redirect().unwrap();
+
+        assert_eq!(redirect.host, "");
+        assert_eq!(redirect.addr(), ":6380");
+    }
+
+    #[test]
+    fn malformed_redirect() {
+        let cases = [
+            "MOVED",
+            "MOVED 3999",
+            "MOVED foo 127.0.0.1:6381",
+            "MOVED 99999 127.0.0.1:6381",
+            "MOVED 16384 127.0.0.1:6381",
+            "ASK 65535 127.0.0.1:6381",
+            "MOVED 3999 127.0.0.1",
+            "MOVED 3999 127.0.0.1:foo",
+            "ASK 3999 127.0.0.1:6381 extra",
+        ];
+
+        for error in cases.iter() {
+            let error = ServerError::parse(error);
+
+            assert!(error.redirect().is_none());
+            assert_eq!(error.kind(), &ErrorKind::Other(error.code().to_owned()));
+        }
+    }
+}
+
+mod test_display {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for error in [
+            "ERR",
+            "ERR unknown command 'foo'",
+            "MOVED 3999 127.0.0.1:6381",
+        ]
+        .iter()
+        {
+            assert_eq!(&ServerError::parse(error).to_string(), error);
+        }
+    }
+}
+
+mod test_value {
+    use super::*;
+
+    #[test]
+    fn err() {
+        let error = Value::err("MOVED 3999 127.0.0.1:6381")
+            .server_error()
+            .unwrap();
+
+        assert_eq!(error.redirect().unwrap().addr(), "127.0.0.1:6381");
+    }
+
+    #[test]
+    fn b_err() {
+        let error = Value::BErr("ASK 3999 127.0.0.1:6381".to_owned())
+            .server_error()
+            .unwrap();
+
+        assert_eq!(error.code(), "ASK");
+        assert_eq!(error.redirect().unwrap().slot, 3999);
+    }
+
+    #[test]
+    fn non_err() {
+        assert!(Value::int(-3).server_error().is_none());
+        assert!(Value::str("OK").server_error().is_none());
+        assert!(Value::b_str(Some("ERR")).server_error().is_none());
+        assert!(Value::Array(None).server_error().is_none());
+    }
+}
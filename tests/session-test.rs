@@ -0,0 +1,394 @@
+extern crate lib_resp;
+
+mod common;
+
+use std::env;
+use std::fs;
+use std::io::{BufReader, Error, ErrorKind, Result};
+use std::net::{SocketAddr, TcpListener};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use common::{array, b_str, ok, MockServer};
+use lib_resp::{replay, Client, ReplayOptions, SessionEntry, SessionReader, SessionWriter, Value};
+
+fn command(args: &[&str]) -> Value {
+    array(args.iter().map(|arg| b_str(arg)).collect())
+}
+
+fn entry(time: u64, request: &[&str], reply: Value) -> SessionEntry {
+    SessionEntry {
+        connection: 0,
+        time: Duration::from_millis(time),
+        request: command(request),
+        reply,
+    }
+}
+
+fn write(entries: &[SessionEntry]) -> Vec<u8> {
+    let mut writer = SessionWriter::new(Vec::new()).unwrap();
+
+    for entry in entries {
+        writer.write(entry).unwrap();
+    }
+
+    writer.into_inner()
+}
+
+fn read(bytes: &[u8]) -> Result<Vec<SessionEntry>> {
+    SessionReader::new(BufReader::new(bytes))?.collect()
+}
+
+/// Starts a mock server which counts with `INCR`, and switches protocol with `HELLO`.
+fn server() -> SocketAddr {
+    let server = MockServer::bind();
+    let addr = server.addr();
+
+    let counter = AtomicUsize::new(0);
+
+    server.serve(move |args| match args[0].as_str() {
+        "HELLO" => Value::Map(vec![(b_str("proto"), Value::int(3))]),
+        "INCR" => Value::int(counter.fetch_add(1, Ordering::SeqCst) as i64 + 1),
+        "GET" => Value::Null,
+        "TIME" => b_str("1700000000"),
+        _ => ok(),
+    });
+
+    addr
+}
+
+fn fast() -> ReplayOptions {
+    ReplayOptions {
+        speed: f64::INFINITY,
+        ..ReplayOptions::default()
+    }
+}
+
+fn path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("lib-resp-{}-{}.resp", name, std::process::id()))
+}
+
+mod test_format {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let entries = vec![
+            entry(0, &["SET", "foo", "bar"], ok()),
+            SessionEntry {
+                connection: 7,
+                time: Duration::from_micros(1_500_001),
+                request: command(&["HELLO", "3"]),
+                reply: Value::Map(vec![(b_str("proto"), Value::int(3))]),
+            },
+            entry(2000, &["GET", "missing"], Value::Null),
+            entry(2500, &["FOO"], Value::err("ERR unknown command 'FOO'")),
+        ];
+
+        assert_eq!(read(&write(&entries)).unwrap(), entries);
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(read(&write(&[])).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn not_a_session() {
+        assert!(SessionReader::new(BufReader::new(&b""[..])).is_err());
+        assert!(SessionReader::new(BufReader::new(&b"*1\r\n$3\r\nFOO\r\n"[..])).is_err());
+    }
+
+    #[test]
+    fn invalid_entry() {
+        let mut bytes = write(&[entry(0, &["PING"], Value::str("PONG"))]);
+
+        bytes.extend_from_slice(b"*2\r\n:1\r\n:2\r\n");
+
+        assert!(read(&bytes).is_err());
+    }
+}
+
+mod test_replay {
+    use super::*;
+
+    #[test]
+    fn matching() {
+        let session = vec![
+            entry(0, &["SET", "foo", "bar"], ok()),
+            entry(1, &["INCR", "n"], Value::int(1)),
+            entry(2, &["INCR", "n"], Value::int(2)),
+        ];
+
+        let report = replay(session.into_iter().map(Ok), server(), &fast()).unwrap();
+
+        assert_eq!(report.replayed, 3);
+        assert_eq!(report.mismatches, vec![]);
+    }
+
+    #[test]
+    fn mismatch() {
+        let session = vec![
+            entry(0, &["INCR", "n"], Value::int(1)),
+            entry(1, &["INCR", "n"], Value::int(5)),
+        ];
+
+        let report = replay(session.clone().into_iter().map(Ok), server(), &fast()).unwrap();
+
+        assert_eq!(report.mismatches.len(), 1);
+        assert_eq!(report.mismatches[0].index, 1);
+        assert_eq!(report.mismatches[0].entry, session[1]);
+        assert_eq!(report.mismatches[0].reply, Value::int(2));
+    }
+
+    #[test]
+    fn ignore() {
+        let session = vec![entry(0, &["time"], b_str("1600000000"))];
+
+        let options = ReplayOptions {
+            ignore: vec!["TIME".to_owned()],
+            ..fast()
+        };
+
+        let report = replay(session.into_iter().map(Ok), server(), &options).unwrap();
+
+        assert_eq!(report.replayed, 1);
+        assert_eq!(report.mismatches, vec![]);
+    }
+
+    #[test]
+    fn hello() {
+        let session = vec![
+            entry(
+                0,
+                &["HELLO", "3"],
+                Value::Map(vec![(b_str("proto"), Value::int(3))]),
+            ),
+            entry(1, &["GET", "missing"], Value::Null),
+        ];
+
+        let report = replay(session.into_iter().map(Ok), server(), &fast()).unwrap();
+
+        assert_eq!(report.mismatches, vec![]);
+    }
+
+    #[test]
+    fn speed() {
+        let session = vec![entry(0, &["PING"], ok()), entry(200, &["PING"], ok())];
+
+        let options = ReplayOptions {
+            speed: 2.0,
+            ..ReplayOptions::default()
+        };
+
+        let start = Instant::now();
+
+        replay(session.clone().into_iter().map(Ok), server(), &options).unwrap();
+
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        let start = Instant::now();
+
+        replay(session.into_iter().map(Ok), server(), &fast()).unwrap();
+
+        assert!(start.elapsed() < Duration::from_millis(100));
+    }
+
+    #[test]
+    fn bad_speed() {
+        for &speed in &[0.0, -1.0, f64::NAN, f64::NEG_INFINITY] {
+            let options = ReplayOptions {
+                speed,
+                ..ReplayOptions::default()
+            };
+
+            let session = vec![entry(0, &["PING"], ok()), entry(200, &["PING"], ok())];
+
+            let err = replay(session.into_iter().map(Ok), server(), &options).unwrap_err();
+
+            assert_eq!(err.kind(), ErrorKind::InvalidInput, "{}", speed);
+        }
+    }
+
+    #[test]
+    fn read_error() {
+        let session = vec![
+            Ok(entry(0, &["PING"], ok())),
+            Err(Error::new(ErrorKind::InvalidData, "boom")),
+        ];
+
+        assert!(replay(session, server(), &fast()).is_err());
+    }
+}
+
+mod test_resp_replay {
+    use super::*;
+
+    #[test]
+    fn replay() {
+        let path = path("replay");
+
+        let mut writer = SessionWriter::create(&path).unwrap();
+
+        writer
+            .write(&entry(0, &["INCR", "n"], Value::int(1)))
+            .unwrap();
+        writer
+            .write(&entry(1, &["INCR", "n"], Value::int(3)))
+            .unwrap();
+        writer.flush().unwrap();
+
+        let output = Command::new(env!("CARGO_BIN_EXE_resp-replay"))
+            .args(["--fast", path.to_str().unwrap(), &server().to_string()])
+            .output()
+            .unwrap();
+
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "#1 [\"INCR\",\"n\"]: expected 3, got 2\n"
+        );
+        assert_eq!(
+            String::from_utf8(output.stderr).unwrap(),
+            "resp-replay: 2 replayed, 1 differed\n"
+        );
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
+
+mod test_record {
+    use super::*;
+
+    /// Kills a child process when dropped, even if the test fails.
+    struct Killed(Child);
+
+    impl Drop for Killed {
+        fn drop(&mut self) {
+            let _ = self.0.kill();
+            let _ = self.0.wait();
+        }
+    }
+
+    /// Starts resp-proxy in front of `upstream`, recording to `path`, and connects to it.
+    fn start(path: &Path, upstream: SocketAddr, options: &[&str]) -> (Killed, Client) {
+        // Any free port will do
+        let listen = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+
+        let proxy = Command::new(env!("CARGO_BIN_EXE_resp-proxy"))
+            .args(options)
+            .args([
+                "--record",
+                path.to_str().unwrap(),
+                &listen.to_string(),
+                &upstream.to_string(),
+            ])
+            .stdout(Stdio::null())
+            .spawn()
+            .map(Killed)
+            .unwrap();
+
+        for _ in 0..100 {
+            match Client::connect(listen) {
+                Ok(client) => return (proxy, client),
+
+                Err(_) => thread::sleep(Duration::from_millis(10)),
+            }
+        }
+
+        panic!("resp-proxy didn't start");
+    }
+
+    /// Waits for `n` entries to be recorded, they're recorded after the reply is sent.
+    fn recorded(path: &Path, n: usize) -> Vec<SessionEntry> {
+        let mut entries = Vec::new();
+
+        for _ in 0..100 {
+            entries = read(&fs::read(path).unwrap()).unwrap();
+
+            if entries.len() >= n {
+                break;
+            }
+
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        entries
+    }
+
+    #[test]
+    fn resp_proxy() {
+        let path = path("record");
+
+        let (proxy, mut client) = start(&path, server(), &[]);
+
+        assert_eq!(client.command(&["INCR", "n"]).unwrap(), Value::int(1));
+        assert_eq!(client.command(&["SET", "foo", "bar"]).unwrap(), ok());
+
+        let entries = recorded(&path, 2);
+
+        drop(proxy);
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].request, command(&["INCR", "n"]));
+        assert_eq!(entries[1].reply, ok());
+        assert_eq!(entries[0].connection, entries[1].connection);
+        assert!(entries[0].time <= entries[1].time);
+
+        fs::remove_file(path).unwrap();
+    }
+
+    /// Blocked requests never reached the server, so they aren't replayed
+    #[test]
+    fn blocked() {
+        let path = path("blocked");
+
+        let (proxy, mut client) = start(&path, server(), &["--block", "FLUSHALL"]);
+
+        assert_eq!(client.command(&["INCR", "n"]).unwrap(), Value::int(1));
+        assert!(client
+            .command(&["FLUSHALL"])
+            .unwrap()
+            .server_error()
+            .is_some());
+        assert_eq!(client.command(&["INCR", "n"]).unwrap(), Value::int(2));
+
+        let entries = recorded(&path, 2);
+
+        drop(proxy);
+
+        let requests: Vec<_> = entries.iter().map(|entry| entry.request.clone()).collect();
+
+        assert_eq!(
+            requests,
+            vec![command(&["INCR", "n"]), command(&["INCR", "n"])]
+        );
+
+        let target = MockServer::bind();
+        let addr = target.addr();
+
+        let counter = AtomicUsize::new(0);
+
+        let log = target.serve(move |args| match args[0].as_str() {
+            "INCR" => Value::int(counter.fetch_add(1, Ordering::SeqCst) as i64 + 1),
+            _ => ok(),
+        });
+
+        let report = replay(entries.into_iter().map(Ok), addr, &fast()).unwrap();
+
+        assert_eq!(report.replayed, 2);
+        assert_eq!(report.mismatches, vec![]);
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![vec!["INCR", "n"], vec!["INCR", "n"]]
+        );
+
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,194 @@
+#![cfg(feature = "proptest")]
+
+extern crate lib_resp;
+extern crate proptest;
+
+use std::io::BufReader;
+
+use lib_resp::{Decoder, Parser, Protocol, Value, ValueKind, ValueParams};
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+/// How deeply aggregates are nested, `0` for scalars.
+fn depth(value: &Value) -> u32 {
+    children(value)
+        .iter()
+        .map(|child| depth(child) + 1)
+        .max()
+        .unwrap_or(0)
+}
+
+fn children(value: &Value) -> Vec<&Value> {
+    match *value {
+        Value::Array(Some(ref data)) | Value::Set(ref data) | Value::Push(ref data) => {
+            data.iter().collect()
+        }
+
+        Value::Map(ref pairs) => pairs.iter().flat_map(|(k, v)| vec![k, v]).collect(),
+
+        _ => vec![],
+    }
+}
+
+/// Checks `check` holds for `value` and everything in it.
+fn all(value: &Value, check: &dyn Fn(&Value) -> bool) -> bool {
+    check(value) && children(value).into_iter().all(|child| all(child, check))
+}
+
+mod test_round_trip {
+    use super::*;
+
+    /// Values small enough to decode a byte at a time.
+    fn small() -> ValueParams {
+        ValueParams {
+            depth: 2,
+            width: 4,
+            string_len: 8,
+            ..ValueParams::default()
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn resp2(value in any_with::<Value>(ValueParams::resp2())) {
+            let encoded = value.encode_bytes();
+
+            prop_assert_eq!(Parser::parse(&encoded), Ok((&b""[..], value)));
+        }
+
+        #[test]
+        fn resp3(value in any::<Value>()) {
+            let encoded = value.encode_bytes();
+
+            prop_assert_eq!(
+                Parser::parse_protocol(&encoded, Protocol::Resp3),
+                Ok((&b""[..], value))
+            );
+        }
+
+        #[test]
+        fn decoder(values in vec(any_with::<Value>(small()), 0..8), capacity in 1usize..64) {
+            let encoded: Vec<u8> = values.iter().flat_map(Value::encode_bytes).collect();
+
+            let mut decoder = Decoder::new(BufReader::with_capacity(capacity, &encoded[..]));
+
+            decoder.set_protocol(Protocol::Resp3);
+
+            for value in values {
+                prop_assert_eq!(decoder.decode().unwrap(), Some(value));
+            }
+
+            prop_assert_eq!(decoder.decode().unwrap(), None);
+        }
+
+        #[test]
+        fn vectored(value in any::<Value>()) {
+            let mut encoded = Vec::new();
+
+            value.encode_vectored().write_to(&mut encoded).unwrap();
+
+            prop_assert_eq!(encoded, value.encode_bytes());
+        }
+    }
+}
+
+mod test_params {
+    use super::*;
+
+    fn params(depth: u32, width: usize, string_len: usize) -> ValueParams {
+        ValueParams {
+            depth,
+            width,
+            string_len,
+            ..ValueParams::default()
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn depth_limit(value in any_with::<Value>(params(2, 4, 8))) {
+            prop_assert!(depth(&value) <= 2);
+        }
+
+        #[test]
+        fn scalars(value in any_with::<Value>(params(0, 4, 8))) {
+            prop_assert!(!value.kind().is_aggregate());
+        }
+
+        #[test]
+        fn width(value in any_with::<Value>(params(3, 2, 8))) {
+            let valid = all(&value, &|value| match *value {
+                Value::Map(ref pairs) => pairs.len() <= 2,
+
+                _ => children(value).len() <= 2,
+            });
+
+            prop_assert!(valid, "{:?}", value);
+        }
+
+        #[test]
+        fn string_len(value in any_with::<Value>(params(2, 4, 5))) {
+            let valid = all(&value, &|value| match *value {
+                Value::Str(ref datum)
+                | Value::Err(ref datum)
+                | Value::BStr(Some(ref datum))
+                | Value::BErr(ref datum)
+                | Value::Verbatim(_, ref datum) => datum.chars().count() <= 5,
+
+                // Plus the sign
+                Value::BigNum(ref datum) => datum.len() <= 6,
+
+                _ => true,
+            });
+
+            prop_assert!(valid, "{:?}", value);
+        }
+
+        #[test]
+        fn valid(value in any::<Value>()) {
+            prop_assert_eq!(value.validate(), Ok(()));
+        }
+
+        #[test]
+        fn lines(value in any::<Value>()) {
+            let valid = all(&value, &|value| match *value {
+                Value::Str(ref datum) | Value::Err(ref datum) => !datum.contains(['\r', '\n']),
+
+                _ => true,
+            });
+
+            prop_assert!(valid, "{:?}", value);
+        }
+
+        #[test]
+        fn kinds(value in any_with::<Value>(ValueParams {
+            kinds: vec![ValueKind::Map, ValueKind::Bool],
+            ..ValueParams::default()
+        })) {
+            let valid = all(&value, &|value| {
+                value.kind() == ValueKind::Map || value.kind() == ValueKind::Bool
+            });
+
+            prop_assert!(valid, "{:?}", value);
+        }
+
+        #[test]
+        fn only_aggregates(value in any_with::<Value>(ValueParams {
+            kinds: vec![ValueKind::Array, ValueKind::Set],
+            ..ValueParams::default()
+        })) {
+            let valid = all(&value, &|value| value.kind().is_aggregate());
+
+            prop_assert!(valid, "{:?}", value);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn no_kinds() {
+        let _ = any_with::<Value>(ValueParams {
+            kinds: vec![],
+            ..ValueParams::default()
+        });
+    }
+}
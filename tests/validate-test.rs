@@ -0,0 +1,132 @@
+extern crate lib_resp;
+
+use lib_resp::{Value, ValueErrorKind};
+
+mod test_constructors {
+    use super::*;
+
+    #[test]
+    fn try_str() {
+        assert_eq!(Value::try_str("OK"), Ok(Value::str("OK")));
+
+        for invalid in &["a\rb", "a\nb", "\r\n"] {
+            assert_eq!(
+                Value::try_str(invalid).unwrap_err().kind(),
+                ValueErrorKind::LineBreak
+            );
+        }
+    }
+
+    #[test]
+    fn try_err() {
+        assert_eq!(Value::try_err("ERR bad"), Ok(Value::err("ERR bad")));
+        assert!(Value::try_err("ERR\r\n+OK").is_err());
+    }
+
+    #[test]
+    fn lossy() {
+        assert_eq!(Value::str_lossy("a\r\nb\nc"), Value::str("a  b c"));
+        assert_eq!(Value::err_lossy("ERR\rbad"), Value::err("ERR bad"));
+    }
+
+    #[test]
+    fn escaped() {
+        assert_eq!(Value::str_escaped("a\r\nb"), Value::str(r"a\r\nb"));
+        assert_eq!(Value::err_escaped(r"ERR \n"), Value::err(r"ERR \\n"));
+        assert!(Value::err_escaped("\r\n\\").validate().is_ok());
+    }
+}
+
+mod test_validate {
+    use super::*;
+
+    #[test]
+    fn valid() {
+        let value = Value::Map(vec![(
+            Value::str("key"),
+            Value::array(Some(vec![
+                Value::BigNum("-123".to_owned()),
+                Value::Verbatim("txt".to_owned(), "a\r\nb".to_owned()),
+                Value::b_str(Some("a\r\nb")),
+                Value::BErr("ERR\r\n".to_owned()),
+            ])),
+        )]);
+
+        assert_eq!(value.validate(), Ok(()));
+    }
+
+    #[test]
+    fn big_num() {
+        for invalid in &["", "-", "1.5", "12a", "+1"] {
+            assert_eq!(
+                Value::BigNum(invalid.to_string())
+                    .validate()
+                    .unwrap_err()
+                    .kind(),
+                ValueErrorKind::InvalidBigNum
+            );
+        }
+    }
+
+    #[test]
+    fn verbatim_format() {
+        assert_eq!(
+            Value::Verbatim("text".to_owned(), "".to_owned())
+                .validate()
+                .unwrap_err()
+                .kind(),
+            ValueErrorKind::InvalidVerbatimFormat
+        );
+    }
+
+    #[test]
+    fn path() {
+        let value = Value::array(Some(vec![
+            Value::int(1),
+            Value::Map(vec![
+                (Value::str("a"), Value::int(1)),
+                (Value::str("b"), Value::Set(vec![Value::err("x\ny")])),
+            ]),
+        ]));
+
+        let error = value.validate().unwrap_err();
+
+        assert_eq!(error.path(), &[1, 3, 0]);
+        assert_eq!(
+            error.to_string(),
+            "Simple string or error contains a line break at [1, 3, 0]"
+        );
+
+        assert_eq!(
+            Value::str("\n").validate().unwrap_err().path(),
+            &[] as &[usize]
+        );
+    }
+
+    #[test]
+    fn deep() {
+        let mut value = Value::str("a\nb");
+
+        for _ in 0..100_000 {
+            value = Value::array(Some(vec![Value::int(1), value]));
+        }
+
+        assert_eq!(value.validate().unwrap_err().path(), &[1; 100_000][..]);
+
+        // Dropping a deep value recurses
+        while let Value::Array(Some(mut values)) = value {
+            value = values.pop().unwrap_or(Value::Null);
+        }
+    }
+
+    #[test]
+    fn try_encode() {
+        assert_eq!(Value::int(1).try_encode().unwrap(), ":1\r\n");
+        assert_eq!(Value::int(1).try_encode_bytes().unwrap(), b":1\r\n");
+
+        let value = Value::array(Some(vec![Value::str("a\r\n")]));
+
+        assert!(value.try_encode().is_err());
+        assert!(value.try_encode_bytes().is_err());
+    }
+}
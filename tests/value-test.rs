@@ -1,6 +1,12 @@
 extern crate lib_resp;
 
-use lib_resp::Value;
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::convert::TryFrom;
+
+use lib_resp::{FromValueError, Value};
 
 mod test_encode {
     use super::*;
@@ -63,6 +69,33 @@ mod test_encode {
             "*4\r\n:-3\r\n+OK\r\n-ERR\r\n$6\r\nfoobar\r\n"
         )
     }
+
+    #[test]
+    fn resp3() {
+        assert_eq!(Value::Null.encode(), "_\r\n");
+        assert_eq!(Value::Bool(true).encode(), "#t\r\n");
+        assert_eq!(Value::Bool(false).encode(), "#f\r\n");
+        assert_eq!(Value::Double(1.23).encode(), ",1.23\r\n");
+        assert_eq!(Value::Double(-10.0).encode(), ",-10\r\n");
+        assert_eq!(Value::Double(f64::INFINITY).encode(), ",inf\r\n");
+        assert_eq!(Value::Double(f64::NEG_INFINITY).encode(), ",-inf\r\n");
+        assert_eq!(Value::Double(f64::NAN).encode(), ",nan\r\n");
+        assert_eq!(Value::BigNum("-1".to_owned()).encode(), "(-1\r\n");
+        assert_eq!(
+            Value::BErr("SYNTAX invalid syntax".to_owned()).encode(),
+            "!21\r\nSYNTAX invalid syntax\r\n"
+        );
+        assert_eq!(
+            Value::Verbatim("txt".to_owned(), "Some string".to_owned()).encode(),
+            "=15\r\ntxt:Some string\r\n"
+        );
+        assert_eq!(
+            Value::Map(vec![(Value::str("a"), Value::int(1))]).encode(),
+            "%1\r\n+a\r\n:1\r\n"
+        );
+        assert_eq!(Value::Set(vec![Value::Null]).encode(), "~1\r\n_\r\n");
+        assert_eq!(Value::Push(Vec::new()).encode(), ">0\r\n");
+    }
 }
 
 mod test_is_null {
@@ -123,6 +156,14 @@ mod test_is_null {
 
         assert!(!value.is_null());
     }
+
+    #[test]
+    fn resp3() {
+        assert!(Value::Null.is_null());
+        assert!(!Value::Bool(false).is_null());
+        assert!(!Value::Map(Vec::new()).is_null());
+        assert!(!Value::Set(Vec::new()).is_null());
+    }
 }
 
 mod test_is_empty {
@@ -191,6 +232,18 @@ mod test_is_empty {
 
         assert!(!value.is_empty());
     }
+
+    #[test]
+    fn resp3() {
+        assert!(Value::Null.is_empty());
+        assert!(!Value::Bool(false).is_empty());
+        assert!(!Value::Double(0.0).is_empty());
+        assert!(Value::BErr(String::new()).is_empty());
+        assert!(Value::Verbatim("txt".to_owned(), String::new()).is_empty());
+        assert!(Value::Map(Vec::new()).is_empty());
+        assert!(!Value::Set(vec![Value::Null]).is_empty());
+        assert!(Value::Push(Vec::new()).is_empty());
+    }
 }
 
 mod test_clone {
@@ -322,4 +375,509 @@ mod test_dbg_fmt {
             "Array[4](Int(-3), Str(\"OK\"), Err(\"ERR\"), BStr(6, \"foobar\"))"
         );
     }
+
+    #[test]
+    fn resp3() {
+        assert_eq!(format!("{:?}", Value::Null), "Null");
+        assert_eq!(format!("{:?}", Value::Bool(true)), "Bool(true)");
+        assert_eq!(format!("{:?}", Value::Double(1.5)), "Double(1.5)");
+        assert_eq!(format!("{:?}", Value::BigNum("12".to_owned())), "BigNum(12)");
+        assert_eq!(format!("{:?}", Value::BErr("ERR".to_owned())), "BErr(\"ERR\")");
+        assert_eq!(
+            format!("{:?}", Value::Verbatim("txt".to_owned(), "foo".to_owned())),
+            "Verbatim(txt, \"foo\")"
+        );
+        assert_eq!(
+            format!(
+                "{:?}",
+                Value::Map(vec![
+                    (Value::str("a"), Value::int(1)),
+                    (Value::str("b"), Value::Null),
+                ])
+            ),
+            "Map[2](Str(\"a\") => Int(1), Str(\"b\") => Null)"
+        );
+        assert_eq!(
+            format!("{:?}", Value::Set(vec![Value::int(1), Value::int(2)])),
+            "Set[2](Int(1), Int(2))"
+        );
+        assert_eq!(format!("{:?}", Value::Push(Vec::new())), "Push[0]()");
+    }
+}
+
+mod test_eq {
+    use super::*;
+
+    #[test]
+    fn double() {
+        assert_eq!(Value::Double(1.5), Value::Double(1.5));
+        assert_ne!(Value::Double(1.5), Value::Double(2.5));
+
+        // Doubles compare by representation
+        assert_eq!(Value::Double(f64::NAN), Value::Double(f64::NAN));
+        assert_ne!(Value::Double(0.0), Value::Double(-0.0));
+    }
+
+    #[test]
+    fn variants() {
+        assert_ne!(Value::Null, Value::BStr(None));
+        assert_ne!(Value::Null, Value::Array(None));
+        assert_ne!(Value::Err("ERR".to_owned()), Value::BErr("ERR".to_owned()));
+        assert_ne!(Value::Set(Vec::new()), Value::Push(Vec::new()));
+        assert_ne!(
+            Value::Array(Some(vec![Value::int(1)])),
+            Value::Set(vec![Value::int(1)])
+        );
+    }
+}
+
+mod test_fmt {
+    use super::*;
+
+    #[test]
+    fn resp2() {
+        assert_eq!(Value::BStr(None).to_string(), "(nil)");
+        assert_eq!(Value::b_str(Some("")).to_string(), "\"\"");
+        assert_eq!(Value::Array(None).to_string(), "(nil)");
+        assert_eq!(Value::array(Some(vec![])).to_string(), "(empty array)");
+        assert_eq!(
+            Value::array(Some(vec![Value::int(1), Value::array(Some(vec![]))])).to_string(),
+            "1) (integer) 1\n2) (empty array)"
+        );
+    }
+
+    #[test]
+    fn resp3() {
+        assert_eq!(Value::Null.to_string(), "(nil)");
+        assert_eq!(Value::Bool(true).to_string(), "(true)");
+        assert_eq!(Value::Double(1.5).to_string(), "(double) 1.5");
+        assert_eq!(Value::BigNum("12".to_owned()).to_string(), "(big number) 12");
+        assert_eq!(Value::BErr("ERR".to_owned()).to_string(), "(error) ERR");
+        assert_eq!(
+            Value::Verbatim("txt".to_owned(), "foo".to_owned()).to_string(),
+            "foo"
+        );
+        assert_eq!(
+            Value::Map(vec![(Value::b_str(Some("a")), Value::int(1))]).to_string(),
+            "1# \"a\" => (integer) 1"
+        );
+        assert_eq!(
+            Value::Set(vec![Value::int(1), Value::int(2)]).to_string(),
+            "1~ (integer) 1\n2~ (integer) 2"
+        );
+    }
+}
+
+mod test_from {
+    use super::*;
+
+    #[test]
+    fn ints() {
+        assert_eq!(Value::from(-1i8), Value::int(-1));
+        assert_eq!(Value::from(2u16), Value::int(2));
+        assert_eq!(Value::from(3i32), Value::int(3));
+        assert_eq!(Value::from(4usize), Value::int(4));
+        assert_eq!(
+            Value::from(u64::max_value()),
+            Value::BigNum(u64::max_value().to_string())
+        );
+        assert_eq!(
+            Value::from(-1i128 << 64),
+            Value::BigNum("-18446744073709551616".to_owned())
+        );
+    }
+
+    #[test]
+    fn strings() {
+        assert_eq!(Value::from("a"), Value::b_str(Some("a")));
+        assert_eq!(Value::from("b".to_owned()), Value::b_str(Some("b")));
+        assert_eq!(Value::from(&b"c"[..]), Value::b_str(Some("c")));
+        assert_eq!(Value::from(b"d".to_vec()), Value::b_str(Some("d")));
+        assert_eq!(
+            Value::from(vec![0xff, b'e']),
+            Value::b_str(Some("\u{fffd}e"))
+        );
+    }
+
+    #[test]
+    fn options() {
+        assert_eq!(Value::from(Some("a")), Value::b_str(Some("a")));
+        assert_eq!(Value::from(None::<i64>), Value::BStr(None));
+    }
+
+    #[test]
+    fn aggregates() {
+        assert_eq!(Value::from(true), Value::Bool(true));
+        assert_eq!(
+            Value::from(vec![Some(1), None]),
+            Value::array(Some(vec![Value::int(1), Value::BStr(None)]))
+        );
+        assert_eq!(
+            Value::from(vec![vec!["a"]]),
+            Value::array(Some(vec![Value::array(Some(vec![Value::b_str(Some(
+                "a"
+            ))]))]))
+        );
+
+        let mut map = HashMap::new();
+
+        map.insert("a", 1);
+
+        assert_eq!(
+            Value::from(map.clone()),
+            Value::Map(vec![(Value::b_str(Some("a")), Value::int(1))])
+        );
+        assert_eq!(
+            Value::flat_map(map),
+            Value::array(Some(vec![Value::b_str(Some("a")), Value::int(1)]))
+        );
+    }
+
+    #[test]
+    fn flat_map() {
+        assert_eq!(
+            Value::flat_map(vec![("a", 1), ("b", 2)]),
+            Value::array(Some(vec![
+                Value::b_str(Some("a")),
+                Value::int(1),
+                Value::b_str(Some("b")),
+                Value::int(2),
+            ]))
+        );
+        assert_eq!(
+            Value::flat_map(Vec::<(i64, i64)>::new()),
+            Value::array(Some(Vec::new()))
+        );
+    }
+}
+
+mod test_try_from {
+    use super::*;
+
+    #[test]
+    fn ints() {
+        assert_eq!(i64::try_from(Value::int(-5)), Ok(-5));
+        assert_eq!(u16::try_from(Value::b_str(Some("42"))), Ok(42));
+        assert_eq!(
+            u64::try_from(Value::BigNum(u64::max_value().to_string())),
+            Ok(u64::max_value())
+        );
+        assert_eq!(i32::try_from(Value::str("7")), Ok(7));
+
+        assert!(u32::try_from(Value::int(-1)).is_err());
+        assert!(i8::try_from(Value::int(128)).is_err());
+        assert!(i64::try_from(Value::b_str(Some("x"))).is_err());
+        assert!(i64::try_from(Value::BStr(None)).is_err());
+    }
+
+    #[test]
+    fn strings() {
+        assert_eq!(String::try_from(Value::str("OK")), Ok("OK".to_owned()));
+        assert_eq!(
+            String::try_from(Value::b_str(Some("a"))),
+            Ok("a".to_owned())
+        );
+        assert_eq!(
+            String::try_from(Value::Verbatim("txt".to_owned(), "b".to_owned())),
+            Ok("b".to_owned())
+        );
+        assert_eq!(
+            Vec::<u8>::try_from(Value::b_str(Some("c"))),
+            Ok(b"c".to_vec())
+        );
+
+        assert!(String::try_from(Value::int(1)).is_err());
+        assert!(String::try_from(Value::err("ERR")).is_err());
+    }
+
+    #[test]
+    fn bools() {
+        assert_eq!(bool::try_from(Value::Bool(false)), Ok(false));
+        assert_eq!(bool::try_from(Value::int(1)), Ok(true));
+        assert_eq!(bool::try_from(Value::int(0)), Ok(false));
+
+        assert!(bool::try_from(Value::int(2)).is_err());
+    }
+
+    #[test]
+    fn options() {
+        assert_eq!(Option::<i64>::try_from(Value::int(1)), Ok(Some(1)));
+        assert_eq!(Option::<i64>::try_from(Value::BStr(None)), Ok(None));
+        assert_eq!(Option::<String>::try_from(Value::Array(None)), Ok(None));
+        assert_eq!(Option::<String>::try_from(Value::Null), Ok(None));
+
+        assert!(Option::<i64>::try_from(Value::str("x")).is_err());
+    }
+
+    #[test]
+    fn vecs() {
+        let value = Value::array(Some(vec![Value::b_str(Some("a")), Value::BStr(None)]));
+
+        assert_eq!(
+            Vec::<Option<String>>::try_from(value),
+            Ok(vec![Some("a".to_owned()), None])
+        );
+        assert_eq!(
+            Vec::<Value>::try_from(Value::Set(vec![Value::Null])),
+            Ok(vec![Value::Null])
+        );
+        assert_eq!(Vec::<i64>::try_from(Value::Push(vec![])), Ok(vec![]));
+
+        assert!(Vec::<i64>::try_from(Value::Array(None)).is_err());
+        assert!(Vec::<i64>::try_from(Value::int(1)).is_err());
+    }
+
+    #[test]
+    fn hash_maps() {
+        let mut expected = HashMap::new();
+
+        expected.insert("a".to_owned(), 1);
+        expected.insert("b".to_owned(), 2);
+
+        let map = Value::Map(vec![
+            (Value::str("a"), Value::int(1)),
+            (Value::str("b"), Value::int(2)),
+        ]);
+
+        assert_eq!(HashMap::try_from(map), Ok(expected.clone()));
+
+        // RESP2 replies with the keys and values in a flat array, e.g. HGETALL
+        let flat = Value::array(Some(vec![
+            Value::b_str(Some("a")),
+            Value::b_str(Some("1")),
+            Value::b_str(Some("b")),
+            Value::b_str(Some("2")),
+        ]));
+
+        assert_eq!(HashMap::try_from(flat), Ok(expected));
+
+        let odd = Value::array(Some(vec![Value::b_str(Some("a"))]));
+
+        assert!(HashMap::<String, String>::try_from(odd).is_err());
+    }
+
+    #[test]
+    fn errors() {
+        let error = i64::try_from(Value::err("ERR bad")).unwrap_err();
+
+        assert_eq!(error.value(), &Value::err("ERR bad"));
+        assert_eq!(error.target(), "i64");
+        assert_eq!(error.to_string(), "Can't convert error \"ERR bad\" to i64");
+
+        // Errors point at the item which couldn't be converted
+        let error: FromValueError =
+            Vec::<i64>::try_from(Value::array(Some(vec![Value::int(1), Value::Null]))).unwrap_err();
+
+        assert_eq!(error.into_value(), Value::Null);
+        assert_eq!(
+            String::try_from(Value::int(1)).unwrap_err().to_string(),
+            "Can't convert Int to String"
+        );
+    }
+}
+
+mod test_hash {
+    use super::*;
+
+    fn hash(value: &Value) -> u64 {
+        let mut hasher = DefaultHasher::new();
+
+        value.hash(&mut hasher);
+
+        hasher.finish()
+    }
+
+    #[test]
+    fn agrees_with_eq() {
+        assert_eq!(
+            hash(&Value::array(Some(vec![Value::int(1), Value::Null]))),
+            hash(&Value::array(Some(vec![Value::int(1), Value::Null])))
+        );
+        assert_eq!(
+            hash(&Value::Double(f64::NAN)),
+            hash(&Value::Double(f64::NAN))
+        );
+    }
+
+    #[test]
+    fn keys() {
+        let mut set = HashSet::new();
+
+        set.insert(Value::str("a"));
+        set.insert(Value::b_str(Some("a")));
+        set.insert(Value::Set(vec![Value::int(1)]));
+        set.insert(Value::Double(0.0));
+        set.insert(Value::Double(-0.0));
+        set.insert(Value::str("a"));
+
+        assert_eq!(set.len(), 5);
+        assert!(set.contains(&Value::Set(vec![Value::int(1)])));
+        assert!(!set.contains(&Value::Push(vec![Value::int(1)])));
+    }
+}
+
+mod test_ord {
+    use super::*;
+
+    #[test]
+    fn kinds() {
+        let values = vec![
+            Value::Push(vec![]),
+            Value::Null,
+            Value::int(2),
+            Value::str("a"),
+            Value::Array(None),
+            Value::b_str(Some("a")),
+        ];
+
+        let sorted: Vec<Value> = values
+            .into_iter()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        assert_eq!(
+            sorted,
+            vec![
+                Value::int(2),
+                Value::str("a"),
+                Value::b_str(Some("a")),
+                Value::Array(None),
+                Value::Null,
+                Value::Push(vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn contents() {
+        assert!(Value::int(-1) < Value::int(1));
+        assert!(Value::str("a") < Value::str("b"));
+        assert!(Value::BStr(None) < Value::b_str(Some("")));
+        assert!(Value::Array(None) < Value::array(Some(vec![])));
+        assert!(Value::array(Some(vec![Value::int(1)])) < Value::array(Some(vec![Value::int(2)])));
+        assert!(Value::Bool(false) < Value::Bool(true));
+        assert!(
+            Value::Verbatim("mkd".to_owned(), "b".to_owned())
+                < Value::Verbatim("txt".to_owned(), "a".to_owned())
+        );
+        assert!(
+            Value::Map(vec![(Value::int(1), Value::int(2))])
+                < Value::Map(vec![(Value::int(1), Value::int(3))])
+        );
+    }
+
+    #[test]
+    fn doubles() {
+        let (nan, infinity) = (f64::NAN, f64::INFINITY);
+
+        let mut values: Vec<Value> = vec![nan, 1.0, -0.0, infinity, -nan, 0.0, -infinity]
+            .into_iter()
+            .map(Value::Double)
+            .collect();
+
+        values.sort();
+
+        let sorted: Vec<u64> = values
+            .iter()
+            .map(|value| match *value {
+                Value::Double(datum) => datum.to_bits(),
+
+                _ => unreachable!(),
+            })
+            .collect();
+
+        let expected: Vec<u64> = vec![-nan, -infinity, -0.0, 0.0, 1.0, infinity, nan]
+            .into_iter()
+            .map(f64::to_bits)
+            .collect();
+
+        assert_eq!(sorted, expected);
+
+        assert_eq!(Value::Double(nan).cmp(&Value::Double(nan)), Ordering::Equal);
+    }
+
+    #[test]
+    fn agrees_with_eq() {
+        let values = vec![
+            Value::int(1),
+            Value::Double(0.0),
+            Value::Double(-0.0),
+            Value::Double(f64::NAN),
+            Value::BStr(None),
+            Value::Array(None),
+            Value::Null,
+            Value::Map(vec![(Value::Null, Value::int(1))]),
+        ];
+
+        for a in &values {
+            for b in &values {
+                assert_eq!(a.cmp(b) == Ordering::Equal, a == b, "{:?} {:?}", a, b);
+            }
+        }
+    }
+}
+
+mod test_canonicalize {
+    use super::*;
+
+    #[test]
+    fn sets_and_maps() {
+        let mut value = Value::array(Some(vec![
+            Value::int(2),
+            Value::int(1),
+            Value::Set(vec![
+                Value::b_str(Some("b")),
+                Value::Set(vec![Value::int(2), Value::int(1)]),
+                Value::b_str(Some("a")),
+            ]),
+            Value::Map(vec![
+                (
+                    Value::b_str(Some("y")),
+                    Value::Set(vec![Value::int(9), Value::int(8)]),
+                ),
+                (Value::b_str(Some("x")), Value::int(0)),
+            ]),
+        ]));
+
+        value.canonicalize();
+
+        assert_eq!(
+            value,
+            Value::array(Some(vec![
+                Value::int(2),
+                Value::int(1),
+                Value::Set(vec![
+                    Value::b_str(Some("a")),
+                    Value::b_str(Some("b")),
+                    Value::Set(vec![Value::int(1), Value::int(2)]),
+                ]),
+                Value::Map(vec![
+                    (Value::b_str(Some("x")), Value::int(0)),
+                    (
+                        Value::b_str(Some("y")),
+                        Value::Set(vec![Value::int(8), Value::int(9)])
+                    ),
+                ]),
+            ]))
+        );
+    }
+
+    #[test]
+    fn nested_order() {
+        // The items are canonicalized before they're sorted
+        let mut a = Value::Set(vec![
+            Value::Set(vec![Value::int(3), Value::int(1)]),
+            Value::Set(vec![Value::int(2)]),
+        ]);
+        let mut b = Value::Set(vec![
+            Value::Set(vec![Value::int(2)]),
+            Value::Set(vec![Value::int(1), Value::int(3)]),
+        ]);
+
+        a.canonicalize();
+        b.canonicalize();
+
+        assert_eq!(a, b);
+    }
 }
@@ -0,0 +1,234 @@
+#[macro_use]
+extern crate lib_resp;
+
+use std::io::{self, IoSlice, Write};
+
+use lib_resp::Value;
+
+/// Accepts at most `max` bytes per write, failing every other call with `error`.
+struct Trickle {
+    written: Vec<u8>,
+    max: usize,
+    error: Option<io::ErrorKind>,
+    calls: usize,
+}
+
+impl Trickle {
+    fn new(max: usize, error: Option<io::ErrorKind>) -> Self {
+        Trickle {
+            written: Vec::new(),
+            max,
+            error,
+            calls: 0,
+        }
+    }
+}
+
+impl Write for Trickle {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_vectored(&[IoSlice::new(buf)])
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice]) -> io::Result<usize> {
+        self.calls += 1;
+
+        if let Some(kind) = self.error {
+            if self.calls % 2 == 1 {
+                return Err(io::Error::new(kind, "trickle"));
+            }
+        }
+
+        let mut n = 0;
+
+        for buf in bufs {
+            let take = buf.len().min(self.max - n);
+
+            self.written.extend_from_slice(&buf[..take]);
+
+            n += take;
+
+            if n == self.max {
+                break;
+            }
+        }
+
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn values() -> Vec<Value> {
+    vec![
+        Value::int(-1),
+        Value::str("OK"),
+        Value::err("ERR"),
+        Value::BStr(None),
+        Value::b_str(Some("")),
+        Value::b_str(Some("x".repeat(1000))),
+        Value::Array(None),
+        resp![],
+        resp![1, ["a", nil], "b".repeat(100)],
+        Value::Null,
+        Value::Bool(true),
+        Value::Double(1.5),
+        Value::BigNum("12345678901234567890".to_owned()),
+        Value::BErr("E".repeat(100)),
+        Value::Verbatim("txt".to_owned(), "v".repeat(100)),
+        resp! { "a" => 1, "b" => "c".repeat(100) },
+        Value::Set(vec![Value::str("d".repeat(100))]),
+        Value::Push(vec![Value::b_str(Some("message")), Value::int(2)]),
+    ]
+}
+
+mod test_slices {
+    use super::*;
+
+    #[test]
+    fn encoding() {
+        for value in values() {
+            let encoder = value.encode_vectored();
+
+            let encoded: Vec<u8> = encoder
+                .slices()
+                .iter()
+                .flat_map(|slice| slice.to_vec())
+                .collect();
+
+            assert_eq!(encoded, value.encode_bytes(), "{:?}", value);
+            assert_eq!(encoder.remaining(), encoded.len());
+        }
+    }
+
+    #[test]
+    fn borrowed() {
+        let payload = "x".repeat(1 << 20);
+        let value = resp![+"OK", &payload[..]];
+
+        let encoder = value.encode_vectored();
+        let slices = encoder.slices();
+
+        assert_eq!(slices.len(), 3);
+        assert_eq!(&*slices[0], &b"*2\r\n+OK\r\n$1048576\r\n"[..]);
+        assert_eq!(&*slices[2], &b"\r\n"[..]);
+
+        // The payload isn't copied
+        match value[1] {
+            Value::BStr(Some(ref datum)) => assert_eq!(slices[1].as_ptr(), datum.as_ptr()),
+
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn small() {
+        // Small payloads are copied along with the headers
+        let value = resp!["a", "b", ["c"]];
+
+        assert_eq!(value.encode_vectored().slices().len(), 1);
+    }
+
+    #[test]
+    fn advance() {
+        let value = resp![1, "x".repeat(100)];
+
+        let mut encoder = value.encode_vectored();
+
+        encoder.advance(5);
+
+        assert_eq!(encoder.written(), 5);
+        assert_eq!(encoder.slices().len(), 3);
+        assert_eq!(&*encoder.slices()[0], &b"1\r\n$100\r\n"[..]);
+
+        // Into the payload
+        encoder.advance(20);
+
+        assert_eq!(encoder.slices().len(), 2);
+        assert_eq!(encoder.slices()[0].len(), 89);
+
+        encoder.advance(91);
+
+        assert!(encoder.is_done());
+        assert!(encoder.slices().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "advancing past the end of the encoded value")]
+    fn advance_past_end() {
+        Value::int(1).encode_vectored().advance(5);
+    }
+}
+
+mod test_write_to {
+    use super::*;
+
+    #[test]
+    fn partial() {
+        for max in 1..8 {
+            for value in values() {
+                let mut dst = Trickle::new(max, None);
+
+                value.encode_vectored().write_to(&mut dst).unwrap();
+
+                assert_eq!(dst.written, value.encode_bytes());
+            }
+        }
+    }
+
+    /// Writes resume from the part they stopped in, rather than from the start of the value
+    #[test]
+    fn many_parts() {
+        let value = Value::Array(Some(vec![Value::b_str(Some("x".repeat(100))); 20_000]));
+
+        let mut dst = Trickle::new(7, None);
+
+        value.encode_vectored().write_to(&mut dst).unwrap();
+
+        assert_eq!(dst.written, value.encode_bytes());
+    }
+
+    #[test]
+    fn interrupted() {
+        let value = resp![1, "x".repeat(100)];
+
+        let mut dst = Trickle::new(16, Some(io::ErrorKind::Interrupted));
+
+        value.encode_vectored().write_to(&mut dst).unwrap();
+
+        assert_eq!(dst.written, value.encode_bytes());
+    }
+
+    #[test]
+    fn would_block() {
+        let value = resp![1, "x".repeat(100)];
+
+        let mut encoder = value.encode_vectored();
+        let mut dst = Trickle::new(64, Some(io::ErrorKind::WouldBlock));
+
+        let mut attempts = 0;
+
+        // Each attempt writes a chunk before blocking, and the next resumes after it
+        while let Err(e) = encoder.write_to(&mut dst) {
+            assert_eq!(e.kind(), io::ErrorKind::WouldBlock);
+
+            attempts += 1;
+        }
+
+        assert_eq!(attempts, 2);
+        assert_eq!(dst.written, value.encode_bytes());
+    }
+
+    #[test]
+    fn write_zero() {
+        let mut dst = Trickle::new(0, None);
+
+        let error = Value::int(1)
+            .encode_vectored()
+            .write_to(&mut dst)
+            .unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::WriteZero);
+    }
+}
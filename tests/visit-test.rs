@@ -0,0 +1,316 @@
+extern crate lib_resp;
+
+use lib_resp::{Value, Visitor};
+
+/// Records the callbacks it receives.
+#[derive(Default)]
+struct Recorder {
+    events: Vec<String>,
+}
+
+impl Visitor for Recorder {
+    fn visit_int(&mut self, datum: i64) {
+        self.events.push(format!("int {}", datum));
+    }
+
+    fn visit_str(&mut self, datum: &str) {
+        self.events.push(format!("str {}", datum));
+    }
+
+    fn visit_err(&mut self, error: &str) {
+        self.events.push(format!("err {}", error));
+    }
+
+    fn visit_b_str(&mut self, datum: Option<&str>) {
+        self.events.push(format!("b_str {:?}", datum));
+    }
+
+    fn visit_null(&mut self) {
+        self.events.push("null".to_owned());
+    }
+
+    fn visit_bool(&mut self, datum: bool) {
+        self.events.push(format!("bool {}", datum));
+    }
+
+    fn visit_double(&mut self, datum: f64) {
+        self.events.push(format!("double {}", datum));
+    }
+
+    fn visit_big_num(&mut self, datum: &str) {
+        self.events.push(format!("big_num {}", datum));
+    }
+
+    fn visit_b_err(&mut self, error: &str) {
+        self.events.push(format!("b_err {}", error));
+    }
+
+    fn visit_verbatim(&mut self, format: &str, datum: &str) {
+        self.events.push(format!("verbatim {}:{}", format, datum));
+    }
+
+    fn enter_array(&mut self, len: Option<usize>) {
+        self.events.push(format!("enter_array {:?}", len));
+    }
+
+    fn leave_array(&mut self) {
+        self.events.push("leave_array".to_owned());
+    }
+
+    fn enter_map(&mut self, len: usize) {
+        self.events.push(format!("enter_map {}", len));
+    }
+
+    fn leave_map(&mut self) {
+        self.events.push("leave_map".to_owned());
+    }
+
+    fn enter_set(&mut self, len: usize) {
+        self.events.push(format!("enter_set {}", len));
+    }
+
+    fn leave_set(&mut self) {
+        self.events.push("leave_set".to_owned());
+    }
+
+    fn enter_push(&mut self, len: usize) {
+        self.events.push(format!("enter_push {}", len));
+    }
+
+    fn leave_push(&mut self) {
+        self.events.push("leave_push".to_owned());
+    }
+}
+
+/// Nests an integer in `depth` arrays, without recursing.
+fn nested(depth: usize) -> Value {
+    let mut value = Value::int(1);
+
+    for _ in 0..depth {
+        value = Value::array(Some(vec![value]));
+    }
+
+    value
+}
+
+/// Drops a value made by `nested`, without recursing.
+fn unnest(mut value: Value) {
+    while let Value::Array(Some(mut values)) = value {
+        value = values.pop().unwrap_or(Value::Null);
+    }
+}
+
+mod test_walk {
+    use super::*;
+
+    #[test]
+    fn order() {
+        let value = Value::array(Some(vec![
+            Value::int(1),
+            Value::str("OK"),
+            Value::err("ERR"),
+            Value::BStr(None),
+            Value::b_str(Some("foo")),
+            Value::Array(None),
+            Value::Map(vec![
+                (Value::Null, Value::Bool(true)),
+                (Value::Double(1.5), Value::BigNum("12".to_owned())),
+            ]),
+            Value::Set(vec![Value::BErr("ERR".to_owned())]),
+            Value::Push(vec![Value::Verbatim("txt".to_owned(), "bar".to_owned())]),
+        ]));
+
+        let mut recorder = Recorder::default();
+
+        value.walk(&mut recorder);
+
+        assert_eq!(
+            recorder.events,
+            vec![
+                "enter_array Some(9)",
+                "int 1",
+                "str OK",
+                "err ERR",
+                "b_str None",
+                "b_str Some(\"foo\")",
+                "enter_array None",
+                "leave_array",
+                "enter_map 2",
+                "null",
+                "bool true",
+                "double 1.5",
+                "big_num 12",
+                "leave_map",
+                "enter_set 1",
+                "b_err ERR",
+                "leave_set",
+                "enter_push 1",
+                "verbatim txt:bar",
+                "leave_push",
+                "leave_array",
+            ]
+        );
+    }
+
+    #[test]
+    fn defaults() {
+        struct Ints(i64);
+
+        impl Visitor for Ints {
+            fn visit_int(&mut self, datum: i64) {
+                self.0 += datum;
+            }
+        }
+
+        let value = Value::array(Some(vec![
+            Value::int(1),
+            Value::Map(vec![(Value::str("a"), Value::int(2))]),
+        ]));
+
+        let mut ints = Ints(0);
+
+        value.walk(&mut ints);
+
+        assert_eq!(ints.0, 3);
+    }
+
+    #[test]
+    fn deep() {
+        let value = nested(100_000);
+
+        let mut recorder = Recorder::default();
+
+        value.walk(&mut recorder);
+
+        assert_eq!(recorder.events.len(), 200_001);
+        assert_eq!(recorder.events[100_000], "int 1");
+
+        unnest(value);
+    }
+}
+
+mod test_walk_mut {
+    use super::*;
+
+    #[test]
+    fn redact() {
+        let mut value = Value::array(Some(vec![
+            Value::b_str(Some("secret")),
+            Value::Map(vec![(
+                Value::b_str(Some("secret")),
+                Value::Set(vec![Value::b_str(Some("secret")), Value::str("secret")]),
+            )]),
+        ]));
+
+        value.walk_mut(|value| {
+            if let Value::BStr(Some(ref mut datum)) = *value {
+                if datum == "secret" {
+                    *datum = "***".to_owned();
+                }
+            }
+        });
+
+        assert_eq!(
+            value,
+            Value::array(Some(vec![
+                Value::b_str(Some("***")),
+                Value::Map(vec![(
+                    Value::b_str(Some("***")),
+                    Value::Set(vec![Value::b_str(Some("***")), Value::str("secret")]),
+                )]),
+            ]))
+        );
+    }
+
+    #[test]
+    fn replace() {
+        let mut value = Value::array(Some(vec![Value::int(1), Value::int(2)]));
+
+        // The items of the replacement are walked
+        value.walk_mut(|value| match *value {
+            Value::Array(_) => *value = Value::Set(vec![Value::int(3)]),
+
+            Value::Int(ref mut datum) => *datum += 1,
+
+            _ => {}
+        });
+
+        assert_eq!(value, Value::Set(vec![Value::int(4)]));
+    }
+
+    #[test]
+    fn deep() {
+        let mut value = nested(100_000);
+
+        let mut count = 0;
+
+        value.walk_mut(|_| count += 1);
+
+        assert_eq!(count, 100_001);
+
+        unnest(value);
+    }
+}
+
+mod test_map_leaves {
+    use super::*;
+
+    #[test]
+    fn leaves() {
+        let value = Value::array(Some(vec![
+            Value::int(1),
+            Value::Array(None),
+            Value::array(Some(vec![])),
+            Value::Map(vec![(Value::str("a"), Value::BStr(None))]),
+        ]));
+
+        let mut leaves = Vec::new();
+
+        let value = value.map_leaves(|leaf| {
+            leaves.push(leaf.clone());
+
+            Value::Null
+        });
+
+        // Null arrays are leaves, but empty arrays aren't
+        assert_eq!(
+            leaves,
+            vec![
+                Value::int(1),
+                Value::Array(None),
+                Value::str("a"),
+                Value::BStr(None),
+            ]
+        );
+        assert_eq!(
+            value,
+            Value::array(Some(vec![
+                Value::Null,
+                Value::Null,
+                Value::array(Some(vec![])),
+                Value::Map(vec![(Value::Null, Value::Null)]),
+            ]))
+        );
+    }
+
+    #[test]
+    fn no_rewalk() {
+        let value = Value::array(Some(vec![Value::int(1)]));
+
+        let value = value.map_leaves(|leaf| Value::array(Some(vec![leaf])));
+
+        assert_eq!(
+            value,
+            Value::array(Some(vec![Value::array(Some(vec![Value::int(1)]))]))
+        );
+    }
+
+    #[test]
+    fn deep() {
+        let value = nested(100_000).map_leaves(|_| Value::int(2));
+
+        assert_eq!(value.pointer(&"/0".repeat(100_000)), Some(&Value::int(2)));
+
+        unnest(value);
+    }
+}